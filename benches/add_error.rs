@@ -1,12 +1,6 @@
 /* crate use */
 use rand::SeedableRng;
 
-/* project use */
-use rustyread;
-
-/* criterion use */
-use criterion::criterion_group;
-
 fn add_error_worker(
     target: f64,
     seq: &[u8],
@@ -14,14 +8,7 @@ fn add_error_worker(
 ) -> rustyread::simulate::error::Changes {
     let mut changes = rustyread::simulate::error::Changes::new();
     let mut local_rng = rand::rngs::SmallRng::from_entropy();
-    rustyread::simulate::error::add_error(
-        7,
-        target as f64,
-        &seq,
-        &mut changes,
-        &model,
-        &mut local_rng,
-    );
+    rustyread::simulate::error::add_error(7, target, seq, &mut changes, model, &mut local_rng);
 
     changes
 }
@@ -0,0 +1,380 @@
+//! Amplicon sequencing, reads are whole amplicons between primers of a primer scheme
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::{Context, Result};
+use rand::distributions::Distribution;
+
+/* local use */
+use crate::error::Amplicon as Error;
+use crate::references::References;
+
+/// An amplicon, region of reference between a left and a right primer, alternative primers are keep
+#[derive(Debug, Clone, PartialEq)]
+pub struct Amplicon {
+    pub id: String,
+    pub ref_id: String,
+    pub pool: String,
+    pub lefts: Vec<(usize, usize)>,
+    pub rights: Vec<(usize, usize)>,
+    pub efficiency: f64,
+}
+
+/// Amplicons of a primer scheme
+pub struct Scheme {
+    amplicons: Vec<Amplicon>,
+}
+
+impl Scheme {
+    /// Read a primer scheme in bed format, columns are reference, start, end, primer name and pool
+    ///
+    /// Primer name is amplicon id follow by _LEFT or _RIGHT and an optional alt suffix
+    pub fn from_stream<R>(input: R) -> Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let mut amplicons: Vec<Amplicon> = Vec::new();
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .quoting(false)
+            .comment(Some(b'#'))
+            .from_reader(input);
+
+        for record in reader.records() {
+            let record = record?;
+            if record.len() < 5 {
+                anyhow::bail!(Error::SchemeParsing);
+            }
+
+            let start = usize::from_str(&record[1]).with_context(|| Error::SchemeParsing)?;
+            let end = usize::from_str(&record[2]).with_context(|| Error::SchemeParsing)?;
+            if end <= start {
+                anyhow::bail!(Error::SchemeParsing);
+            }
+
+            let (id, left) = match (record[3].rfind("_LEFT"), record[3].rfind("_RIGHT")) {
+                (Some(pos), None) => (&record[3][..pos], true),
+                (None, Some(pos)) => (&record[3][..pos], false),
+                _ => anyhow::bail!(Error::SchemeParsing),
+            };
+
+            let index = match amplicons.iter().position(|x| x.id == id) {
+                Some(index) => index,
+                None => {
+                    amplicons.push(Amplicon {
+                        id: id.to_string(),
+                        ref_id: record[0].to_string(),
+                        pool: record[4].to_string(),
+                        lefts: Vec::new(),
+                        rights: Vec::new(),
+                        efficiency: 1.0,
+                    });
+                    amplicons.len() - 1
+                }
+            };
+
+            if left {
+                amplicons[index].lefts.push((start, end));
+            } else {
+                amplicons[index].rights.push((start, end));
+            }
+        }
+
+        if amplicons.is_empty()
+            || amplicons.iter().any(|x| {
+                x.lefts.is_empty()
+                    || x.rights.is_empty()
+                    || x.lefts.iter().map(|l| l.0).min() >= x.rights.iter().map(|r| r.1).max()
+            })
+        {
+            anyhow::bail!(Error::SchemeParsing);
+        }
+
+        Ok(Self { amplicons })
+    }
+
+    /// Amplicons of scheme
+    pub fn amplicons(&self) -> &[Amplicon] {
+        &self.amplicons
+    }
+
+    /// Set efficiency of each amplicon
+    ///
+    /// Efficiency is weight of pool, balance is percentage of pool A and pool B (pools are sort by name), multiply by a log normal noise with variability as standard deviation, dropout is percentage of amplicons without any read
+    pub fn set_efficiency<RNG>(
+        &mut self,
+        variability: f64,
+        dropout: f64,
+        balance: (f64, f64),
+        rng: &mut RNG,
+    ) -> Result<()>
+    where
+        RNG: rand::Rng,
+    {
+        if variability < 0.0 || !(0.0..=100.0).contains(&dropout) {
+            anyhow::bail!(Error::ParamOutOfRange);
+        }
+
+        let mut pools: Vec<&str> = self.amplicons.iter().map(|x| x.pool.as_str()).collect();
+        pools.sort_unstable();
+        pools.dedup();
+        if pools.len() > 2 {
+            anyhow::bail!(Error::TooManyPools);
+        }
+        let pools: Vec<String> = pools.into_iter().map(|x| x.to_string()).collect();
+
+        let noise = if variability != 0.0 {
+            Some(rand_distr::LogNormal::new(0.0, variability)?)
+        } else {
+            None
+        };
+
+        for amplicon in self.amplicons.iter_mut() {
+            let pool = if amplicon.pool == pools[0] {
+                balance.0
+            } else {
+                balance.1
+            };
+
+            amplicon.efficiency = if rng.gen_bool(dropout / 100.0) {
+                0.0
+            } else {
+                pool * noise.map(|x| x.sample(rng)).unwrap_or(1.0)
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Find amplicons in references, primers of haplotypes are lifted in haplotype coordinate and weight of an amplicon is split between references with his id
+    pub fn targets(&self, references: &References) -> Result<Targets<'_>> {
+        let mut targets = Vec::new();
+        let mut weights = Vec::new();
+
+        for (index, amplicon) in self.amplicons.iter().enumerate() {
+            let matched: Vec<usize> = references
+                .sequences
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| x.id == amplicon.ref_id)
+                .map(|(i, _)| i)
+                .collect();
+            let total = matched.iter().map(|x| references.weights[*x]).sum::<f64>();
+
+            for ref_index in matched {
+                let reference = &references.sequences[ref_index];
+                let lift = |pos: usize| match &reference.haplotype {
+                    Some(haplotype) => haplotype.liftover.unlift(pos),
+                    None => Some(pos).filter(|x| *x <= reference.seq.len()),
+                };
+
+                let starts: Vec<usize> = amplicon.lefts.iter().filter_map(|x| lift(x.0)).collect();
+                let ends: Vec<usize> = amplicon.rights.iter().filter_map(|x| lift(x.1)).collect();
+                if starts.is_empty() || ends.is_empty() || starts.iter().min() >= ends.iter().max()
+                {
+                    continue;
+                }
+
+                targets.push(Target {
+                    amplicon: index,
+                    ref_index,
+                    starts,
+                    ends,
+                });
+                weights.push(amplicon.efficiency * references.weights[ref_index] / total);
+            }
+        }
+
+        let dist =
+            rand::distributions::WeightedIndex::new(&weights).with_context(|| Error::NoAmplicon)?;
+
+        Ok(Targets {
+            scheme: self,
+            targets,
+            dist,
+        })
+    }
+
+    /// Write amplicon id, reference, pool, efficiency, number of reads and bases of each amplicon
+    pub fn write_truth<W>(
+        &self,
+        output: &mut W,
+        simulated: &rustc_hash::FxHashMap<String, (u64, u64)>,
+    ) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        writeln!(
+            output,
+            "#amplicon\treference\tpool\tefficiency\treads\tbases"
+        )?;
+        for amplicon in self.amplicons.iter() {
+            let (reads, bases) = simulated.get(&amplicon.id).cloned().unwrap_or((0, 0));
+            writeln!(
+                output,
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                amplicon.id, amplicon.ref_id, amplicon.pool, amplicon.efficiency, reads, bases
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An amplicon in a reference, start of left primers and end of right primers in reference coordinate
+struct Target {
+    amplicon: usize,
+    ref_index: usize,
+    starts: Vec<usize>,
+    ends: Vec<usize>,
+}
+
+/// Amplicons found in references
+pub struct Targets<'a> {
+    scheme: &'a Scheme,
+    targets: Vec<Target>,
+    dist: rand::distributions::WeightedIndex<f64>,
+}
+
+impl<'a> Targets<'a> {
+    /// Choose an amplicon according to efficiency and a left and right primer, return reference index, start, end and amplicon id
+    pub fn choose<RNG>(&self, rng: &mut RNG) -> (usize, usize, usize, &str)
+    where
+        RNG: rand::Rng,
+    {
+        let target = &self.targets[self.dist.sample(rng)];
+
+        let start = target.starts[rng.gen_range(0..target.starts.len())];
+        let end = target.ends[rng.gen_range(0..target.ends.len())];
+
+        (
+            target.ref_index,
+            start,
+            end.max(start + 1),
+            &self.scheme.amplicons[target.amplicon].id,
+        )
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    static SCHEME: &[u8] = b"chr1\t0\t4\tscheme_1_LEFT\tscheme_1\t+
+chr1\t20\t24\tscheme_1_RIGHT\tscheme_1\t-
+chr1\t16\t20\tscheme_2_LEFT\tscheme_2\t+
+chr1\t36\t40\tscheme_2_RIGHT\tscheme_2\t-
+chr1\t17\t21\tscheme_2_LEFT_alt1\tscheme_2\t+
+";
+
+    #[test]
+    fn parse() {
+        let scheme = Scheme::from_stream(SCHEME).unwrap();
+
+        assert_eq!(
+            scheme.amplicons(),
+            &[
+                Amplicon {
+                    id: "scheme_1".to_string(),
+                    ref_id: "chr1".to_string(),
+                    pool: "scheme_1".to_string(),
+                    lefts: vec![(0, 4)],
+                    rights: vec![(20, 24)],
+                    efficiency: 1.0,
+                },
+                Amplicon {
+                    id: "scheme_2".to_string(),
+                    ref_id: "chr1".to_string(),
+                    pool: "scheme_2".to_string(),
+                    lefts: vec![(16, 20), (17, 21)],
+                    rights: vec![(36, 40)],
+                    efficiency: 1.0,
+                }
+            ]
+        );
+
+        assert!(Scheme::from_stream(&b"chr1\t0\t4\tscheme_1_LEFT\t1\n"[..]).is_err());
+        assert!(Scheme::from_stream(&b"chr1\t0\t4\tscheme_1\t1\n"[..]).is_err());
+        assert!(Scheme::from_stream(&b"chr1\t0\t4\tscheme_1_LEFT\n"[..]).is_err());
+        assert!(Scheme::from_stream(&b""[..]).is_err());
+    }
+
+    #[test]
+    fn efficiency() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut scheme = Scheme::from_stream(SCHEME).unwrap();
+
+        assert!(scheme
+            .set_efficiency(-1.0, 0.0, (50.0, 50.0), &mut rng)
+            .is_err());
+        assert!(scheme
+            .set_efficiency(0.0, 101.0, (50.0, 50.0), &mut rng)
+            .is_err());
+
+        scheme
+            .set_efficiency(0.0, 0.0, (80.0, 20.0), &mut rng)
+            .unwrap();
+        assert_eq!(scheme.amplicons()[0].efficiency, 80.0);
+        assert_eq!(scheme.amplicons()[1].efficiency, 20.0);
+
+        scheme
+            .set_efficiency(0.5, 100.0, (80.0, 20.0), &mut rng)
+            .unwrap();
+        assert!(scheme.amplicons().iter().all(|x| x.efficiency == 0.0));
+
+        let mut scheme = Scheme::from_stream(
+            &b"chr1\t0\t4\ta_LEFT\t1\nchr1\t8\t10\ta_RIGHT\t1\nchr1\t0\t4\tb_LEFT\t2\nchr1\t8\t10\tb_RIGHT\t2\nchr1\t0\t4\tc_LEFT\t3\nchr1\t8\t10\tc_RIGHT\t3\n"[..],
+        )
+        .unwrap();
+        assert!(scheme
+            .set_efficiency(0.0, 0.0, (50.0, 50.0), &mut rng)
+            .is_err());
+    }
+
+    #[test]
+    fn targets() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut fasta = b">chr1\n".to_vec();
+        fasta.extend(crate::random_seq(50, &mut rng));
+        fasta.extend(b"\n>chr2\n");
+        fasta.extend(crate::random_seq(50, &mut rng));
+        fasta.push(b'\n');
+        let references = References::from_stream(std::io::Cursor::new(fasta)).unwrap();
+
+        let mut scheme = Scheme::from_stream(SCHEME).unwrap();
+        scheme
+            .set_efficiency(0.0, 0.0, (50.0, 50.0), &mut rng)
+            .unwrap();
+        let targets = scheme.targets(&references).unwrap();
+
+        let mut simulated = rustc_hash::FxHashMap::default();
+        for _ in 0..1_000 {
+            let (ref_index, start, end, id) = targets.choose(&mut rng);
+            assert_eq!(ref_index, 0);
+            match id {
+                "scheme_1" => assert_eq!((start, end), (0, 24)),
+                "scheme_2" => assert!((start, end) == (16, 40) || (start, end) == (17, 40)),
+                _ => unreachable!(),
+            }
+            simulated.entry(id.to_string()).or_insert((0, 0)).0 += 1;
+        }
+        assert!((400..600).contains(&simulated["scheme_1"].0));
+
+        scheme
+            .set_efficiency(0.0, 100.0, (50.0, 50.0), &mut rng)
+            .unwrap();
+        assert!(scheme.targets(&references).is_err());
+
+        let mut output = Vec::new();
+        scheme.write_truth(&mut output, &simulated).unwrap();
+        assert!(String::from_utf8(output).unwrap().starts_with(
+            "#amplicon\treference\tpool\tefficiency\treads\tbases\nscheme_1\tchr1\tscheme_1\t0\t"
+        ));
+    }
+}
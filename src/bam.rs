@@ -0,0 +1,248 @@
+//! Write unaligned reads in bam format
+
+/* standard use */
+use std::io::Write;
+
+/* crate use */
+use anyhow::Result;
+
+/* constant definition */
+const BLOCK_SIZE: usize = 0xff00;
+const EOF_BLOCK: [u8; 28] = [
+    0x1f, 0x8b, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x06, 0x00, 0x42, 0x43, 0x02, 0x00,
+    0x1b, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+const SEQ_CODE: &[u8; 16] = b"=ACMGRSVTWYHKDBN";
+
+/// Value of an optional field
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i32),
+    Float(f32),
+    String(String),
+}
+
+/// Write unaligned records in a bgzf compressed bam
+pub struct Writer<W>
+where
+    W: std::io::Write,
+{
+    output: W,
+    block: Vec<u8>,
+}
+
+impl<W> Writer<W>
+where
+    W: std::io::Write,
+{
+    /// Create a writer and write header, header is in sam format without reference
+    pub fn new(output: W, header: &str) -> Result<Self> {
+        let mut writer = Self {
+            output,
+            block: Vec::with_capacity(BLOCK_SIZE),
+        };
+
+        let mut data = b"BAM\x01".to_vec();
+        data.extend((header.len() as i32).to_le_bytes());
+        data.extend(header.as_bytes());
+        data.extend(0_i32.to_le_bytes());
+        writer.write_data(&data)?;
+
+        Ok(writer)
+    }
+
+    /// Write an unmapped record, quality is in phred+33
+    pub fn write(
+        &mut self,
+        name: &str,
+        seq: &[u8],
+        qual: &[u8],
+        tags: &[(&str, Value)],
+    ) -> Result<()> {
+        let mut record = Vec::with_capacity(36 + name.len() + seq.len() * 2);
+        record.extend((-1_i32).to_le_bytes()); // refID
+        record.extend((-1_i32).to_le_bytes()); // pos
+        record.push(name.len() as u8 + 1);
+        record.push(255); // mapq
+        record.extend(4680_u16.to_le_bytes()); // bin
+        record.extend(0_u16.to_le_bytes()); // n_cigar_op
+        record.extend(4_u16.to_le_bytes()); // flag unmapped
+        record.extend((seq.len() as u32).to_le_bytes());
+        record.extend((-1_i32).to_le_bytes()); // next refID
+        record.extend((-1_i32).to_le_bytes()); // next pos
+        record.extend(0_i32.to_le_bytes()); // tlen
+
+        record.extend(name.as_bytes());
+        record.push(0);
+
+        for pair in seq.chunks(2) {
+            let high = encode_base(pair[0]);
+            let low = pair.get(1).map(|x| encode_base(*x)).unwrap_or(0);
+            record.push(high << 4 | low);
+        }
+        record.extend(qual.iter().map(|x| x.saturating_sub(33)));
+
+        for (tag, value) in tags {
+            record.extend(tag.as_bytes());
+            match value {
+                Value::Int(v) => {
+                    record.push(b'i');
+                    record.extend(v.to_le_bytes());
+                }
+                Value::Float(v) => {
+                    record.push(b'f');
+                    record.extend(v.to_le_bytes());
+                }
+                Value::String(v) => {
+                    record.push(b'Z');
+                    record.extend(v.as_bytes());
+                    record.push(0);
+                }
+            }
+        }
+
+        let mut data = (record.len() as i32).to_le_bytes().to_vec();
+        data.extend(record);
+
+        self.write_data(&data)
+    }
+
+    /// Write last block and end of file marker
+    pub fn finish(mut self) -> Result<()> {
+        self.flush_block()?;
+        self.output.write_all(&EOF_BLOCK)?;
+        self.output.flush()?;
+
+        Ok(())
+    }
+
+    fn write_data(&mut self, mut data: &[u8]) -> Result<()> {
+        while !data.is_empty() {
+            let space = BLOCK_SIZE - self.block.len();
+            let size = space.min(data.len());
+
+            self.block.extend(&data[..size]);
+            data = &data[size..];
+
+            if self.block.len() == BLOCK_SIZE {
+                self.flush_block()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn flush_block(&mut self) -> Result<()> {
+        if self.block.is_empty() {
+            return Ok(());
+        }
+
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&self.block)?;
+        let compressed = encoder.finish()?;
+
+        let mut crc = flate2::Crc::new();
+        crc.update(&self.block);
+
+        self.output
+            .write_all(&[0x1f, 0x8b, 0x08, 0x04, 0, 0, 0, 0, 0, 0xff, 0x06, 0x00])?;
+        self.output.write_all(b"BC")?;
+        self.output.write_all(&2_u16.to_le_bytes())?;
+        self.output
+            .write_all(&((compressed.len() + 25) as u16).to_le_bytes())?;
+        self.output.write_all(&compressed)?;
+        self.output.write_all(&crc.sum().to_le_bytes())?;
+        self.output
+            .write_all(&(self.block.len() as u32).to_le_bytes())?;
+
+        self.block.clear();
+
+        Ok(())
+    }
+}
+
+/// Read group identifier of PacBio, first 8 characters of md5 of movie name and read type
+pub fn read_group_id(movie: &str, read_type: &str) -> String {
+    format!("{:x}", md5::compute(format!("{}//{}", movie, read_type)))[..8].to_string()
+}
+
+fn encode_base(base: u8) -> u8 {
+    SEQ_CODE
+        .iter()
+        .position(|x| *x == base.to_ascii_uppercase())
+        .unwrap_or(15) as u8
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn write() {
+        let mut output = Vec::new();
+        let mut writer = Writer::new(&mut output, "@HD\tVN:1.5\n").unwrap();
+        writer
+            .write(
+                "read",
+                b"ACGTN",
+                b"+5?I!",
+                &[
+                    ("zm", Value::Int(3)),
+                    ("rq", Value::Float(0.5)),
+                    ("RG", Value::String("abc".to_string())),
+                ],
+            )
+            .unwrap();
+        writer.finish().unwrap();
+
+        assert!(output.ends_with(&EOF_BLOCK));
+
+        let mut data = Vec::new();
+        flate2::read::MultiGzDecoder::new(&output[..])
+            .read_to_end(&mut data)
+            .unwrap();
+
+        let mut expected = b"BAM\x01\x0b\x00\x00\x00@HD\tVN:1.5\n\x00\x00\x00\x00".to_vec();
+        expected.extend(66_i32.to_le_bytes());
+        expected.extend([
+            255, 255, 255, 255, 255, 255, 255, 255, 5, 255, 0x48, 0x12, 0, 0, 4, 0,
+        ]);
+        expected.extend([
+            5, 0, 0, 0, 255, 255, 255, 255, 255, 255, 255, 255, 0, 0, 0, 0,
+        ]);
+        expected.extend(b"read\x00");
+        expected.extend([0x12, 0x48, 0xf0]);
+        expected.extend([10, 20, 30, 40, 0]);
+        expected.extend(b"zmi\x03\x00\x00\x00rqf\x00\x00\x00\x3fRGZabc\x00");
+
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn large() {
+        let mut output = Vec::new();
+        let mut writer = Writer::new(&mut output, "").unwrap();
+        let seq = vec![b'A'; 50_000];
+        for _ in 0..4 {
+            writer.write("read", &seq, &seq, &[]).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let mut data = Vec::new();
+        flate2::read::MultiGzDecoder::new(&output[..])
+            .read_to_end(&mut data)
+            .unwrap();
+        assert_eq!(data.len(), 12 + 4 * (4 + 32 + 5 + 25_000 + 50_000));
+    }
+
+    #[test]
+    fn group_id() {
+        assert_eq!(read_group_id("movie", "SUBREAD").len(), 8);
+        assert_ne!(
+            read_group_id("movie", "SUBREAD"),
+            read_group_id("movie", "CCS")
+        );
+    }
+}
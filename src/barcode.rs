@@ -0,0 +1,399 @@
+//! Multiplex samples with barcodes add between adapter and fragment
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::{Context, Result};
+
+/* local use */
+use crate::cli::simulate::Quantity;
+use crate::error::Barcode as Error;
+use crate::model;
+use crate::references::{Reference, References};
+use crate::simulate::description::{Description, ReadType};
+
+/* constant definition */
+const BARCODES: [&str; 24] = [
+    "AAGAAAGTTGTCGGTGTCTTTGTG",
+    "TCGATTCCGTTTGTAGTCGTCTGT",
+    "GAGTCTTGTGTCCCAGTTACCAGG",
+    "TTCGGATTCTATCGTGTTTCCCTA",
+    "CTTGTCCAGGGTTTGTGTAACCTT",
+    "TTCTCGCAAAGGCAGAAAGTAGTC",
+    "GTGTTACCGTGGGAATGAATCCTT",
+    "TTCAGGGAACAAACCAAGTTACGT",
+    "AACTAGGCACAGCGAGTCTTGGTT",
+    "AAGCGTTGAAACCTTTGTCCTCTC",
+    "GTTTCATCTATCGGAGGGAATGGA",
+    "CAGGTAGAAAGAAGCAGAATCGGA",
+    "AGAACGACTTCCATACTCGTGTGA",
+    "AACGAGTCTCTTGGGACCCATAGA",
+    "AGGTCTACCTCGCTAACACCACTG",
+    "CGTCAACTGACAGTGGTTCGTACT",
+    "ACCCTCCAGGAAAGTACCTCTGAT",
+    "CCAAACCCAACAACCTAGATAGGC",
+    "GTTCCTCGTGCAGTGTCAAGAGAT",
+    "TTGCGTCCTGTTACGAGAACTCAT",
+    "GAGCCTCTCATTGTCCGTTCTCTA",
+    "ACCACTGCCATGTATCAAAGTACG",
+    "CTTACTACCCAGAACACACACAAA",
+    "GCATAGTTCTGCATGATGGGTTAG",
+];
+const NATIVE_FLANKS: (&str, &str) = ("AAGGTTAA", "CAGCACCT");
+const RAPID_FLANKS: (&str, &str) = (
+    "GCTTGGGTGTTTAACC",
+    "GTTTTCGCATTTATCGTGAAACGCTTTCGCGTTTTTCGTGCGCCGCTTCA",
+);
+
+/// A set of barcodes, each barcode is associate to sequence add at start and at end of read
+pub struct Kit {
+    barcodes: Vec<(String, Vec<u8>, Vec<u8>)>,
+}
+
+impl Kit {
+    /// Native barcoding kit (NB01 to NB24), barcodes with their flanks are add at both ends
+    pub fn native() -> Self {
+        Self {
+            barcodes: BARCODES
+                .iter()
+                .enumerate()
+                .map(|(i, barcode)| {
+                    let mut start = NATIVE_FLANKS.0.as_bytes().to_vec();
+                    start.extend(bio::alphabets::dna::revcomp(barcode.as_bytes()));
+                    start.extend(NATIVE_FLANKS.1.as_bytes());
+
+                    let end = bio::alphabets::dna::revcomp(&start);
+                    (format!("NB{:02}", i + 1), start, end)
+                })
+                .collect(),
+        }
+    }
+
+    /// Rapid barcoding kit (RB01 to RB12), barcodes with their flanks are add only at start
+    pub fn rapid() -> Self {
+        Self {
+            barcodes: BARCODES[..12]
+                .iter()
+                .enumerate()
+                .map(|(i, barcode)| {
+                    let mut start = RAPID_FLANKS.0.as_bytes().to_vec();
+                    start.extend(barcode.as_bytes());
+                    start.extend(RAPID_FLANKS.1.as_bytes());
+
+                    (format!("RB{:02}", i + 1), start, Vec::new())
+                })
+                .collect(),
+        }
+    }
+
+    /// Read custom barcodes in fasta format, barcode is add at start and his reverse complement at end
+    pub fn from_stream<R>(input: R) -> Result<Self>
+    where
+        R: std::io::Read,
+    {
+        Ok(Self {
+            barcodes: References::from_stream(input)?
+                .sequences
+                .into_iter()
+                .map(|x| (x.id, x.seq.to_vec(), x.revcomp.to_vec()))
+                .collect(),
+        })
+    }
+
+    /// Sequences add at start and end of read for a barcode
+    pub fn get(&self, name: &str) -> Option<(&[u8], &[u8])> {
+        self.barcodes
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map(|(_, start, end)| (&start[..], &end[..]))
+    }
+}
+
+/// A sample, reads of sample are generate from reference and get barcode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub barcode: String,
+    pub reference_path: String,
+    pub quantity: Quantity,
+}
+
+/// Read sample sheet, columns are barcode name, path to reference fasta and quantity
+pub fn read_samples<R>(input: R, kit: &Kit) -> Result<Vec<Sample>>
+where
+    R: std::io::Read,
+{
+    let mut samples = Vec::new();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(false)
+        .flexible(true)
+        .quoting(false)
+        .comment(Some(b'#'))
+        .from_reader(input);
+
+    for record in reader.records() {
+        let record = record?;
+        if record.len() < 3 {
+            anyhow::bail!(Error::SampleParsing);
+        }
+
+        if kit.get(&record[0]).is_none() {
+            anyhow::bail!(Error::UnknownBarcode(record[0].to_string()));
+        }
+
+        samples.push(Sample {
+            barcode: record[0].to_string(),
+            reference_path: record[1].to_string(),
+            quantity: Quantity::from_str(&record[2]).with_context(|| Error::SampleParsing)?,
+        });
+    }
+
+    if samples.is_empty() {
+        anyhow::bail!(Error::SampleParsing);
+    }
+
+    Ok(samples)
+}
+
+/// Add barcodes of sample in reads, a barcode can be truncated and all barcodes of an unclassified read are lost
+pub struct Barcoding {
+    kit: Kit,
+    truncation_rate: f64,
+    unclassified_rate: f64,
+}
+
+impl Barcoding {
+    /// Create model, rates are percentage
+    pub fn new(kit: Kit, truncation_rate: f64, unclassified_rate: f64) -> Result<Self> {
+        if !(0.0..=100.0).contains(&truncation_rate) || !(0.0..=100.0).contains(&unclassified_rate)
+        {
+            anyhow::bail!(Error::ParamOutOfRange);
+        }
+
+        Ok(Self {
+            kit,
+            truncation_rate: truncation_rate / 100.0,
+            unclassified_rate: unclassified_rate / 100.0,
+        })
+    }
+
+    /// Adapter model of first and each chimeric part of read, with barcodes of their reference
+    ///
+    /// Add a tag `barcode_kept` in description, with value both, start, end or none, to indicate which complete barcode is present in read
+    pub fn adapters<RNG>(
+        &self,
+        description: &mut Description,
+        references: &[&Reference],
+        adapter: &model::Adapter,
+        rng: &mut RNG,
+    ) -> Vec<model::Adapter>
+    where
+        RNG: rand::Rng,
+    {
+        let unclassified = rng.gen_bool(self.unclassified_rate);
+
+        let first = if description.origin.read_type == ReadType::Real {
+            self.barcodes(references[0], unclassified, rng)
+        } else {
+            ((Vec::new(), false), (Vec::new(), false))
+        };
+        let mut parts = vec![first];
+        for (chimera, reference) in description.chimera.iter().zip(&references[1..]) {
+            let part = if chimera.read_type == ReadType::Real {
+                self.barcodes(reference, unclassified, rng)
+            } else {
+                parts[0].clone()
+            };
+            parts.push(part);
+        }
+
+        let kept = match ((parts[0].0).1, (parts[parts.len() - 1].1).1) {
+            (true, true) => "both",
+            (true, false) => "start",
+            (false, true) => "end",
+            (false, false) => "none",
+        };
+        description.tags.push(("barcode_kept", kept.to_string()));
+
+        parts
+            .into_iter()
+            .map(|(start, end)| adapter.with_barcodes(start.0, end.0))
+            .collect()
+    }
+
+    /// Start and end barcodes of a reference, with a boolean true if barcode is complete
+    #[allow(clippy::type_complexity)]
+    fn barcodes<RNG>(
+        &self,
+        reference: &Reference,
+        unclassified: bool,
+        rng: &mut RNG,
+    ) -> ((Vec<u8>, bool), (Vec<u8>, bool))
+    where
+        RNG: rand::Rng,
+    {
+        match reference.barcode().and_then(|x| self.kit.get(x)) {
+            Some((start, end)) if !unclassified => (
+                self.truncate(start, true, rng),
+                self.truncate(end, false, rng),
+            ),
+            _ => ((Vec::new(), false), (Vec::new(), false)),
+        }
+    }
+
+    /// Truncate barcode, start barcode lose his begin and end barcode lose his end
+    fn truncate<RNG>(&self, barcode: &[u8], start: bool, rng: &mut RNG) -> (Vec<u8>, bool)
+    where
+        RNG: rand::Rng,
+    {
+        if barcode.is_empty() {
+            (Vec::new(), false)
+        } else if rng.gen_bool(self.truncation_rate) {
+            let length = rng.gen_range(0..barcode.len());
+            if start {
+                (barcode[barcode.len() - length..].to_vec(), false)
+            } else {
+                (barcode[..length].to_vec(), false)
+            }
+        } else {
+            (barcode.to_vec(), true)
+        }
+    }
+}
+
+/// Write number of reads and bases produce by each sample
+pub fn write_truth<W>(
+    output: &mut W,
+    samples: &[Sample],
+    simulated: &rustc_hash::FxHashMap<String, (u64, u64)>,
+) -> Result<()>
+where
+    W: std::io::Write,
+{
+    writeln!(output, "#barcode\treference\treads\tbases")?;
+    for sample in samples {
+        let (reads, bases) = simulated.get(&sample.barcode).cloned().unwrap_or((0, 0));
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}",
+            sample.barcode, sample.reference_path, reads, bases
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    use crate::simulate::description::{Label, Origin};
+
+    #[test]
+    fn kits() {
+        let native = Kit::native();
+        let (start, end) = native.get("NB01").unwrap();
+        assert_eq!(start, b"AAGGTTAACACAAAGACACCGACAACTTTCTTCAGCACCT");
+        assert_eq!(end, b"AGGTGCTGAAGAAAGTTGTCGGTGTCTTTGTGTTAACCTT");
+        assert!(native.get("NB24").is_some());
+        assert!(native.get("NB25").is_none());
+
+        let rapid = Kit::rapid();
+        let (start, end) = rapid.get("RB12").unwrap();
+        assert!(start.starts_with(b"GCTTGGGTGTTTAACCCAGGTAGAAAGAAGCAGAATCGGA"));
+        assert!(end.is_empty());
+        assert!(rapid.get("RB13").is_none());
+
+        let custom = Kit::from_stream(&b">bc1\nACCGTT\n"[..]).unwrap();
+        assert_eq!(custom.get("bc1"), Some((&b"ACCGTT"[..], &b"AACGGT"[..])));
+    }
+
+    #[test]
+    fn sample_sheet() {
+        let kit = Kit::native();
+
+        let samples =
+            read_samples(&b"NB01\tref1.fasta\t20x\nNB02\tref2.fasta\t5M\n"[..], &kit).unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[1].barcode, "NB02");
+        assert_eq!(samples[1].quantity.number_of_base(100), 5_000_000);
+
+        assert!(read_samples(&b"NB01\tref1.fasta\n"[..], &kit).is_err());
+        assert!(read_samples(&b"RB01\tref1.fasta\t20x\n"[..], &kit).is_err());
+        assert!(read_samples(&b"NB01\tref1.fasta\t20y\n"[..], &kit).is_err());
+    }
+
+    #[test]
+    fn adapters() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let adapter = model::Adapter::new(Vec::new(), Vec::new(), 0.0, 0.0, 0.0, 0.0).unwrap();
+
+        let mut first =
+            Reference::new("r1".to_string(), b"ACGT".to_vec().into_boxed_slice(), false);
+        first.labels.push(Label::Barcode("NB01".to_string()));
+        let mut second =
+            Reference::new("r2".to_string(), b"ACGT".to_vec().into_boxed_slice(), false);
+        second.labels.push(Label::Barcode("NB02".to_string()));
+
+        assert!(Barcoding::new(Kit::native(), 101.0, 0.0).is_err());
+
+        let barcoding = Barcoding::new(Kit::native(), 0.0, 0.0).unwrap();
+        let mut description = Description::new(
+            Origin::reference("r1".to_string(), '+', 0, 4),
+            vec![Origin::reference("r2".to_string(), '+', 0, 4)],
+            8,
+            90.0,
+        );
+        let adapters = barcoding.adapters(&mut description, &[&first, &second], &adapter, &mut rng);
+        let (start, end) = (&adapters[0], &adapters[1]);
+        assert_eq!(
+            start.get_start(&mut rng),
+            Kit::native().get("NB01").unwrap().0
+        );
+        assert_eq!(
+            start.get_end(&mut rng),
+            Kit::native().get("NB01").unwrap().1
+        );
+        assert_eq!(
+            end.get_start(&mut rng),
+            Kit::native().get("NB02").unwrap().0
+        );
+        assert_eq!(end.get_end(&mut rng), Kit::native().get("NB02").unwrap().1);
+        assert_eq!(description.tags, vec![("barcode_kept", "both".to_string())]);
+
+        let barcoding = Barcoding::new(Kit::native(), 0.0, 100.0).unwrap();
+        let mut description = Description::new(
+            Origin::reference("r1".to_string(), '+', 0, 4),
+            Vec::new(),
+            4,
+            90.0,
+        );
+        let adapters = barcoding.adapters(&mut description, &[&first], &adapter, &mut rng);
+        assert_eq!(adapters.len(), 1);
+        assert!(adapters[0].get_start(&mut rng).is_empty());
+        assert!(adapters[0].get_end(&mut rng).is_empty());
+        assert_eq!(description.tags, vec![("barcode_kept", "none".to_string())]);
+
+        let barcoding = Barcoding::new(Kit::native(), 100.0, 0.0).unwrap();
+        let (start, _) = barcoding.barcodes(&first, false, &mut rng);
+        assert!(!start.1);
+        assert!(Kit::native().get("NB01").unwrap().0.ends_with(&start.0));
+    }
+
+    #[test]
+    fn truth() {
+        let samples = read_samples(&b"NB01\tref1.fasta\t20x\n"[..], &Kit::native()).unwrap();
+        let mut simulated = rustc_hash::FxHashMap::default();
+        simulated.insert("NB01".to_string(), (3, 30));
+
+        let mut output = Vec::new();
+        write_truth(&mut output, &samples, &simulated).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "#barcode\treference\treads\tbases\nNB01\tref1.fasta\t3\t30\n"
+        );
+    }
+}
@@ -2,11 +2,12 @@
 
 use anyhow::Context;
 
-/// Store quantity as coverage of number of base
-#[derive(Debug, PartialEq, Eq)]
+/// Store quantity as coverage, number of base or number of reads
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Quantity {
     coverage: u64,
     base: Option<u64>,
+    reads: Option<u64>,
 }
 
 impl std::str::FromStr for Quantity {
@@ -14,11 +15,23 @@ impl std::str::FromStr for Quantity {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match &s.as_bytes() {
+            &[rest @ .., b'r', b'e', b'a', b'd', b's'] => match std::str::from_utf8(rest) {
+                Ok(number) => match u64::from_str(number) {
+                    Ok(n) => Ok(Quantity {
+                        coverage: 0,
+                        base: None,
+                        reads: Some(n),
+                    }),
+                    Err(_) => Err(crate::error::Cli::CantParseQuantity),
+                },
+                Err(_) => Err(crate::error::Cli::CantParseQuantity),
+            },
             &[rest @ .., b'x'] => match std::str::from_utf8(rest) {
                 Ok(number) => match u64::from_str(number) {
                     Ok(n) => Ok(Quantity {
                         coverage: n,
                         base: None,
+                        reads: None,
                     }),
                     Err(_) => Err(crate::error::Cli::CantParseQuantity),
                 },
@@ -29,6 +42,7 @@ impl std::str::FromStr for Quantity {
                     Ok(n) => Ok(Quantity {
                         coverage: 0,
                         base: Some(n * 1_000_000_000),
+                        reads: None,
                     }),
                     Err(_) => Err(crate::error::Cli::CantParseQuantity),
                 },
@@ -39,6 +53,7 @@ impl std::str::FromStr for Quantity {
                     Ok(n) => Ok(Quantity {
                         coverage: 0,
                         base: Some(n * 1_000_000),
+                        reads: None,
                     }),
                     Err(_) => Err(crate::error::Cli::CantParseQuantity),
                 },
@@ -49,6 +64,7 @@ impl std::str::FromStr for Quantity {
                     Ok(n) => Ok(Quantity {
                         coverage: 0,
                         base: Some(n * 1_000),
+                        reads: None,
                     }),
                     Err(_) => Err(crate::error::Cli::CantParseQuantity),
                 },
@@ -59,6 +75,7 @@ impl std::str::FromStr for Quantity {
                     Ok(n) => Ok(Quantity {
                         coverage: 0,
                         base: Some(n),
+                        reads: None,
                     }),
                     Err(_) => Err(crate::error::Cli::CantParseQuantity),
                 },
@@ -69,13 +86,89 @@ impl std::str::FromStr for Quantity {
 }
 
 impl Quantity {
-    /// Convert Quantity in a number of base, if base is set return just number of base else return genome_length times coverage
+    /// Convert Quantity in a number of base, if base is set return just number of base else return genome_length times coverage, a number of reads doesn't limit number of base and u64::MAX is return
     pub fn number_of_base(&self, genome_length: u64) -> u64 {
-        match self.base {
-            Some(n) => n,
-            None => genome_length * self.coverage,
+        match (self.base, self.reads) {
+            (Some(n), _) => n,
+            (None, Some(_)) => u64::MAX,
+            (None, None) => genome_length * self.coverage,
+        }
+    }
+
+    /// Quantity is a coverage relative to genome length
+    pub fn is_coverage(&self) -> bool {
+        self.base.is_none() && self.reads.is_none()
+    }
+
+    /// Number of reads if quantity is a number of reads
+    pub fn number_of_reads(&self) -> Option<u64> {
+        self.reads
+    }
+}
+
+/// Quantity target of a reference, can be parse from str `id:quantity`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceQuantity {
+    pub id: String,
+    pub quantity: Quantity,
+}
+
+impl std::str::FromStr for ReferenceQuantity {
+    type Err = crate::error::Cli;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().rsplit_once(':') {
+            Some((id, quantity)) if !id.is_empty() => Ok(ReferenceQuantity {
+                id: id.to_string(),
+                quantity: Quantity::from_str(quantity)?,
+            }),
+            _ => Err(crate::error::Cli::CantParseReferenceQuantity),
+        }
+    }
+}
+
+/// Get quantity targets of references, value is a path to a tsv (reference id and quantity) or a list of id:quantity separate by comma
+pub fn reference_quantities(value: &str) -> anyhow::Result<Vec<ReferenceQuantity>> {
+    let path = std::path::Path::new(value);
+
+    let quantities = if path.is_file() {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .quoting(false)
+            .comment(Some(b'#'))
+            .from_reader(
+                std::fs::File::open(path).with_context(|| "Open quantity per reference file")?,
+            );
+
+        let mut quantities = Vec::new();
+        for record in reader.records() {
+            let record = record.with_context(|| crate::error::Cli::CantParseReferenceQuantity)?;
+            if record.len() < 2 {
+                anyhow::bail!(crate::error::Cli::CantParseReferenceQuantity);
+            }
+
+            quantities.push(ReferenceQuantity {
+                id: record[0].to_string(),
+                quantity: std::str::FromStr::from_str(&record[1])
+                    .with_context(|| crate::error::Cli::CantParseReferenceQuantity)?,
+            });
         }
+
+        quantities
+    } else {
+        value
+            .split(',')
+            .map(std::str::FromStr::from_str)
+            .collect::<Result<Vec<ReferenceQuantity>, crate::error::Cli>>()?
+    };
+
+    if quantities.is_empty() {
+        anyhow::bail!(crate::error::Cli::CantParseReferenceQuantity);
     }
+
+    Ok(quantities)
 }
 
 /// Store a pair of value, can be parse from str if it's match with \d+,\d+
@@ -135,7 +228,7 @@ pub fn found_model(value: String, model_type: String) -> anyhow::Result<std::pat
         Ok(path)
     } else {
         let result = std::process::Command::new("python")
-            .args(&["-c", "import sys; print(','.join(sys.path))"])
+            .args(["-c", "import sys; print(','.join(sys.path))"])
             .output()
             .with_context(|| crate::error::Cli::CantFoundModelPath)?;
 
@@ -163,28 +256,42 @@ pub fn found_model(value: String, model_type: String) -> anyhow::Result<std::pat
 #[clap(about = "Generate fake long read")]
 pub struct Command {
     /// Path to reference fasta (can be gzipped, bzip2ped, xzped)
-    #[clap(long = "reference", required = true)]
-    pub reference_path: String,
+    #[clap(
+        long = "reference",
+        required_unless_present_any = &["community-path", "transcriptome-path", "samples-path"]
+    )]
+    pub reference_path: Option<String>,
 
     /// Path where read is write
     #[clap(long = "output")]
     pub output_path: Option<String>,
 
-    /// Either an absolute value (e.g. 250M) or a relative depth (e.g. 25x)
-    #[clap(long = "quantity", required = true)]
-    pub quantity: Quantity,
+    /// Either an absolute value (e.g. 250M), a relative depth (e.g. 25x) or a number of reads (e.g. 50000reads)
+    #[clap(
+        long = "quantity",
+        required_unless_present_any = &["samples-path", "quantity-per-reference"]
+    )]
+    pub quantity: Option<Quantity>,
+
+    /// Quantity of some references, a list of id:quantity (e.g. chr1:30x,plasmid:200x) or path to a tsv of reference id and quantity, a reference stop when his target is reach and other references are ignored
+    #[clap(long = "quantity_per_reference", conflicts_with_all = &["quantity", "samples-path", "primer-scheme", "pore-c"])]
+    pub quantity_per_reference: Option<String>,
 
     /// Fragment length distribution (mean and stdev)
     #[clap(long = "length", default_value = "15000,13000")]
     pub length: Duo,
 
+    /// Size selection of fragments, min[,max][,sharpness], a fragment outside bounds is remove if sharpness is 0 else keep with a logistic probability (e.g. BluePippin 10000 or SRE 10000,,2000)
+    #[clap(long = "size_selection")]
+    pub size_selection: Option<crate::model::SizeSelection>,
+
     /// Sequencing identity distribution (mean, max and stdev)
     #[clap(long = "identity", default_value = "85,95,5")]
     pub identity: Trio,
 
-    /// Path to an error model file
-    #[clap(long = "error_model", default_value = "nanopore2020")]
-    pub error_model: String,
+    /// Path to an error model file, random or hifi (random errors dominated by homopolymer insertion and deletion) (default: nanopore2020, hifi in PacBio HiFi mode)
+    #[clap(long = "error_model")]
+    pub error_model: Option<String>,
 
     /// Path to an quality score model file
     #[clap(long = "qscore_model", default_value = "nanopore2020")]
@@ -225,6 +332,49 @@ pub struct Command {
     #[clap(long = "chimera", default_value = "1")]
     pub chimera: f64,
 
+    /// Mean number of parts of a chimera, number of parts follow a geometric distribution
+    #[clap(long = "chimera_parts", default_value = "2")]
+    pub chimera_parts: f64,
+
+    /// How parts of a chimera are join: random (random position of a random reference), adjacent (next position on same strand) or proximal (random strand near previous part)
+    #[clap(long = "chimera_junction", default_value = "random")]
+    pub chimera_junction: crate::model::chimera::Junction,
+
+    /// Library preparation kit: ligation or rapid (transposase cut molecules at motif-weighted insertions and attach rapid adapter at 5' end only, mean of length set mean distance between insertions)
+    #[clap(
+        long = "library",
+        default_value = "ligation",
+        conflicts_with_all = &["pacbio", "transcriptome-path", "pore-c", "primer-scheme"]
+    )]
+    pub library: crate::model::transposase::Library,
+
+    /// IUPAC motif prefer by transposase, default is MuA target site
+    #[clap(long = "insertion_motif", default_value = "CYSRG")]
+    pub insertion_motif: String,
+
+    /// Transposase insertion at a site match motif is this number of times more likely than at a site without any match
+    #[clap(long = "insertion_bias", default_value = "10")]
+    pub insertion_bias: f64,
+
+    /// Shear long parent molecules in reads with a device: g-tube, needle or a mean fragment size, length of reads isn't draw from length distribution
+    #[clap(
+        long = "shearing",
+        conflicts_with_all = &["library", "pcr-cycles", "pore-c", "primer-scheme", "transcriptome-path"]
+    )]
+    pub shearing: Option<crate::model::shearing::Device>,
+
+    /// Parent molecules length parameters (mean and stdev), only use with shearing
+    #[clap(long = "parent_length", default_value = "50000,30000")]
+    pub parent_length: Duo,
+
+    /// Chance, in percentage, to get an end adapter and a start adapter at a chimera junction
+    #[clap(long = "chimera_adapter", default_value = "25,25")]
+    pub chimera_adapter: Duo,
+
+    /// Percentage of foldback reads, molecule is read through a hairpin and back along his reverse complement (not available in PacBio mode)
+    #[clap(long = "foldback", default_value = "0", conflicts_with = "pacbio")]
+    pub foldback: f64,
+
     /// Read glitch parameters (rate, size and skip)
     #[clap(long = "glitches", default_value = "10000,25,25")]
     pub glitches: Trio,
@@ -236,6 +386,301 @@ pub struct Command {
     /// Number of base, rustyread can store in ram before write in output in absolute value (e.g. 250M) or a relative depth (e.g. 25x)
     #[clap(long = "number_base_store")]
     pub nb_base_store: Option<Quantity>,
+
+    /// Path to a phased vcf (can be gzipped, bzip2ped, xzped), reads are generate from haplotypes build with reference and variants
+    #[clap(long = "vcf")]
+    pub vcf_path: Option<String>,
+
+    /// Number of haplotypes build for each reference when vcf or structural variants are set
+    #[clap(long = "ploidy", default_value = "2")]
+    pub ploidy: usize,
+
+    /// Number of random structural variants (deletion, insertion, inversion, duplication, translocation) add in haplotypes
+    #[clap(long = "sv_random", default_value = "0")]
+    pub sv_random: usize,
+
+    /// Path to a vcf of structural variants add in haplotypes (can be gzipped, bzip2ped, xzped)
+    #[clap(long = "sv_vcf")]
+    pub sv_vcf_path: Option<String>,
+
+    /// Path to a bed of tandem repeats: reference, start, end, motif, number of copy on each haplotype (e.g. 20,45) and an optional standard deviation of copy number between reads (somatic mosaicism)
+    #[clap(long = "repeat_catalog")]
+    pub repeat_catalog: Option<String>,
+
+    /// Fraction of reference base mutate before sampling to simulate a related strain (e.g. 0.5%)
+    #[clap(long = "divergence")]
+    pub divergence: Option<crate::variant::divergence::Divergence>,
+
+    /// Ratio between number of snv and indel generate by divergence
+    #[clap(long = "divergence_snv_indel", default_value = "9.0")]
+    pub divergence_snv_indel: f64,
+
+    /// Ratio between number of transition and transversion generate by divergence
+    #[clap(long = "divergence_ts_tv", default_value = "2.0")]
+    pub divergence_ts_tv: f64,
+
+    /// Path to a bed of copy number: reference, start, end and number of copy, depth of interval is scaled by number of copy relative to ploidy
+    #[clap(long = "copy_number")]
+    pub copy_number: Option<String>,
+
+    /// Path to a tsv of tumour subclones: name, parent, fraction of tumour cells, somatic small variant vcf, somatic structural variant vcf and copy number bed ('.' if missing)
+    #[clap(long = "clones")]
+    pub clones_path: Option<String>,
+
+    /// Fraction of tumour cells in sample, use with clones
+    #[clap(long = "purity", default_value = "1.0")]
+    pub purity: f64,
+
+    /// Path where a matched normal read set is write, use with clones
+    #[clap(long = "normal_output", requires = "clones-path")]
+    pub normal_output: Option<String>,
+
+    /// Path to a genome fasta, reads are generate from full-length cDNA of transcripts build with annotation
+    #[clap(
+        long = "transcriptome",
+        requires = "annotation",
+        conflicts_with_all = &["reference-path", "community-path", "strains-path"]
+    )]
+    pub transcriptome_path: Option<String>,
+
+    /// Path to a gtf annotation of genome, transcripts are build from exons
+    #[clap(long = "annotation", requires = "transcriptome-path")]
+    pub annotation: Option<String>,
+
+    /// Path to a tsv of transcripts expression: transcript id and expression (e.g. TPM), by default all transcripts have same expression
+    #[clap(long = "expression", requires = "transcriptome-path")]
+    pub expression: Option<String>,
+
+    /// Poly-A tail length distribution of cDNA (mean and stdev)
+    #[clap(long = "poly_a", default_value = "100,30")]
+    pub poly_a: Duo,
+
+    /// Percentage of cDNA with a truncated 5' end
+    #[clap(long = "truncation_rate", default_value = "10")]
+    pub truncation_rate: f64,
+
+    /// Percentage of cDNA primed on an internal poly-A stretch
+    #[clap(long = "intra_priming_rate", default_value = "2")]
+    pub intra_priming_rate: f64,
+
+    /// Strand switching primer add at 5' end of cDNA
+    #[clap(long = "ssp_seq", default_value = "TTTCTGTTGGTGCTGATATTGCTGGG")]
+    pub ssp_seq: String,
+
+    /// VN primer add at 3' end of cDNA, after poly-T
+    #[clap(long = "vnp_seq", default_value = "ACTTGCCTGTCGCTCTATCTTC")]
+    pub vnp_seq: String,
+
+    /// Sequence RNA molecules directly, reads are in 3' to 5' orientation and start with start adapter
+    #[clap(
+        long = "direct_rna",
+        requires = "transcriptome-path",
+        conflicts_with = "duplex-output"
+    )]
+    pub direct_rna: bool,
+
+    /// Number of base at 5' end of RNA never sequenced (minimum and maximum)
+    #[clap(long = "five_prime_loss", default_value = "10,20")]
+    pub five_prime_loss: Duo,
+
+    /// Write direct RNA reads with U in place of T
+    #[clap(long = "uracil", requires = "direct-rna")]
+    pub uracil: bool,
+
+    /// Simulate single-cell cDNA, reads contains read-1 adapter, cell barcode, UMI and poly-T
+    #[clap(
+        long = "single_cell",
+        requires = "transcriptome-path",
+        conflicts_with = "direct-rna"
+    )]
+    pub single_cell: bool,
+
+    /// Number of cells
+    #[clap(long = "cells", default_value = "100")]
+    pub cells: usize,
+
+    /// Path to a whitelist of cell barcodes, one barcode by line (default: random barcodes)
+    #[clap(long = "whitelist", requires = "single-cell")]
+    pub whitelist: Option<String>,
+
+    /// Path to a tsv of cell barcode, transcript id and expression, replace cells and their random expression profile
+    #[clap(long = "cell_expression", requires = "single-cell")]
+    pub cell_expression: Option<String>,
+
+    /// Standard deviation of log of cell size and of transcript expression in a cell
+    #[clap(long = "cell_variability", default_value = "0.5")]
+    pub cell_variability: f64,
+
+    /// Mean number of reads by UMI, PCR duplicates
+    #[clap(long = "umi_duplicates", default_value = "1.5")]
+    pub umi_duplicates: f64,
+
+    /// Read-1 adapter add before cell barcode
+    #[clap(long = "read1_seq", default_value = "CTACACGACGCTCTTCCGATCT")]
+    pub read1_seq: String,
+
+    /// Template switching oligo add at 5' end of single-cell cDNA
+    #[clap(long = "tso_seq", default_value = "AAGCAGTGGTATCAACGCAGAGTACATGGG")]
+    pub tso_seq: String,
+
+    /// Path to a sample sheet, a tsv of barcode name, reference fasta path and quantity, reads of each sample get his barcode
+    #[clap(
+        long = "samples",
+        conflicts_with_all = &["reference-path", "community-path", "transcriptome-path", "strains-path"]
+    )]
+    pub samples_path: Option<String>,
+
+    /// Barcode kit, NB (native barcoding, barcodes at both ends), RB (rapid barcoding, barcode at start) or path to a fasta of custom barcodes
+    #[clap(long = "barcode_kit", default_value = "NB")]
+    pub barcode_kit: String,
+
+    /// Percentage of barcodes truncated at their outer side
+    #[clap(long = "barcode_truncation", default_value = "10")]
+    pub barcode_truncation: f64,
+
+    /// Percentage of reads without any barcode
+    #[clap(long = "unclassified_rate", default_value = "5")]
+    pub unclassified_rate: f64,
+
+    /// Percentage of molecules sequenced on both strands, template read is follow by his complement read (ignored in direct RNA and PacBio mode)
+    #[clap(long = "duplex", default_value = "0")]
+    pub duplex: f64,
+
+    /// Path where duplex consensus reads are write, reads are named template;complement
+    #[clap(long = "duplex_output")]
+    pub duplex_output: Option<String>,
+
+    /// Duplex consensus read identity distribution (mean, max and stdev)
+    #[clap(long = "duplex_identity", default_value = "99,100,1")]
+    pub duplex_identity: Trio,
+
+    /// Simulate PacBio reads, subreads: polymerase read go around a SMRTbell and is split in subreads at adapters, hifi: a consensus read with accuracy depends on number of passes
+    #[clap(
+        long = "pacbio",
+        conflicts_with_all = &["samples-path", "single-cell", "direct-rna", "duplex-output"]
+    )]
+    pub pacbio: Option<crate::simulate::pacbio::Mode>,
+
+    /// SMRTbell insert length distribution (mean and stdev), in PacBio mode length is polymerase read length
+    #[clap(long = "insert_length", default_value = "10000,2000")]
+    pub insert_length: Duo,
+
+    /// Hairpin adapter of SMRTbell
+    #[clap(
+        long = "smrtbell_adapter",
+        default_value = "ATCTCTCTCAACAACAACAACGGAGGAGGAGGAAAAGAGAGAGAT"
+    )]
+    pub smrtbell_adapter: String,
+
+    /// Movie name use in PacBio read name
+    #[clap(long = "movie", default_value = "m64000_000000_000000")]
+    pub movie: String,
+
+    /// HiFi read qscore curve: qscore of one pass, gain of qscore each time number of passes double and maximal qscore
+    #[clap(long = "pass_accuracy", default_value = "10,5,40")]
+    pub pass_accuracy: Trio,
+
+    /// HiFi reads with a qscore lower than this value are filtered
+    #[clap(long = "min_qscore", default_value = "20")]
+    pub min_qscore: f64,
+
+    /// Path where PacBio reads are write in unaligned bam
+    #[clap(long = "bam", requires = "pacbio")]
+    pub bam_path: Option<String>,
+
+    /// Path to a primer scheme bed (reference, start, end, primer name and pool), reads are whole amplicons between primers and length distribution isn't use
+    #[clap(
+        long = "primer_scheme",
+        conflicts_with_all = &["pore-c", "single-cell", "transcriptome-path"]
+    )]
+    pub primer_scheme: Option<String>,
+
+    /// Standard deviation of log of amplicon efficiency
+    #[clap(long = "amplicon_variability", default_value = "0.5")]
+    pub amplicon_variability: f64,
+
+    /// Percentage of amplicons without any read
+    #[clap(long = "amplicon_dropout", default_value = "0")]
+    pub amplicon_dropout: f64,
+
+    /// Balance of primer pools, percentage of reads from first and second pool
+    #[clap(long = "pool_balance", default_value = "50,50")]
+    pub pool_balance: Duo,
+
+    /// Number of PCR cycles apply on molecules before sequencing, 0 disable PCR
+    #[clap(
+        long = "pcr_cycles",
+        default_value = "0",
+        conflicts_with_all = &["single-cell", "pore-c"]
+    )]
+    pub pcr_cycles: usize,
+
+    /// Percentage of molecules copied at each PCR cycle
+    #[clap(long = "pcr_efficiency", default_value = "80")]
+    pub pcr_efficiency: f64,
+
+    /// Percentage of polymerase substitution by base and by copy, errors are inherit by descendants
+    #[clap(long = "pcr_error_rate", default_value = "0.001")]
+    pub pcr_error_rate: f64,
+
+    /// Percentage of PCR copies with a template switch to previous molecule
+    #[clap(long = "template_switch", default_value = "0.1")]
+    pub template_switch: f64,
+
+    /// Mean number of reads by PCR duplicate group
+    #[clap(long = "pcr_duplicates", default_value = "2")]
+    pub pcr_duplicates: f64,
+
+    /// Simulate Pore-C concatemers of restriction fragments in contact, references are digest by this enzyme (DpnII, MboI, NlaIII, HindIII, EcoRI or a restriction site sequence)
+    #[clap(
+        long = "pore_c",
+        conflicts_with_all = &["pacbio", "single-cell", "direct-rna"]
+    )]
+    pub pore_c: Option<String>,
+
+    /// Mean number of restriction fragments in a Pore-C concatemer
+    #[clap(long = "contacts", default_value = "4")]
+    pub contacts: f64,
+
+    /// Probability of a cis contact decrease with distance to the power of this value
+    #[clap(long = "contact_decay", default_value = "1")]
+    pub contact_decay: f64,
+
+    /// Percentage of Pore-C contacts between two references
+    #[clap(long = "trans_contacts", default_value = "10")]
+    pub trans_contacts: f64,
+
+    /// Path to a bed of contact domains (TADs) of Pore-C
+    #[clap(long = "domains", requires = "pore-c")]
+    pub domains_path: Option<String>,
+
+    /// Percentage of Pore-C cis contacts between two domains rejected
+    #[clap(long = "domain_insulation", default_value = "50")]
+    pub domain_insulation: f64,
+
+    /// Path to a tsv of strains: name, path to a vcf apply on reference or to a fasta, and proportion
+    #[clap(long = "strains", conflicts_with = "community-path")]
+    pub strains_path: Option<String>,
+
+    /// Path to a tsv of community members: genome fasta path or taxon id (taxid= in reference description), abundance and optional plasmid copy number
+    #[clap(long = "community")]
+    pub community_path: Option<String>,
+
+    /// Unit of community abundance, cell or mass
+    #[clap(long = "abundance_unit", default_value = "cell")]
+    pub abundance_unit: crate::community::Unit,
+
+    /// Draw community abundances from a distribution, lognormal[,sigma] or powerlaw[,alpha]
+    #[clap(long = "abundance_distribution")]
+    pub abundance_distribution: Option<crate::community::Distribution>,
+
+    /// Contaminant fasta mix in reads at a fraction of bases or reads (e.g. lambda.fa:0.5% or human.fa:2%reads), can be repeat
+    #[clap(long = "contaminant")]
+    pub contaminants: Vec<crate::contaminant::Contaminant>,
+
+    /// Prefix of truth files (e.g. structural variants vcf and bed)
+    #[clap(long = "truth_prefix", default_value = "rustyread_truth")]
+    pub truth_prefix: String,
 }
 
 #[cfg(test)]
@@ -250,35 +695,40 @@ mod t {
             Quantity::from_str("50").unwrap(),
             Quantity {
                 coverage: 0,
-                base: Some(50)
+                base: Some(50),
+                reads: None
             }
         );
         assert_eq!(
             Quantity::from_str("50K").unwrap(),
             Quantity {
                 coverage: 0,
-                base: Some(50_000)
+                base: Some(50_000),
+                reads: None
             }
         );
         assert_eq!(
             Quantity::from_str("50M").unwrap(),
             Quantity {
                 coverage: 0,
-                base: Some(50_000_000)
+                base: Some(50_000_000),
+                reads: None
             }
         );
         assert_eq!(
             Quantity::from_str("50G").unwrap(),
             Quantity {
                 coverage: 0,
-                base: Some(50_000_000_000)
+                base: Some(50_000_000_000),
+                reads: None
             }
         );
         assert_eq!(
             Quantity::from_str("50x").unwrap(),
             Quantity {
                 coverage: 50,
-                base: None
+                base: None,
+                reads: None
             }
         );
 
@@ -289,6 +739,47 @@ mod t {
         assert!(Quantity::from_str("bépo50M").is_err());
         assert!(Quantity::from_str("bépo50G").is_err());
         assert!(Quantity::from_str("bépo50x").is_err());
+
+        assert_eq!(
+            Quantity::from_str("50000reads").unwrap(),
+            Quantity {
+                coverage: 0,
+                base: None,
+                reads: Some(50_000)
+            }
+        );
+        assert!(Quantity::from_str("reads").is_err());
+        assert!(Quantity::from_str("béporeads").is_err());
+    }
+
+    #[test]
+    fn parse_reference_quantity() {
+        assert_eq!(
+            ReferenceQuantity::from_str("chr1:30x").unwrap(),
+            ReferenceQuantity {
+                id: "chr1".to_string(),
+                quantity: Quantity::from_str("30x").unwrap()
+            }
+        );
+        assert_eq!(
+            ReferenceQuantity::from_str("HLA:A*01:01:200reads")
+                .unwrap()
+                .id,
+            "HLA:A*01:01"
+        );
+
+        assert!(ReferenceQuantity::from_str("chr1").is_err());
+        assert!(ReferenceQuantity::from_str(":30x").is_err());
+        assert!(ReferenceQuantity::from_str("chr1:30z").is_err());
+
+        assert_eq!(
+            reference_quantities("chr1:30x,plasmid:200x").unwrap(),
+            vec![
+                ReferenceQuantity::from_str("chr1:30x").unwrap(),
+                ReferenceQuantity::from_str("plasmid:200x").unwrap()
+            ]
+        );
+        assert!(reference_quantities("chr1:30x,plasmid").is_err());
     }
 
     #[test]
@@ -307,6 +798,10 @@ mod t {
             50_000_000_000
         );
         assert_eq!(Quantity::from_str("50x").unwrap().number_of_base(50), 2500);
+        assert_eq!(
+            Quantity::from_str("50reads").unwrap().number_of_base(50),
+            u64::MAX
+        );
     }
 
     #[test]
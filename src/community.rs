@@ -0,0 +1,409 @@
+//! Manage metagenomic community
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::{Context, Result};
+use rand::distributions::Distribution as _;
+
+/* local use */
+use crate::error::Community as Error;
+use crate::references::{Reference, References};
+use crate::simulate::description::Label;
+
+/// Abundance of community members is a number of cell or a DNA mass
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Cell,
+    Mass,
+}
+
+impl FromStr for Unit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cell" => Ok(Unit::Cell),
+            "mass" => Ok(Unit::Mass),
+            _ => Err(Error::CantParseUnit),
+        }
+    }
+}
+
+/// Distribution use to draw abundance of community members
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    LogNormal(f64),
+    PowerLaw(f64),
+}
+
+impl FromStr for Distribution {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, param) = match s.split_once(',') {
+            Some((name, param)) => (
+                name,
+                Some(f64::from_str(param).map_err(|_| Error::CantParseDistribution)?),
+            ),
+            None => (s, None),
+        };
+
+        match name {
+            "lognormal" => Ok(Distribution::LogNormal(param.unwrap_or(1.0))),
+            "powerlaw" => Ok(Distribution::PowerLaw(param.unwrap_or(1.0))),
+            _ => Err(Error::CantParseDistribution),
+        }
+    }
+}
+
+impl Distribution {
+    /// Draw an abundance
+    pub fn sample<RNG>(&self, rng: &mut RNG) -> Result<f64>
+    where
+        RNG: rand::Rng,
+    {
+        match self {
+            Distribution::LogNormal(sigma) => Ok(rand_distr::LogNormal::new(0.0, *sigma)
+                .with_context(|| Error::CantParseDistribution)?
+                .sample(rng)),
+            Distribution::PowerLaw(alpha) => Ok(rand_distr::Pareto::new(1.0, *alpha)
+                .with_context(|| Error::CantParseDistribution)?
+                .sample(rng)),
+        }
+    }
+}
+
+/// A member of community, genome is a path to a fasta file or a taxon id
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub genome: String,
+    pub abundance: f64,
+    pub plasmid_copy: f64,
+    pub size: f64,
+}
+
+/// A collection of community members
+#[derive(Debug, Clone, PartialEq)]
+pub struct Community {
+    pub members: Vec<Member>,
+    pub unit: Unit,
+}
+
+impl Community {
+    /// Read community from a tsv, columns are genome, abundance and an optional plasmid copy number
+    pub fn from_stream<R>(input: R, unit: Unit) -> Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let mut members = Vec::new();
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .quoting(false)
+            .comment(Some(b'#'))
+            .from_reader(input);
+
+        for record in reader.records() {
+            let record = record?;
+            if record.len() < 2 || record[0].is_empty() {
+                anyhow::bail!(Error::CommunityParsing);
+            }
+
+            members.push(Member {
+                genome: record[0].to_string(),
+                abundance: f64::from_str(&record[1]).with_context(|| Error::CommunityParsing)?,
+                plasmid_copy: if let Some(copy) = record.get(2) {
+                    f64::from_str(copy).with_context(|| Error::CommunityParsing)?
+                } else {
+                    1.0
+                },
+                size: 0.0,
+            });
+        }
+
+        Ok(Self { members, unit })
+    }
+
+    /// Replace abundance of each member by a value draw in distribution
+    pub fn draw_abundances<RNG>(&mut self, distribution: Distribution, rng: &mut RNG) -> Result<()>
+    where
+        RNG: rand::Rng,
+    {
+        for member in self.members.iter_mut() {
+            member.abundance = distribution.sample(rng)?;
+        }
+
+        Ok(())
+    }
+
+    /// Abundance of each member normalized to sum to 1
+    pub fn relative_abundances(&self) -> Vec<f64> {
+        let total: f64 = self.members.iter().map(|x| x.abundance).sum();
+
+        self.members
+            .iter()
+            .map(|x| {
+                if total > 0.0 {
+                    x.abundance / total
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+
+    /// Expected fraction of base produce by each member
+    pub fn base_fractions(&self) -> Vec<f64> {
+        let bases: Vec<f64> = match self.unit {
+            Unit::Cell => self.members.iter().map(|x| x.abundance * x.size).collect(),
+            Unit::Mass => self.members.iter().map(|x| x.abundance).collect(),
+        };
+        let total: f64 = bases.iter().sum();
+
+        bases
+            .iter()
+            .map(|x| if total > 0.0 { x / total } else { 0.0 })
+            .collect()
+    }
+
+    /// Get sequences of each member and their weight
+    ///
+    /// If member genome is a file sequences are read in it, else sequences with same id or taxid in taxa are used.
+    /// Circular sequences other than the longest sequence of a member are consider as plasmids and their weight is multiply by plasmid copy number.
+    pub fn sequences(&mut self, taxa: &[Reference]) -> Result<(Vec<Reference>, Vec<f64>)> {
+        let mut sequences = Vec::new();
+        let mut weights = Vec::new();
+
+        for member in self.members.iter_mut() {
+            let (mut seqs, mut prob) = if std::path::Path::new(&member.genome).is_file() {
+                References::read_reference(
+                    niffler::get_reader(Box::new(std::io::BufReader::new(
+                        std::fs::File::open(&member.genome)
+                            .with_context(|| "Read community genome")?,
+                    )))
+                    .with_context(|| "Read community genome niffler")?
+                    .0,
+                )?
+            } else {
+                let seqs: Vec<Reference> = taxa
+                    .iter()
+                    .filter(|x| {
+                        x.id == member.genome
+                            || x.tags
+                                .iter()
+                                .any(|(k, v)| *k == "taxid" && *v == member.genome)
+                    })
+                    .cloned()
+                    .collect();
+                let prob = seqs.iter().map(|x| x.seq.len() as f64).collect();
+
+                (seqs, prob)
+            };
+
+            if seqs.is_empty() {
+                anyhow::bail!(Error::MissingGenome(member.genome.clone()));
+            }
+
+            let longest = (0..seqs.len())
+                .max_by_key(|i| seqs[*i].seq.len())
+                .unwrap_or(0);
+            for (i, reference) in seqs.iter().enumerate() {
+                if reference.circular && i != longest {
+                    prob[i] *= member.plasmid_copy;
+                }
+            }
+
+            member.size = prob.iter().sum();
+            let factor = match self.unit {
+                Unit::Cell => member.abundance,
+                Unit::Mass if member.size == 0.0 => {
+                    anyhow::bail!(Error::EmptyGenome(member.genome.clone()))
+                }
+                Unit::Mass => member.abundance / member.size,
+            };
+
+            for (mut reference, weight) in seqs.drain(..).zip(prob) {
+                reference.labels.push(Label::Genome(member.genome.clone()));
+
+                sequences.push(reference);
+                weights.push(weight * factor);
+            }
+        }
+
+        Ok((sequences, weights))
+    }
+
+    /// Write truth abundance table, simulated associate genome to number of reads and bases produce
+    pub fn write_truth<W>(
+        &self,
+        output: &mut W,
+        simulated: &rustc_hash::FxHashMap<String, (u64, u64)>,
+    ) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        let total: u64 = simulated.values().map(|x| x.1).sum();
+
+        writeln!(
+            output,
+            "#genome\tabundance\texpected_base_fraction\treads\tbases\tbase_fraction"
+        )?;
+        for ((member, abundance), fraction) in self
+            .members
+            .iter()
+            .zip(self.relative_abundances())
+            .zip(self.base_fractions())
+        {
+            let (reads, bases) = simulated.get(&member.genome).cloned().unwrap_or((0, 0));
+
+            writeln!(
+                output,
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                member.genome,
+                abundance,
+                fraction,
+                reads,
+                bases,
+                if total > 0 {
+                    bases as f64 / total as f64
+                } else {
+                    0.0
+                }
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    static FASTA: &[u8] = b">chr1 taxid=562
+ACTGACTGACTGACTGACTG
+>plasmid1 circular=true taxid=562
+ACTGACTGAC
+>chr2 taxid=1280
+ACTGACTGACTGACTGACTGACTGACTGACTGACTGACTG
+";
+
+    static TSV: &[u8] = b"#genome\tabundance\tplasmid copy
+562\t3\t10
+1280\t1
+";
+
+    #[test]
+    fn parse() {
+        assert_eq!(Unit::from_str("cell").unwrap(), Unit::Cell);
+        assert_eq!(Unit::from_str("mass").unwrap(), Unit::Mass);
+        assert!(Unit::from_str("bépo").is_err());
+
+        assert_eq!(
+            Distribution::from_str("lognormal").unwrap(),
+            Distribution::LogNormal(1.0)
+        );
+        assert_eq!(
+            Distribution::from_str("powerlaw,2.5").unwrap(),
+            Distribution::PowerLaw(2.5)
+        );
+        assert!(Distribution::from_str("powerlaw,bépo").is_err());
+        assert!(Distribution::from_str("normal").is_err());
+
+        let community = Community::from_stream(TSV, Unit::Cell).unwrap();
+        assert_eq!(
+            community.members,
+            vec![
+                Member {
+                    genome: "562".to_string(),
+                    abundance: 3.0,
+                    plasmid_copy: 10.0,
+                    size: 0.0,
+                },
+                Member {
+                    genome: "1280".to_string(),
+                    abundance: 1.0,
+                    plasmid_copy: 1.0,
+                    size: 0.0,
+                }
+            ]
+        );
+        assert_eq!(community.relative_abundances(), vec![0.75, 0.25]);
+
+        assert!(Community::from_stream(&b"562\n"[..], Unit::Cell).is_err());
+        assert!(Community::from_stream(&b"562\tauie\n"[..], Unit::Cell).is_err());
+    }
+
+    #[test]
+    fn sequences() {
+        let taxa = References::read_reference(FASTA).unwrap().0;
+
+        let mut community = Community::from_stream(TSV, Unit::Cell).unwrap();
+        let (seqs, weights) = community.sequences(&taxa).unwrap();
+
+        assert_eq!(
+            seqs.iter().map(|x| x.id.clone()).collect::<Vec<String>>(),
+            vec!["chr1", "plasmid1", "chr2"]
+        );
+        assert_eq!(seqs[1].tags, vec![("taxid", "562".to_string())]);
+        assert_eq!(seqs[1].labels, vec![Label::Genome("562".to_string())]);
+        assert_eq!(weights, vec![60.0, 300.0, 40.0]);
+        assert_eq!(community.base_fractions(), vec![0.9, 0.1]);
+
+        let mut community = Community::from_stream(TSV, Unit::Mass).unwrap();
+        let (_, weights) = community.sequences(&taxa).unwrap();
+
+        assert_eq!(weights, vec![0.5, 2.5, 1.0]);
+        assert_eq!(community.base_fractions(), vec![0.75, 0.25]);
+
+        let mut community = Community::from_stream(&b"auie\t1\n"[..], Unit::Mass).unwrap();
+        assert!(community.sequences(&taxa).is_err());
+
+        let empty = vec![Reference::new("auie".to_string(), Box::new([]), false)];
+        let mut community = Community::from_stream(&b"auie\t1\n"[..], Unit::Mass).unwrap();
+        assert!(community.sequences(&empty).is_err());
+    }
+
+    #[test]
+    fn draw() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut community = Community::from_stream(TSV, Unit::Cell).unwrap();
+
+        community
+            .draw_abundances(Distribution::LogNormal(1.0), &mut rng)
+            .unwrap();
+        assert!(community.members.iter().all(|x| x.abundance > 0.0));
+
+        community
+            .draw_abundances(Distribution::PowerLaw(1.5), &mut rng)
+            .unwrap();
+        assert!(community.members.iter().all(|x| x.abundance >= 1.0));
+    }
+
+    #[test]
+    fn truth() {
+        let mut community = Community::from_stream(TSV, Unit::Mass).unwrap();
+        community.members[0].size = 30.0;
+        community.members[1].size = 40.0;
+
+        let mut simulated = rustc_hash::FxHashMap::default();
+        simulated.insert("562".to_string(), (2, 300));
+        simulated.insert("1280".to_string(), (1, 100));
+
+        let mut output = Vec::new();
+        community.write_truth(&mut output, &simulated).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "#genome\tabundance\texpected_base_fraction\treads\tbases\tbase_fraction
+562\t0.75\t0.75\t2\t300\t0.75
+1280\t0.25\t0.25\t1\t100\t0.25
+"
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Manage contaminant spike-in
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::{Context, Result};
+
+/* local use */
+use crate::error::Cli as Error;
+use crate::model;
+use crate::references::{Reference, References};
+use crate::simulate::description::Label;
+
+/// Fraction of contaminant is a fraction of reads or of bases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Reads,
+    Bases,
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Unit::Reads => write!(f, "reads"),
+            Unit::Bases => write!(f, "bases"),
+        }
+    }
+}
+
+/// A contaminant fasta mix in reads at a fraction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contaminant {
+    pub path: String,
+    pub label: String,
+    pub fraction: f64,
+    pub unit: Unit,
+}
+
+impl FromStr for Contaminant {
+    type Err = Error;
+
+    /// Parse `path:fraction%[reads|bases]`, by default fraction is a fraction of bases
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (path, spec) = s.rsplit_once(':').ok_or(Error::CantParseContaminant)?;
+
+        let (spec, unit) = if let Some(rest) = spec.strip_suffix("reads") {
+            (rest, Unit::Reads)
+        } else if let Some(rest) = spec.strip_suffix("bases") {
+            (rest, Unit::Bases)
+        } else {
+            (spec, Unit::Bases)
+        };
+
+        let fraction = spec
+            .strip_suffix('%')
+            .and_then(|x| f64::from_str(x).ok())
+            .ok_or(Error::CantParseContaminant)?
+            / 100.0;
+
+        if path.is_empty() || !(0.0..1.0).contains(&fraction) {
+            return Err(Error::CantParseContaminant);
+        }
+
+        let label = std::path::Path::new(path)
+            .file_name()
+            .and_then(|x| x.to_str())
+            .and_then(|x| x.split('.').next())
+            .unwrap_or(path)
+            .to_string();
+
+        Ok(Self {
+            path: path.to_string(),
+            label,
+            fraction,
+            unit,
+        })
+    }
+}
+
+impl Contaminant {
+    /// Read contaminant sequences and their adjusted weight, sequences are tagged with contaminant label
+    pub fn sequences<RNG>(
+        &self,
+        small_plasmid_bias: bool,
+        length_model: &model::Length,
+        rng: &mut RNG,
+    ) -> Result<(Vec<Reference>, Vec<f64>)>
+    where
+        RNG: rand::Rng,
+    {
+        let (mut seqs, prob) = References::read_reference(
+            niffler::get_reader(Box::new(std::io::BufReader::new(
+                std::fs::File::open(&self.path).with_context(|| "Read contaminant file")?,
+            )))
+            .with_context(|| "Read contaminant file niffler")?
+            .0,
+        )?;
+
+        if seqs.is_empty() {
+            anyhow::bail!(Error::CantParseContaminant);
+        }
+
+        let prob = References::adjust_depth(&seqs, prob, small_plasmid_bias, length_model, rng)?;
+
+        for reference in seqs.iter_mut() {
+            reference
+                .labels
+                .push(Label::Contaminant(self.label.clone()));
+        }
+
+        Ok((seqs, prob))
+    }
+}
+
+/// Compute probability to draw a fragment in each contaminant
+///
+/// `main_length` and `lengths` are mean fragment length of main references and of each contaminant.
+/// For a fraction of bases, probability p_k is solve to get p_k * L_k = f_k * E where E is the expected length of a fragment.
+pub fn probabilities(
+    contaminants: &[Contaminant],
+    main_length: f64,
+    lengths: &[f64],
+) -> Result<Vec<f64>> {
+    let (mut reads_fraction, mut reads_length) = (0.0, 0.0);
+    let (mut bases_fraction, mut bases_ratio) = (0.0, 0.0);
+
+    for (contaminant, length) in contaminants.iter().zip(lengths) {
+        match contaminant.unit {
+            Unit::Reads => {
+                reads_fraction += contaminant.fraction;
+                reads_length += contaminant.fraction * length;
+            }
+            Unit::Bases => {
+                bases_fraction += contaminant.fraction;
+                bases_ratio += contaminant.fraction / length;
+            }
+        }
+    }
+
+    let expected = (reads_length + (1.0 - reads_fraction) * main_length)
+        / (1.0 - bases_fraction + main_length * bases_ratio);
+
+    let probabilities: Vec<f64> = contaminants
+        .iter()
+        .zip(lengths)
+        .map(|(contaminant, length)| match contaminant.unit {
+            Unit::Reads => contaminant.fraction,
+            Unit::Bases => contaminant.fraction * expected / length,
+        })
+        .collect();
+
+    if !expected.is_finite() || probabilities.iter().sum::<f64>() >= 1.0 {
+        anyhow::bail!(Error::ContaminantFractionTooHigh);
+    }
+
+    Ok(probabilities)
+}
+
+/// Write truth table of contaminants, simulated associate label to number of reads and bases produce, total is number of reads and bases produce
+pub fn write_truth<W>(
+    output: &mut W,
+    contaminants: &[Contaminant],
+    simulated: &rustc_hash::FxHashMap<String, (u64, u64)>,
+    total: (u64, u64),
+) -> Result<()>
+where
+    W: std::io::Write,
+{
+    writeln!(
+        output,
+        "#contaminant\tpath\tfraction\tunit\treads\tbases\treads_fraction\tbases_fraction"
+    )?;
+
+    for contaminant in contaminants {
+        let (reads, bases) = simulated.get(&contaminant.label).cloned().unwrap_or((0, 0));
+
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            contaminant.label,
+            contaminant.path,
+            contaminant.fraction,
+            contaminant.unit,
+            reads,
+            bases,
+            reads as f64 / total.0.max(1) as f64,
+            bases as f64 / total.1.max(1) as f64,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Contaminant::from_str("data/lambda.fa.gz:0.5%").unwrap(),
+            Contaminant {
+                path: "data/lambda.fa.gz".to_string(),
+                label: "lambda".to_string(),
+                fraction: 0.005,
+                unit: Unit::Bases,
+            }
+        );
+        assert_eq!(
+            Contaminant::from_str("human.fa:10%reads").unwrap(),
+            Contaminant {
+                path: "human.fa".to_string(),
+                label: "human".to_string(),
+                fraction: 0.1,
+                unit: Unit::Reads,
+            }
+        );
+        assert_eq!(
+            Contaminant::from_str("human.fa:10%bases").unwrap().unit,
+            Unit::Bases
+        );
+
+        assert!(Contaminant::from_str("human.fa").is_err());
+        assert!(Contaminant::from_str("human.fa:10").is_err());
+        assert!(Contaminant::from_str("human.fa:auie%").is_err());
+        assert!(Contaminant::from_str("human.fa:100%").is_err());
+        assert!(Contaminant::from_str(":1%").is_err());
+    }
+
+    #[test]
+    fn probability() {
+        let contaminants = vec![
+            Contaminant::from_str("a.fa:10%reads").unwrap(),
+            Contaminant::from_str("b.fa:20%").unwrap(),
+        ];
+
+        let probs = probabilities(&contaminants, 1000.0, &[500.0, 2000.0]).unwrap();
+
+        assert!((probs[0] - 0.1).abs() < 1e-12);
+
+        // check fraction of bases produce by b
+        let main = 1.0 - probs[0] - probs[1];
+        let expected = probs[0] * 500.0 + probs[1] * 2000.0 + main * 1000.0;
+        assert!((probs[1] * 2000.0 / expected - 0.2).abs() < 1e-12);
+
+        assert!(probabilities(
+            &[
+                Contaminant::from_str("a.fa:60%reads").unwrap(),
+                Contaminant::from_str("b.fa:50%reads").unwrap()
+            ],
+            1000.0,
+            &[500.0, 2000.0]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn truth() {
+        let contaminants = vec![Contaminant::from_str("a.fa:10%reads").unwrap()];
+        let mut simulated = rustc_hash::FxHashMap::default();
+        simulated.insert("a".to_string(), (1, 50));
+
+        let mut output = Vec::new();
+        write_truth(&mut output, &contaminants, &simulated, (10, 1000)).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "#contaminant\tpath\tfraction\tunit\treads\tbases\treads_fraction\tbases_fraction
+a\ta.fa\t0.1\treads\t1\t50\t0.1\t0.05
+"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Scale depth of reference intervals by their copy number
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::{Context, Result};
+use rand::distributions::Distribution;
+
+/* local use */
+use crate::error::Variant as Error;
+use crate::references::Reference;
+
+/// Total number of copy of a reference interval
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interval {
+    pub ref_id: String,
+    pub start: usize,
+    pub end: usize,
+    pub copies: usize,
+}
+
+/// Read copy number intervals in bed format, columns are reference, start, end and number of copy
+pub fn from_stream<R>(input: R) -> Result<Vec<Interval>>
+where
+    R: std::io::Read,
+{
+    let mut intervals = Vec::new();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(false)
+        .flexible(true)
+        .quoting(false)
+        .comment(Some(b'#'))
+        .from_reader(input);
+
+    for record in reader.records() {
+        let record = record?;
+        if record.len() < 4 {
+            anyhow::bail!(Error::CopyNumberParsing);
+        }
+
+        let start = usize::from_str(&record[1]).with_context(|| Error::CopyNumberParsing)?;
+        let end = usize::from_str(&record[2]).with_context(|| Error::CopyNumberParsing)?;
+        if end <= start {
+            anyhow::bail!(Error::CopyNumberParsing);
+        }
+
+        intervals.push(Interval {
+            ref_id: record[0].to_string(),
+            start,
+            end,
+            copies: usize::from_str(&record[3]).with_context(|| Error::CopyNumberParsing)?,
+        });
+    }
+
+    Ok(intervals)
+}
+
+/// Relative depth along a sequence, use to choose fragment start
+#[derive(Debug, Clone)]
+pub struct Profile {
+    segments: Vec<(usize, usize)>,
+    dist: rand::distributions::WeightedIndex<f64>,
+    mean: f64,
+}
+
+impl Profile {
+    /// Build profile of a reference, depth of an interval is his number of copy divide by ploidy and depth outside intervals is 1
+    ///
+    /// Intervals of haplotype are lifted in haplotype coordinate, None is return if no interval match reference
+    pub fn new(reference: &Reference, intervals: &[Interval], ploidy: usize) -> Option<Self> {
+        let length = reference.seq.len();
+
+        let mut matched: Vec<(usize, usize, f64)> = intervals
+            .iter()
+            .filter(|x| x.ref_id == reference.id)
+            .filter_map(|x| {
+                let (start, end) = if let Some(haplotype) = &reference.haplotype {
+                    (
+                        haplotype.liftover.unlift(x.start)?,
+                        haplotype.liftover.unlift(x.end)?,
+                    )
+                } else {
+                    (x.start, x.end)
+                };
+
+                Some((
+                    start.min(length),
+                    end.min(length),
+                    x.copies as f64 / ploidy as f64,
+                ))
+            })
+            .filter(|(start, end, _)| start < end)
+            .collect();
+
+        if matched.is_empty() {
+            return None;
+        }
+        matched.sort_by_key(|x| x.0);
+
+        let mut segments = Vec::new();
+        let mut weights = Vec::new();
+        let mut pos = 0;
+        for (start, end, depth) in matched {
+            let start = start.max(pos);
+            if start >= end {
+                continue;
+            }
+
+            if pos < start {
+                segments.push((pos, start));
+                weights.push((start - pos) as f64);
+            }
+            segments.push((start, end));
+            weights.push((end - start) as f64 * depth);
+
+            pos = end;
+        }
+        if pos < length {
+            segments.push((pos, length));
+            weights.push((length - pos) as f64);
+        }
+
+        let mean = weights.iter().sum::<f64>() / length as f64;
+
+        Some(Self {
+            segments,
+            dist: rand::distributions::WeightedIndex::new(&weights).ok()?,
+            mean,
+        })
+    }
+
+    /// Mean depth of sequence
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Choose a position according to depth
+    pub fn sample<RNG>(&self, rng: &mut RNG) -> usize
+    where
+        RNG: rand::Rng,
+    {
+        let (start, end) = self.segments[self.dist.sample(rng)];
+
+        rng.gen_range(start..end)
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn read() {
+        let intervals = from_stream(&b"# cnv\nchrX\t0\t100\t1\nchr1\t10\t20\t6\n"[..]).unwrap();
+
+        assert_eq!(
+            intervals[1],
+            Interval {
+                ref_id: "chr1".to_string(),
+                start: 10,
+                end: 20,
+                copies: 6,
+            }
+        );
+
+        assert!(from_stream(&b"chr1\t10\t20\n"[..]).is_err());
+        assert!(from_stream(&b"chr1\t20\t10\t2\n"[..]).is_err());
+        assert!(from_stream(&b"chr1\t10\t20\t1.5\n"[..]).is_err());
+    }
+
+    #[test]
+    fn profile() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let reference =
+            Reference::new("chr1".to_string(), vec![b'A'; 40].into_boxed_slice(), false);
+        let intervals = vec![
+            Interval {
+                ref_id: "chr1".to_string(),
+                start: 10,
+                end: 20,
+                copies: 6,
+            },
+            Interval {
+                ref_id: "chr1".to_string(),
+                start: 30,
+                end: 40,
+                copies: 0,
+            },
+        ];
+
+        let profile = Profile::new(&reference, &intervals, 2).unwrap();
+        assert_eq!(profile.mean(), 1.25);
+
+        let mut counts = [0; 4];
+        for _ in 0..10_000 {
+            counts[profile.sample(&mut rng) / 10] += 1;
+        }
+        assert_eq!(counts[3], 0);
+        assert!(counts[1] > counts[0] * 2 && counts[1] > counts[2] * 2);
+
+        let other = Reference::new("chr2".to_string(), vec![b'A'; 40].into_boxed_slice(), false);
+        assert!(Profile::new(&other, &intervals, 2).is_none());
+    }
+}
@@ -0,0 +1,26 @@
+//! Amplicon error
+
+/* crate use */
+use thiserror::Error;
+
+/// Enum to manage error polymorphism
+#[derive(Debug, Error)]
+pub enum Amplicon {
+    /// A primer record can't be parsed
+    #[error("We aren't able to parse primer scheme, line must contains reference id, start, end, primer name ending by _LEFT or _RIGHT (with optional alt suffix) and pool, each amplicon must have a left and a right primer")]
+    SchemeParsing,
+
+    /// Primer scheme contains more than two pools
+    #[error("Primer scheme must contains one or two pools")]
+    TooManyPools,
+
+    /// No amplicon could be produce
+    #[error("No amplicon of primer scheme match a reference or all amplicons drop out")]
+    NoAmplicon,
+
+    /// A parameter isn't in valid range
+    #[error(
+        "Amplicon variability must be positive and amplicon dropout must be between 0 and 100"
+    )]
+    ParamOutOfRange,
+}
@@ -0,0 +1,20 @@
+//! Barcode error
+
+/* crate use */
+use thiserror::Error;
+
+/// Enum to manage error polymorphism
+#[derive(Debug, Error)]
+pub enum Barcode {
+    /// A sample sheet record can't be parsed
+    #[error("We aren't able to parse sample sheet record, line must contains barcode name, reference path and quantity")]
+    SampleParsing,
+
+    /// A sample use a barcode absent of kit
+    #[error("Barcode {0} isn't present in barcode kit")]
+    UnknownBarcode(String),
+
+    /// A parameter isn't in valid range
+    #[error("Barcode truncation and unclassified rate must be between 0 and 100")]
+    ParamOutOfRange,
+}
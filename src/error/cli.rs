@@ -6,8 +6,8 @@ use thiserror::Error;
 /// Enum to manage error polymorphism
 #[derive(Debug, Error)]
 pub enum Cli {
-    /// quantity didn't match to pattern \d+\[KMGx\]?
-    #[error("We aren't able to parse quantity, quantity must match with this regex '\\d+[KMGx]?'")]
+    /// quantity didn't match to pattern \d+(\[KMGx\]|reads)?
+    #[error("We aren't able to parse quantity, quantity must match with this regex '\\d+([KMGx]|reads)?'")]
     CantParseQuantity,
 
     /// Cant parse a duo of value
@@ -25,4 +25,48 @@ pub enum Cli {
     /// Small plasmid bias
     #[error("Error: fragment length distribution incompatible with reference lengths - try running with --small_plasmid_bias to avoid this error")]
     SmallPlasmidBias,
+
+    /// Cant parse contaminant
+    #[error("We aren't able to parse contaminant, contaminant must match with 'path:fraction%[reads|bases]' and fraction must be lower than 100%")]
+    CantParseContaminant,
+
+    /// Cant parse divergence
+    #[error("We aren't able to parse divergence, divergence must be a percentage (e.g. 0.5%) or a fraction lower than 1")]
+    CantParseDivergence,
+
+    /// Cant parse PacBio mode
+    #[error("We aren't able to parse PacBio mode, mode must be 'subreads' or 'hifi'")]
+    CantParsePacbio,
+
+    /// Cant parse chimera junction
+    #[error("We aren't able to parse chimera junction, junction must be 'random', 'adjacent' or 'proximal'")]
+    CantParseJunction,
+
+    /// Cant parse library
+    #[error("We aren't able to parse library, library must be 'ligation' or 'rapid'")]
+    CantParseLibrary,
+
+    /// Cant parse shearing
+    #[error("We aren't able to parse shearing, shearing must be 'g-tube', 'needle' or a mean fragment size")]
+    CantParseShearing,
+
+    /// Cant parse size selection
+    #[error("We aren't able to parse size selection, format is min[,max][,sharpness] with min lower than max and sharpness upper or equal to 0")]
+    CantParseSizeSelection,
+
+    /// Cant parse quantity of a reference
+    #[error("We aren't able to parse quantity per reference, it must be a tsv of reference id and quantity or a list of id:quantity separate by comma")]
+    CantParseReferenceQuantity,
+
+    /// A quantity per reference target a reference absent of references
+    #[error("Reference {0} of quantity per reference isn't present in references")]
+    UnknownReference(String),
+
+    /// A quantity in reads is use where only bases are support
+    #[error("Quantity of {0} can't be a number of reads")]
+    QuantityInReads(String),
+
+    /// Sum of contaminant fraction is too high
+    #[error("Sum of contaminant fractions is too high, no read can be generate from reference")]
+    ContaminantFractionTooHigh,
 }
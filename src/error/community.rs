@@ -0,0 +1,28 @@
+//! Community error
+
+/* crate use */
+use thiserror::Error;
+
+/// Enum to manage error polymorphism
+#[derive(Debug, Error)]
+pub enum Community {
+    /// A community record can't be parsed
+    #[error("We aren't able to parse community record, line must contains genome, abundance and optionaly plasmid copy number")]
+    CommunityParsing,
+
+    /// Abundance unit isn't cell or mass
+    #[error("We aren't able to parse abundance unit, unit must be cell or mass")]
+    CantParseUnit,
+
+    /// Abundance distribution isn't lognormal or powerlaw
+    #[error("We aren't able to parse abundance distribution, distribution must match with 'lognormal[,sigma]' or 'powerlaw[,alpha]'")]
+    CantParseDistribution,
+
+    /// No sequence found for a community member
+    #[error("Community member {0} isn't a fasta file and no reference sequence match with it")]
+    MissingGenome(String),
+
+    /// Genome of a community member is empty
+    #[error("Community member {0} genome is empty, abundance by mass can't be convert in number of cell")]
+    EmptyGenome(String),
+}
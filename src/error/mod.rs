@@ -4,21 +4,63 @@
 use thiserror::Error;
 
 /* module declaration */
+pub mod amplicon;
+pub mod barcode;
 pub mod cli;
+pub mod community;
 pub mod model;
+pub mod pore_c;
+pub mod single_cell;
+pub mod transcriptome;
+pub mod variant;
 
 /* reexport for easiest use */
+pub use amplicon::Amplicon;
+pub use barcode::Barcode;
 pub use cli::Cli;
+pub use community::Community;
 pub use model::Model;
+pub use pore_c::PoreC;
+pub use single_cell::SingleCell;
+pub use transcriptome::Transcriptome;
+pub use variant::Variant;
 
 /// Enum to manage error polymorphism
 #[derive(Debug, Error)]
 pub enum Error {
+    /// Error related to amplicon
+    #[error(transparent)]
+    Amplicon(#[from] Amplicon),
+
+    /// Error related to barcode
+    #[error(transparent)]
+    Barcode(#[from] Barcode),
+
     /// Error related to command line interface
     #[error(transparent)]
     Cli(#[from] Cli),
 
+    /// Error related to community
+    #[error(transparent)]
+    Community(#[from] Community),
+
     /// Error related to model
     #[error(transparent)]
     Model(#[from] Model),
+
+    /// Error related to Pore-C
+    #[error(transparent)]
+    PoreC(#[from] PoreC),
+
+    /// Error related to single-cell
+    #[error(transparent)]
+    SingleCell(#[from] SingleCell),
+
+    /// Error related to transcriptome
+    #[error(transparent)]
+    Transcriptome(#[from] Transcriptome),
+
+    /// Error related to variant
+    #[error(transparent)]
+    Variant(#[from] Variant),
 }
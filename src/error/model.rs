@@ -29,4 +29,42 @@ pub enum Model {
     /// Identity model parameter must be upper than 0.0
     #[error("Identity model parameter must be upper than 0.0")]
     IdentityParamMustBeUpperThan0,
+
+    /// Transcript model parameter is out of range
+    #[error(
+        "Transcript model poly-A length must be upper than 0.0 and rates must be between 0 and 100"
+    )]
+    TranscriptParamOutOfRange,
+
+    /// Pass accuracy model parameter is out of range
+    #[error("Pass accuracy qscores and gain must be upper than 0.0 and maximal qscore upper than qscore of one pass")]
+    PassAccuracyParamOutOfRange,
+
+    /// Chimera model parameter is out of range
+    #[error("Mean number of parts of a chimera must be upper or equal to 2 and junction adapter chances must be between 0 and 100")]
+    ChimeraParamOutOfRange,
+
+    /// PCR parameter is out of range
+    #[error("PCR efficiency, error rate and template switching rate must be between 0 and 100 and mean number of reads by duplicate group must be upper or equal to 1")]
+    PcrParamOutOfRange,
+
+    /// Transposase parameter is out of range
+    #[error("Transposase motif must be a non empty IUPAC sequence, insertion bias must be upper or equal to 1 and mean distance between insertions upper than 0.0")]
+    TransposaseParamOutOfRange,
+
+    /// Shearing parameter is out of range
+    #[error("Shearing fragment size must be upper than 0.0")]
+    ShearingParamOutOfRange,
+
+    /// Size selection remove all fragments
+    #[error("Size selection remove all fragments generate by length model")]
+    SizeSelectionTooStrict,
+
+    /// Size selection remove many consecutive fragments
+    #[error("Size selection remove {0} consecutive fragments, check molecule length and size selection parameters")]
+    SizeSelectionNoFragment(usize),
+
+    /// No read is write after many generation steps
+    #[error("{0} consecutive generation steps didn't write any read, check length, filter and quantity parameters")]
+    NoReadWritten(usize),
 }
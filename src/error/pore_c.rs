@@ -0,0 +1,20 @@
+//! Pore-C error
+
+/* crate use */
+use thiserror::Error;
+
+/// Enum to manage error polymorphism
+#[derive(Debug, Error)]
+pub enum PoreC {
+    /// Enzyme isn't a know enzyme or a restriction site
+    #[error("We aren't able to parse enzyme, enzyme must be DpnII, MboI, NlaIII, HindIII, EcoRI or a restriction site sequence")]
+    EnzymeParsing,
+
+    /// A contact domain record can't be parsed
+    #[error("We aren't able to parse contact domain record, line must contains reference id, start and end")]
+    DomainParsing,
+
+    /// A parameter isn't in valid range
+    #[error("Mean number of contacts must be upper or equal to 1, contact decay must be positive and trans contact and domain insulation must be between 0 and 100")]
+    ParamOutOfRange,
+}
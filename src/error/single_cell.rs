@@ -0,0 +1,24 @@
+//! Single-cell error
+
+/* crate use */
+use thiserror::Error;
+
+/// Enum to manage error polymorphism
+#[derive(Debug, Error)]
+pub enum SingleCell {
+    /// A barcode of whitelist isn't valid
+    #[error("We aren't able to parse whitelist, each line must be a barcode of 16 nucleotides")]
+    BarcodeParsing,
+
+    /// A cell expression record can't be parsed
+    #[error("We aren't able to parse cell expression record, line must contains cell barcode, transcript id and expression")]
+    ProfileParsing,
+
+    /// Whitelist is too small
+    #[error("Whitelist contains less barcode than number of cells")]
+    NotEnoughBarcode,
+
+    /// A parameter isn't in valid range
+    #[error("Cell variability must be positive and mean number of reads by UMI must be upper or equal to 1")]
+    ParamOutOfRange,
+}
@@ -0,0 +1,20 @@
+//! Transcriptome error
+
+/* crate use */
+use thiserror::Error;
+
+/// Enum to manage error polymorphism
+#[derive(Debug, Error)]
+pub enum Transcriptome {
+    /// A gtf record can't be parsed
+    #[error("We aren't able to parse gtf record, exon must have a start, an end, a strand and a transcript_id attribute")]
+    GtfParsing,
+
+    /// An expression record can't be parsed
+    #[error("We aren't able to parse expression record, line must contains transcript id and expression")]
+    ExpressionParsing,
+
+    /// No transcript can be build
+    #[error("No transcript can be build from annotation and genome")]
+    NoTranscript,
+}
@@ -0,0 +1,52 @@
+//! Variant error
+
+/* crate use */
+use thiserror::Error;
+
+/// Enum to manage error polymorphism
+#[derive(Debug, Error)]
+pub enum Variant {
+    /// A vcf record can't be parsed
+    #[error("We aren't able to parse vcf record")]
+    VcfParsing,
+
+    /// A vcf record didn't contains genotype
+    #[error("Vcf record {0}:{1} didn't contains a genotype")]
+    VcfMissingGenotype(String, usize),
+
+    /// Structural variant type isn't supported
+    #[error("Structural variant type {0} isn't supported")]
+    UnsupportedSvType(String),
+
+    /// A structural variant vcf record didn't contains required information
+    #[error("Structural variant record {0}:{1} didn't contains required information")]
+    SvMissingInfo(String, usize),
+
+    /// A repeat catalog record can't be parsed
+    #[error("We aren't able to parse repeat catalog record")]
+    RepeatParsing,
+
+    /// A strain record can't be parsed
+    #[error("We aren't able to parse strain record, line must contains name, path and proportion")]
+    StrainParsing,
+
+    /// A copy number record can't be parsed
+    #[error("We aren't able to parse copy number record, line must contains reference, start, end and number of copy")]
+    CopyNumberParsing,
+
+    /// A subclone record can't be parsed
+    #[error("We aren't able to parse subclone record, line must contains name, parent, fraction, small variant vcf, structural variant vcf and copy number bed")]
+    SubcloneParsing,
+
+    /// Parent of a subclone isn't define before it
+    #[error("Parent {0} of subclone isn't define before it")]
+    UnknownParent(String),
+
+    /// Sum of subclone fraction is upper than 1
+    #[error("Sum of subclone cellular fractions is upper than 1")]
+    CellularFractionTooHigh,
+
+    /// Ploidy must be upper than 0
+    #[error("Ploidy must be upper than 0")]
+    PloidyMustBeUpperThan0,
+}
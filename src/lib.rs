@@ -2,15 +2,28 @@
 
 /* module declaration */
 pub mod alignment;
+pub mod amplicon;
+pub mod bam;
+pub mod barcode;
 pub mod cli;
+pub mod community;
+pub mod contaminant;
+pub mod copy_number;
 pub mod error;
 pub mod model;
+pub mod pore_c;
 pub mod references;
 pub mod simulate;
+pub mod single_cell;
+pub mod strain;
+pub mod transcriptome;
+pub mod variant;
 
 /* constant definition */
 const CHIMERA_START_ADAPTER_CHANCE: f64 = 0.25;
 const CHIMERA_END_ADAPTER_CHANCE: f64 = 0.25;
+const FOLDBACK_HAIRPIN_CHANCE: f64 = 0.5;
+const FOLDBACK_HAIRPIN_LENGTH: std::ops::RangeInclusive<usize> = 4..=12;
 
 const NUCS: [u8; 4] = [b'A', b'C', b'T', b'G'];
 
@@ -44,6 +57,22 @@ where
     (0..length).map(|_| random_base(rng)).collect()
 }
 
+#[cfg(test)]
+/// Write content in a temporary file and return his path, use by tests of files which contains path of other files
+pub(crate) fn write_temp_file(name: &str, content: &[u8]) -> String {
+    use std::io::Write;
+
+    let mut path = std::env::temp_dir();
+    path.push(format!("rustyread_{}", name));
+
+    std::fs::File::create(&path)
+        .unwrap()
+        .write_all(content)
+        .unwrap();
+
+    path.to_str().unwrap().to_string()
+}
+
 #[cfg(test)]
 mod t {
     use super::*;
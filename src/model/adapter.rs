@@ -9,13 +9,18 @@ use rand::distributions::Distribution;
 /* local use */
 
 /// Struct to get sequence adapter
+#[derive(Clone)]
 pub struct Adapter {
     start: Vec<u8>,
     end: Vec<u8>,
+    start_barcode: Vec<u8>,
+    end_barcode: Vec<u8>,
     start_rate: f64,
     end_rate: f64,
     start_dist: Option<rand_distr::Beta<f64>>,
     end_dist: Option<rand_distr::Beta<f64>>,
+    chimera_end_chance: f64,
+    chimera_start_chance: f64,
 }
 
 impl Adapter {
@@ -54,14 +59,69 @@ impl Adapter {
         Ok(Self {
             start,
             end,
+            start_barcode: Vec::new(),
+            end_barcode: Vec::new(),
             start_rate,
             end_rate,
             start_dist,
             end_dist,
+            chimera_end_chance: crate::CHIMERA_END_ADAPTER_CHANCE,
+            chimera_start_chance: crate::CHIMERA_START_ADAPTER_CHANCE,
         })
     }
 
+    /// Set chance, in percentage, to get an end adapter and a start adapter at junction of chimera parts
+    pub fn chimera_adapters(mut self, end_chance: f64, start_chance: f64) -> Result<Self> {
+        if !(0.0..=100.0).contains(&end_chance) || !(0.0..=100.0).contains(&start_chance) {
+            anyhow::bail!(crate::error::Model::ChimeraParamOutOfRange);
+        }
+
+        self.chimera_end_chance = end_chance / 100.0;
+        self.chimera_start_chance = start_chance / 100.0;
+
+        Ok(self)
+    }
+
+    /// Chance to get an end adapter at a chimera junction
+    pub fn chimera_end_chance(&self) -> f64 {
+        self.chimera_end_chance
+    }
+
+    /// Chance to get a start adapter at a chimera junction
+    pub fn chimera_start_chance(&self) -> f64 {
+        self.chimera_start_chance
+    }
+
+    /// Copy of model where start barcode is add after start adapter and end barcode before end adapter, barcodes are never lost
+    pub fn with_barcodes(&self, start: Vec<u8>, end: Vec<u8>) -> Self {
+        let mut model = self.clone();
+        model.start_barcode = start;
+        model.end_barcode = end;
+
+        model
+    }
+
     pub fn get_start<RNG>(&self, rng: &mut RNG) -> Vec<u8>
+    where
+        RNG: rand::Rng,
+    {
+        let mut adapter = self.get_partial_start(rng);
+        adapter.extend(&self.start_barcode);
+
+        adapter
+    }
+
+    pub fn get_end<RNG>(&self, rng: &mut RNG) -> Vec<u8>
+    where
+        RNG: rand::Rng,
+    {
+        let mut adapter = self.end_barcode.clone();
+        adapter.extend(self.get_partial_end(rng));
+
+        adapter
+    }
+
+    fn get_partial_start<RNG>(&self, rng: &mut RNG) -> Vec<u8>
     where
         RNG: rand::Rng,
     {
@@ -77,7 +137,7 @@ impl Adapter {
         }
     }
 
-    pub fn get_end<RNG>(&self, rng: &mut RNG) -> Vec<u8>
+    fn get_partial_end<RNG>(&self, rng: &mut RNG) -> Vec<u8>
     where
         RNG: rand::Rng,
     {
@@ -93,7 +153,7 @@ impl Adapter {
     }
 
     pub fn max_len(&self) -> usize {
-        self.start.len() + self.end.len()
+        self.start.len() + self.end.len() + self.start_barcode.len() + self.end_barcode.len()
     }
 }
 
@@ -191,4 +251,49 @@ mod t {
 
         assert_eq!(model.max_len(), 20);
     }
+
+    #[test]
+    fn barcodes() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let model = Adapter::new(
+            b"CGATCACAAA".to_vec(),
+            b"CAACATATGT".to_vec(),
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+        )
+        .unwrap()
+        .with_barcodes(b"TTTT".to_vec(), b"GGGG".to_vec());
+
+        assert_eq!(model.get_start(&mut rng), b"TTTT".to_vec());
+        assert_eq!(model.get_end(&mut rng), b"GGGG".to_vec());
+        assert_eq!(model.max_len(), 28);
+    }
+
+    #[test]
+    fn chimera_adapters() {
+        let model = Adapter::new(b"ACGT".to_vec(), b"TGCA".to_vec(), 0.0, 0.0, 0.0, 0.0).unwrap();
+        assert_eq!(
+            model.chimera_end_chance(),
+            crate::CHIMERA_END_ADAPTER_CHANCE
+        );
+        assert_eq!(
+            model.chimera_start_chance(),
+            crate::CHIMERA_START_ADAPTER_CHANCE
+        );
+
+        let model = model.chimera_adapters(10.0, 80.0).unwrap();
+        assert_eq!(model.chimera_end_chance(), 0.1);
+        assert_eq!(model.chimera_start_chance(), 0.8);
+        assert_eq!(
+            model
+                .with_barcodes(Vec::new(), Vec::new())
+                .chimera_end_chance(),
+            0.1
+        );
+
+        assert!(model.clone().chimera_adapters(101.0, 0.0).is_err());
+        assert!(model.chimera_adapters(0.0, -1.0).is_err());
+    }
 }
@@ -0,0 +1,119 @@
+//! Model to get parts of chimeric reads
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::Result;
+
+/* local use */
+use crate::error::Model;
+
+/* constant definition */
+/// Maximal distance between two parts of a chimera with local proximity junction
+pub const PROXIMITY_DISTANCE: usize = 10_000;
+
+/// How the next part of a chimera is choose
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Junction {
+    /// Part come from a random position of a random reference
+    Random,
+    /// Part begin at end of previous part, on same strand
+    Adjacent,
+    /// Part come from a random strand near previous part
+    Proximal,
+}
+
+impl FromStr for Junction {
+    type Err = crate::error::Cli;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(Junction::Random),
+            "adjacent" => Ok(Junction::Adjacent),
+            "proximal" => Ok(Junction::Proximal),
+            _ => Err(crate::error::Cli::CantParseJunction),
+        }
+    }
+}
+
+/// Struct to get number of parts of a chimera and their junction type, number of parts follow a geometric distribution
+#[derive(Debug, Clone)]
+pub struct Chimera {
+    extension: f64,
+    junction: Junction,
+}
+
+impl Chimera {
+    /// Create model from mean number of parts of a chimera, must be upper or equal to 2
+    pub fn new(parts_mean: f64, junction: Junction) -> Result<Self> {
+        if parts_mean < 2.0 {
+            anyhow::bail!(Model::ChimeraParamOutOfRange);
+        }
+
+        Ok(Self {
+            extension: (parts_mean - 2.0) / (parts_mean - 1.0),
+            junction,
+        })
+    }
+
+    /// Junction type between parts
+    pub fn junction(&self) -> Junction {
+        self.junction
+    }
+
+    /// Return true if a chimera get another part
+    pub fn add_part<RNG>(&self, rng: &mut RNG) -> bool
+    where
+        RNG: rand::Rng,
+    {
+        self.extension != 0.0 && rng.gen_bool(self.extension)
+    }
+}
+
+impl Default for Chimera {
+    fn default() -> Self {
+        Self {
+            extension: 0.0,
+            junction: Junction::Random,
+        }
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn junction() {
+        assert_eq!(Junction::from_str("random").unwrap(), Junction::Random);
+        assert_eq!(Junction::from_str("adjacent").unwrap(), Junction::Adjacent);
+        assert_eq!(Junction::from_str("proximal").unwrap(), Junction::Proximal);
+        assert!(Junction::from_str("bépo").is_err());
+    }
+
+    #[test]
+    fn parts() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        assert!(Chimera::new(1.5, Junction::Random).is_err());
+
+        let model = Chimera::new(2.0, Junction::Adjacent).unwrap();
+        assert_eq!(model.junction(), Junction::Adjacent);
+        assert!((0..100).all(|_| !model.add_part(&mut rng)));
+
+        let model = Chimera::new(4.0, Junction::Random).unwrap();
+        let parts: Vec<usize> = (0..10_000)
+            .map(|_| {
+                let mut parts = 2;
+                while model.add_part(&mut rng) {
+                    parts += 1;
+                }
+                parts
+            })
+            .collect();
+        let mean = parts.iter().sum::<usize>() as f64 / parts.len() as f64;
+        assert!((3.9..4.1).contains(&mean));
+    }
+}
@@ -15,10 +15,15 @@ type Kmer = Vec<u8>;
 type KmerEdit = (Kmer, u64);
 type KmerEditWeight = (Vec<KmerEdit>, Vec<f64>);
 
+/* constant definition */
+/// Fraction of homopolymer insertion or deletion in homopolymer error model
+pub const HOMOPOLYMER_ERROR_RATE: f64 = 0.8;
+
 /// Struct to load and apply error model
 pub struct Error {
     length: usize,
     kmer2alts_edit_prob: Option<rustc_hash::FxHashMap<Kmer, KmerEditWeight>>,
+    homopolymer_rate: f64,
 }
 
 impl Error {
@@ -78,6 +83,7 @@ impl Error {
         Ok(Self {
             length: kmer_length,
             kmer2alts_edit_prob: Some(data),
+            homopolymer_rate: 0.0,
         })
     }
 
@@ -86,6 +92,16 @@ impl Error {
         Self {
             length: k,
             kmer2alts_edit_prob: None,
+            homopolymer_rate: 0.0,
+        }
+    }
+
+    /// Setup a random error model dominated by homopolymer insertion and deletion, like PacBio HiFi
+    pub fn homopolymer(k: usize) -> Self {
+        Self {
+            length: k,
+            kmer2alts_edit_prob: None,
+            homopolymer_rate: HOMOPOLYMER_ERROR_RATE,
         }
     }
 
@@ -101,6 +117,8 @@ impl Error {
             } else {
                 (random_error(kmer, rng), 1)
             }
+        } else if self.homopolymer_rate != 0.0 && rng.gen_bool(self.homopolymer_rate) {
+            (homopolymer_error(kmer, rng), 1)
         } else {
             (random_error(kmer, rng), 1)
         }
@@ -134,6 +152,26 @@ where
     new_kmer
 }
 
+/// Insert or delete a base in homopolymer of a random position of kmer, a single base is never deleted
+pub fn homopolymer_error<RNG>(kmer: &[u8], rng: &mut RNG) -> Kmer
+where
+    RNG: rand::Rng,
+{
+    let error_pos = rng.gen_range(0..kmer.len());
+    let in_run = (error_pos > 0 && kmer[error_pos - 1] == kmer[error_pos])
+        || kmer.get(error_pos + 1) == Some(&kmer[error_pos]);
+
+    let mut new_kmer: Vec<u8> = Vec::with_capacity(kmer.len() + 1);
+    new_kmer.extend(&kmer[0..error_pos]);
+    if !in_run || rng.gen_bool(0.5) {
+        new_kmer.push(kmer[error_pos]);
+        new_kmer.push(kmer[error_pos]);
+    }
+    new_kmer.extend(kmer[error_pos + 1..].iter());
+
+    new_kmer
+}
+
 #[cfg(test)]
 mod t {
     use super::*;
@@ -153,6 +191,20 @@ mod t {
         assert_eq!(b"AAGAAAAA", &random_error(b"AAAAAAA", &mut rng)[..]);
     }
 
+    #[test]
+    fn homopolymer_error_() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            let kmer = homopolymer_error(b"ACCCGTT", &mut rng);
+            let mut runs = kmer.clone();
+            runs.dedup();
+
+            assert_eq!(runs, b"ACGT");
+            assert_eq!(crate::alignment::edit_distance(&kmer, b"ACCCGTT"), 1);
+        }
+    }
+
     static MODEL: &[u8] = b"ACAGTTG,0.25;ACGGTTG,0.25;ACAGG,0.25;";
 
     #[test]
@@ -219,4 +271,21 @@ mod t {
             kmers
         );
     }
+
+    #[test]
+    fn homopolymer() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let model = Error::homopolymer(7);
+
+        let indels = (0..1000)
+            .filter(|_| {
+                let (kmer, _) = model.add_errors_to_kmer(b"AACCGGT", &mut rng);
+                let mut runs = kmer.clone();
+                runs.dedup();
+                runs == b"ACGT"
+            })
+            .count();
+
+        assert!(indels > 800);
+    }
 }
@@ -8,11 +8,13 @@ use rand::distributions::Distribution;
 
 /* local use */
 use crate::error::Model;
+use crate::model::size_selection::SizeSelection;
 
 /// Struct to generate length of fragment
 pub struct Length {
     mean: f64,
     dist: Option<rand_distr::Gamma<f64>>,
+    selection: Option<SizeSelection>,
 }
 
 impl Length {
@@ -31,7 +33,35 @@ impl Length {
             None
         };
 
-        Ok(Self { mean, dist })
+        Ok(Self {
+            mean,
+            dist,
+            selection: None,
+        })
+    }
+
+    /// Mean of length distribution
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Add a size selection, molecules are filter after fragmentation
+    pub fn size_selection(mut self, selection: SizeSelection) -> Self {
+        self.selection = Some(selection);
+        self
+    }
+
+    /// Probability a molecule of this length pass size selection
+    pub fn selection_probability(&self, length: u64) -> f64 {
+        self.selection.map(|x| x.probability(length)).unwrap_or(1.0)
+    }
+
+    /// Check if a molecule of this length pass size selection, without selection rng isn't use
+    pub fn keep<RNG>(&self, length: u64, rng: &mut RNG) -> bool
+    where
+        RNG: rand::Rng,
+    {
+        self.selection.map(|x| x.keep(length, rng)).unwrap_or(true)
     }
 
     /// Get length from model
@@ -61,6 +91,23 @@ mod t {
         assert!(Length::new(1.0, 1.0).is_ok());
     }
 
+    #[test]
+    fn size_selection() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let dist = Length::new(50.0, 20.0).unwrap();
+        assert_eq!(dist.selection_probability(1), 1.0);
+        assert!(dist.keep(1, &mut rng));
+
+        let dist = dist.size_selection(SizeSelection {
+            min: 40.0,
+            max: Some(60.0),
+            sharpness: 0.0,
+        });
+        assert_eq!(dist.selection_probability(39), 0.0);
+        assert_eq!(dist.selection_probability(50), 1.0);
+        assert!(!dist.keep(61, &mut rng));
+    }
+
     #[test]
     fn get_value() {
         let mut rng = rand::rngs::StdRng::seed_from_u64(42);
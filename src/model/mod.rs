@@ -2,16 +2,30 @@
 
 /* module declaration */
 pub mod adapter;
+pub mod chimera;
 pub mod error;
 pub mod glitch;
 pub mod identity;
 pub mod length;
+pub mod pass_accuracy;
 pub mod quality;
+pub mod shearing;
+pub mod size_selection;
+pub mod smrtbell;
+pub mod transcript;
+pub mod transposase;
 
 /* reexport for easiest use */
 pub use adapter::Adapter;
+pub use chimera::Chimera;
 pub use error::Error;
 pub use glitch::Glitch;
 pub use identity::Identity;
 pub use length::Length;
+pub use pass_accuracy::PassAccuracy;
 pub use quality::Quality;
+pub use shearing::Shearing;
+pub use size_selection::SizeSelection;
+pub use smrtbell::Smrtbell;
+pub use transcript::Transcript;
+pub use transposase::Transposase;
@@ -0,0 +1,115 @@
+//! Model to get accuracy of a consensus read from his number of passes
+
+/* standard use */
+
+/* crate use */
+use anyhow::Result;
+use rand::distributions::Distribution;
+
+/* local use */
+use crate::error::Model;
+
+/* constant definition */
+/// Standard deviation of read qscore around accuracy curve
+pub const QSCORE_STDEV: f64 = 2.0;
+
+/// Struct to generate qscore of a consensus read, qscore follow a curve that increase with log2 of number of passes
+pub struct PassAccuracy {
+    first: f64,
+    gain: f64,
+    max: f64,
+    min: f64,
+    noise: rand_distr::Normal<f64>,
+}
+
+impl PassAccuracy {
+    /// Create model, qscore of one pass, gain of qscore each time number of passes double, maximal qscore and minimal qscore of a read
+    pub fn new(first: f64, gain: f64, max: f64, min: f64) -> Result<Self> {
+        if first < 0.0 || gain < 0.0 || max < first || min < 0.0 {
+            anyhow::bail!(Model::PassAccuracyParamOutOfRange);
+        }
+
+        Ok(Self {
+            first,
+            gain,
+            max,
+            min,
+            noise: rand_distr::Normal::new(0.0, QSCORE_STDEV)?,
+        })
+    }
+
+    /// Mean qscore of a read with this number of passes
+    pub fn curve(&self, passes: usize) -> f64 {
+        if passes == 0 {
+            return 0.0;
+        }
+
+        (self.first + self.gain * (passes as f64).log2()).min(self.max)
+    }
+
+    /// Get qscore of a read with this number of passes, None if qscore is lower than minimal qscore
+    pub fn get_qscore<RNG>(&self, passes: usize, rng: &mut RNG) -> Option<f64>
+    where
+        RNG: rand::Rng,
+    {
+        if passes == 0 {
+            return None;
+        }
+
+        let qscore = (self.curve(passes) + self.noise.sample(rng)).clamp(0.0, self.max);
+
+        if qscore < self.min {
+            None
+        } else {
+            Some(qscore)
+        }
+    }
+}
+
+/// Convert a qscore in identity
+pub fn identity(qscore: f64) -> f64 {
+    1.0 - 10_f64.powf(-qscore / 10.0)
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn create() {
+        assert!(PassAccuracy::new(-1.0, 5.0, 40.0, 20.0).is_err());
+        assert!(PassAccuracy::new(10.0, 5.0, 5.0, 20.0).is_err());
+        assert!(PassAccuracy::new(10.0, 5.0, 40.0, 20.0).is_ok());
+    }
+
+    #[test]
+    fn curve() {
+        let model = PassAccuracy::new(10.0, 5.0, 40.0, 20.0).unwrap();
+
+        assert_eq!(model.curve(0), 0.0);
+        assert_eq!(model.curve(1), 10.0);
+        assert_eq!(model.curve(4), 20.0);
+        assert_eq!(model.curve(16), 30.0);
+        assert_eq!(model.curve(1024), 40.0);
+    }
+
+    #[test]
+    fn qscore() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let model = PassAccuracy::new(10.0, 5.0, 40.0, 20.0).unwrap();
+
+        assert_eq!(model.get_qscore(0, &mut rng), None);
+        assert_eq!(model.get_qscore(1, &mut rng), None);
+
+        let qscores: Vec<f64> = (0..1000)
+            .filter_map(|_| model.get_qscore(16, &mut rng))
+            .collect();
+        assert_eq!(qscores.len(), 1000);
+        let mean = qscores.iter().sum::<f64>() / 1000.0;
+        assert!((29.5..30.5).contains(&mean));
+
+        assert!((identity(20.0) - 0.99).abs() < 1e-9);
+        assert!((identity(30.0) - 0.999).abs() < 1e-9);
+    }
+}
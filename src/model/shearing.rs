@@ -0,0 +1,140 @@
+//! Model of molecule shearing, long parent molecules are break in fragments
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::Result;
+use rand::distributions::Distribution;
+
+/* local use */
+use crate::error::Model;
+use crate::model::Length;
+
+/* constant definition */
+/// Mean fragment size of a g-TUBE
+pub const GTUBE_SIZE: f64 = 10_000.0;
+
+/// Mean fragment size of needle shearing
+pub const NEEDLE_SIZE: f64 = 20_000.0;
+
+/// Shearing device, set mean size of fragments
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Device {
+    /// Covaris g-TUBE
+    GTube,
+    /// Pass through a needle
+    Needle,
+    /// Custom mean fragment size
+    Size(f64),
+}
+
+impl Device {
+    /// Mean size of fragments produce by device
+    pub fn size(&self) -> f64 {
+        match self {
+            Device::GTube => GTUBE_SIZE,
+            Device::Needle => NEEDLE_SIZE,
+            Device::Size(size) => *size,
+        }
+    }
+}
+
+impl FromStr for Device {
+    type Err = crate::error::Cli;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "g-tube" | "gtube" => Ok(Device::GTube),
+            "needle" => Ok(Device::Needle),
+            _ => s
+                .parse::<f64>()
+                .map(Device::Size)
+                .map_err(|_| crate::error::Cli::CantParseShearing),
+        }
+    }
+}
+
+/// Struct to generate parent molecules and their breakpoints
+pub struct Shearing {
+    parent: Length,
+    breakpoint: rand_distr::Exp<f64>,
+}
+
+impl Shearing {
+    /// Create model, parent length follow a gamma distribution, distance between breakpoints follow an exponential distribution of mean size
+    pub fn new(parent_mean: f64, parent_stdev: f64, size: f64) -> Result<Self> {
+        if size <= 0.0 {
+            anyhow::bail!(Model::ShearingParamOutOfRange);
+        }
+
+        Ok(Self {
+            parent: Length::new(parent_mean, parent_stdev)?,
+            breakpoint: rand_distr::Exp::new(1.0 / size)?,
+        })
+    }
+
+    /// Get length of a parent molecule
+    pub fn get_parent_length<RNG>(&self, rng: &mut RNG) -> usize
+    where
+        RNG: rand::Rng,
+    {
+        self.parent.get_length(rng) as usize
+    }
+
+    /// Get bounds of fragments of a molecule, fragments are adjacent and cover all molecule
+    pub fn fragments<RNG>(&self, length: usize, rng: &mut RNG) -> Vec<(usize, usize)>
+    where
+        RNG: rand::Rng,
+    {
+        let mut fragments = Vec::new();
+
+        let mut begin = 0;
+        while begin < length {
+            let end = (begin + (self.breakpoint.sample(rng).ceil() as usize).max(1)).min(length);
+            fragments.push((begin, end));
+            begin = end;
+        }
+
+        fragments
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn device() {
+        assert_eq!(Device::from_str("g-tube").unwrap(), Device::GTube);
+        assert_eq!(Device::from_str("needle").unwrap(), Device::Needle);
+        assert_eq!(Device::from_str("5000").unwrap(), Device::Size(5000.0));
+        assert!(Device::from_str("blender").is_err());
+
+        assert_eq!(Device::GTube.size(), GTUBE_SIZE);
+        assert_eq!(Device::Size(42.0).size(), 42.0);
+    }
+
+    #[test]
+    fn param() {
+        assert!(Shearing::new(50_000.0, 10_000.0, 0.0).is_err());
+        assert!(Shearing::new(0.0, 10_000.0, 10_000.0).is_err());
+        assert!(Shearing::new(50_000.0, 10_000.0, 10_000.0).is_ok());
+    }
+
+    #[test]
+    fn fragments() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let model = Shearing::new(50_000.0, 10_000.0, 1_000.0).unwrap();
+
+        assert!(model.fragments(0, &mut rng).is_empty());
+
+        let fragments = model.fragments(100_000, &mut rng);
+        assert_eq!(fragments[0].0, 0);
+        assert_eq!(fragments.last().unwrap().1, 100_000);
+        assert!(fragments.windows(2).all(|x| x[0].1 == x[1].0));
+        assert!(fragments.iter().all(|(begin, end)| begin < end));
+        assert!((80..120).contains(&fragments.len()));
+    }
+}
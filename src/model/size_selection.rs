@@ -0,0 +1,163 @@
+//! Model of size selection of molecules (BluePippin, SRE)
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+
+/* local use */
+
+/// Struct to store size selection parameter, a selection is hard if sharpness is 0 else molecule is keep with a logistic probability
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeSelection {
+    pub min: f64,
+    pub max: Option<f64>,
+    pub sharpness: f64,
+}
+
+impl SizeSelection {
+    /// Probability to keep a molecule of this length
+    pub fn probability(&self, length: u64) -> f64 {
+        let length = length as f64;
+
+        if self.sharpness == 0.0 {
+            if length >= self.min && self.max.map(|max| length <= max).unwrap_or(true) {
+                1.0
+            } else {
+                0.0
+            }
+        } else {
+            let lower = logistic((length - self.min) / self.sharpness);
+            let upper = self
+                .max
+                .map(|max| logistic((max - length) / self.sharpness))
+                .unwrap_or(1.0);
+
+            lower * upper
+        }
+    }
+
+    /// Check if a molecule of this length is keep
+    pub fn keep<RNG>(&self, length: u64, rng: &mut RNG) -> bool
+    where
+        RNG: rand::Rng,
+    {
+        let probability = self.probability(length);
+
+        if probability >= 1.0 {
+            true
+        } else if probability <= 0.0 {
+            false
+        } else {
+            rng.gen_bool(probability)
+        }
+    }
+}
+
+impl FromStr for SizeSelection {
+    type Err = crate::error::Cli;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values: Vec<&str> = s.split(',').collect();
+        if values.is_empty() || values.len() > 3 {
+            return Err(crate::error::Cli::CantParseSizeSelection);
+        }
+
+        let min = values[0]
+            .parse::<f64>()
+            .map_err(|_| crate::error::Cli::CantParseSizeSelection)?;
+        let max = match values.get(1) {
+            Some(value) if !value.is_empty() => Some(
+                value
+                    .parse::<f64>()
+                    .map_err(|_| crate::error::Cli::CantParseSizeSelection)?,
+            ),
+            _ => None,
+        };
+        let sharpness = match values.get(2) {
+            Some(value) => value
+                .parse::<f64>()
+                .map_err(|_| crate::error::Cli::CantParseSizeSelection)?,
+            None => 0.0,
+        };
+
+        if min < 0.0 || sharpness < 0.0 || max.map(|max| max < min).unwrap_or(false) {
+            return Err(crate::error::Cli::CantParseSizeSelection);
+        }
+
+        Ok(Self {
+            min,
+            max,
+            sharpness,
+        })
+    }
+}
+
+fn logistic(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            SizeSelection::from_str("1000").unwrap(),
+            SizeSelection {
+                min: 1000.0,
+                max: None,
+                sharpness: 0.0
+            }
+        );
+        assert_eq!(
+            SizeSelection::from_str("1000,5000").unwrap(),
+            SizeSelection {
+                min: 1000.0,
+                max: Some(5000.0),
+                sharpness: 0.0
+            }
+        );
+        assert_eq!(
+            SizeSelection::from_str("1000,,200").unwrap(),
+            SizeSelection {
+                min: 1000.0,
+                max: None,
+                sharpness: 200.0
+            }
+        );
+
+        assert!(SizeSelection::from_str("").is_err());
+        assert!(SizeSelection::from_str("bépo").is_err());
+        assert!(SizeSelection::from_str("1000,500").is_err());
+        assert!(SizeSelection::from_str("1000,5000,-1").is_err());
+        assert!(SizeSelection::from_str("1000,5000,10,10").is_err());
+    }
+
+    #[test]
+    fn probability() {
+        let hard = SizeSelection::from_str("1000,5000").unwrap();
+        assert_eq!(hard.probability(999), 0.0);
+        assert_eq!(hard.probability(1000), 1.0);
+        assert_eq!(hard.probability(5000), 1.0);
+        assert_eq!(hard.probability(5001), 0.0);
+
+        let soft = SizeSelection::from_str("1000,,100").unwrap();
+        assert!((soft.probability(1000) - 0.5).abs() < 1e-9);
+        assert!(soft.probability(500) < 0.01);
+        assert!(soft.probability(1500) > 0.99);
+    }
+
+    #[test]
+    fn keep() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let soft = SizeSelection::from_str("1000,,100").unwrap();
+
+        let kept = (0..10_000).filter(|_| soft.keep(1000, &mut rng)).count();
+        assert!((4_500..5_500).contains(&kept));
+
+        assert!(!soft.keep(0, &mut rng));
+    }
+}
@@ -0,0 +1,113 @@
+//! Model to get passes of polymerase around a SMRTbell
+
+/* standard use */
+
+/* crate use */
+
+/* local use */
+use crate::model::Length;
+
+/// A pass of polymerase on insert, strand and interval of insert (on reverse complement for '-' strand)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pass {
+    pub strand: char,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Struct to generate passes of polymerase, a SMRTbell is an insert close by two hairpin adapters
+pub struct Smrtbell {
+    adapter: Vec<u8>,
+    polymerase: Length,
+}
+
+impl Smrtbell {
+    /// Create model, length of polymerase read follow polymerase model
+    pub fn new(adapter: Vec<u8>, polymerase: Length) -> Self {
+        Self {
+            adapter,
+            polymerase,
+        }
+    }
+
+    /// Hairpin adapter sequence
+    pub fn adapter(&self) -> &[u8] {
+        &self.adapter
+    }
+
+    /// Get number of passes of a polymerase read around an insert, polymerase read length divide by insert length
+    pub fn get_pass_number<RNG>(&self, insert_length: usize, rng: &mut RNG) -> usize
+    where
+        RNG: rand::Rng,
+    {
+        if insert_length == 0 {
+            return 0;
+        }
+
+        self.polymerase.get_length(rng) as usize / insert_length
+    }
+
+    /// Get passes of a polymerase read around an insert
+    ///
+    /// Polymerase start at a random position of a random strand and read insert, adapter, reverse complement of insert, adapter, etc. until polymerase read length is reach
+    pub fn get_passes<RNG>(&self, insert_length: usize, rng: &mut RNG) -> Vec<Pass>
+    where
+        RNG: rand::Rng,
+    {
+        let mut passes = Vec::new();
+        if insert_length == 0 {
+            return passes;
+        }
+
+        let mut remain = self.polymerase.get_length(rng) as usize;
+        let mut strand = if rng.gen_bool(0.5) { '+' } else { '-' };
+        let mut start = rng.gen_range(0..insert_length);
+
+        while remain > 0 {
+            let end = insert_length.min(start + remain);
+            passes.push(Pass { strand, start, end });
+
+            remain -= end - start;
+            if remain <= self.adapter.len() {
+                break;
+            }
+            remain -= self.adapter.len();
+
+            strand = if strand == '+' { '-' } else { '+' };
+            start = 0;
+        }
+
+        passes
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn passes() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let model = Smrtbell::new(b"ACGT".to_vec(), Length::new(100.0, 0.0).unwrap());
+        assert_eq!(model.adapter(), b"ACGT");
+        assert!(model.get_passes(0, &mut rng).is_empty());
+
+        let passes = model.get_passes(20, &mut rng);
+        let polymerase =
+            passes.iter().map(|x| x.end - x.start).sum::<usize>() + (passes.len() - 1) * 4;
+        assert!((96..=100).contains(&polymerase));
+        assert!(passes[1..passes.len() - 1]
+            .iter()
+            .all(|x| x.start == 0 && x.end == 20));
+        assert!(passes.windows(2).all(|w| w[0].strand != w[1].strand));
+
+        let passes = model.get_passes(1000, &mut rng);
+        assert_eq!(passes.len(), 1);
+        assert_eq!(
+            passes[0].end - passes[0].start,
+            100.min(1000 - passes[0].start)
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! Model to get full-length cDNA or direct RNA molecule of a transcript
+
+/* standard use */
+
+/* crate use */
+use anyhow::Result;
+use rand::distributions::Distribution;
+
+/* local use */
+use crate::error::Model;
+
+/* constant definition */
+/// Length of poly-A tail store after each transcript, length of tail in molecule is lower or equal
+pub const POLY_A_MAX_LENGTH: usize = 300;
+/// Minimal length of an internal poly-A stretch where oligo-dT can prime
+pub const INTRA_PRIMING_MIN_LENGTH: usize = 8;
+
+/// Struct to generate coordinate of a cDNA molecule on a transcript
+pub struct Transcript {
+    poly_a: Option<rand_distr::Normal<f64>>,
+    poly_a_mean: f64,
+    truncation_rate: f64,
+    intra_priming_rate: f64,
+    five_prime_loss: Option<(usize, usize)>,
+    uracil: bool,
+}
+
+impl Transcript {
+    /// Create model from parameter, rates are percentage
+    pub fn new(
+        poly_a_mean: f64,
+        poly_a_stdev: f64,
+        truncation_rate: f64,
+        intra_priming_rate: f64,
+    ) -> Result<Self> {
+        if poly_a_mean < 0.0
+            || poly_a_stdev < 0.0
+            || !(0.0..=100.0).contains(&truncation_rate)
+            || !(0.0..=100.0).contains(&intra_priming_rate)
+        {
+            anyhow::bail!(Model::TranscriptParamOutOfRange);
+        }
+
+        let poly_a = if poly_a_stdev != 0.0 {
+            Some(rand_distr::Normal::new(poly_a_mean, poly_a_stdev)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            poly_a,
+            poly_a_mean,
+            truncation_rate: truncation_rate / 100.0,
+            intra_priming_rate: intra_priming_rate / 100.0,
+            five_prime_loss: None,
+            uracil: false,
+        })
+    }
+
+    /// Switch to direct RNA, a number of base between the two bounds of 5' end are never sequenced and intra-priming is disable
+    ///
+    /// If uracil is true, reads are written with U in place of T
+    pub fn direct_rna(mut self, five_prime_loss: (usize, usize), uracil: bool) -> Result<Self> {
+        if five_prime_loss.0 > five_prime_loss.1 {
+            anyhow::bail!(Model::TranscriptParamOutOfRange);
+        }
+
+        self.intra_priming_rate = 0.0;
+        self.five_prime_loss = Some(five_prime_loss);
+        self.uracil = uracil;
+
+        Ok(self)
+    }
+
+    /// Return true if model simulate direct RNA
+    pub fn is_direct_rna(&self) -> bool {
+        self.five_prime_loss.is_some()
+    }
+
+    /// Maximal number of base of 5' end never sequenced
+    pub fn max_five_prime_loss(&self) -> usize {
+        self.five_prime_loss.map(|x| x.1).unwrap_or(0)
+    }
+
+    /// Get length of poly-A tail
+    pub fn get_poly_a<RNG>(&self, rng: &mut RNG) -> usize
+    where
+        RNG: rand::Rng,
+    {
+        let length = if let Some(dist) = self.poly_a {
+            dist.sample(rng).round()
+        } else {
+            self.poly_a_mean.round()
+        };
+
+        (length.max(0.0) as usize).min(POLY_A_MAX_LENGTH)
+    }
+
+    /// Get begin and end of a molecule on forward strand of a transcript follow by his poly-A tail
+    ///
+    /// Molecule is full-length with a poly-A tail, begin is move if 5' end is truncated and end is set after an internal poly-A stretch in case of intra-priming
+    /// In direct RNA, begin is also move by the 5' end loss
+    pub fn get_molecule<RNG>(&self, seq: &[u8], rng: &mut RNG) -> (usize, usize)
+    where
+        RNG: rand::Rng,
+    {
+        let (begin, end) = self.get_cdna(seq, rng);
+
+        match self.five_prime_loss {
+            Some((min, max)) => ((begin + rng.gen_range(min..=max)).min(end), end),
+            None => (begin, end),
+        }
+    }
+
+    /// Reverse read in 3' to 5' orientation and replace T by U if it's required
+    pub fn rna_read(&self, seq: &mut [u8], qual: &mut [u8]) {
+        seq.reverse();
+        qual.reverse();
+
+        if self.uracil {
+            for base in seq.iter_mut() {
+                match *base {
+                    b'T' => *base = b'U',
+                    b't' => *base = b'u',
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    fn get_cdna<RNG>(&self, seq: &[u8], rng: &mut RNG) -> (usize, usize)
+    where
+        RNG: rand::Rng,
+    {
+        let length = seq.len().saturating_sub(POLY_A_MAX_LENGTH);
+        if length == 0 {
+            return (0, seq.len().min(self.get_poly_a(rng)));
+        }
+
+        let begin = if rng.gen_bool(self.truncation_rate) {
+            rng.gen_range(0..length)
+        } else {
+            0
+        };
+
+        if rng.gen_bool(self.intra_priming_rate) {
+            let sites = intra_priming_sites(&seq[begin..length]);
+            if !sites.is_empty() {
+                return (begin, begin + sites[rng.gen_range(0..sites.len())]);
+            }
+        }
+
+        (begin, length + self.get_poly_a(rng))
+    }
+}
+
+/// Position after each internal poly-A stretch of a sequence
+pub fn intra_priming_sites(seq: &[u8]) -> Vec<usize> {
+    let mut sites = Vec::new();
+
+    let mut run = 0;
+    for (i, base) in seq.iter().enumerate() {
+        if base.eq_ignore_ascii_case(&b'A') {
+            run += 1;
+        } else {
+            if run >= INTRA_PRIMING_MIN_LENGTH {
+                sites.push(i);
+            }
+            run = 0;
+        }
+    }
+
+    sites
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn transcript(seq: &[u8]) -> Vec<u8> {
+        let mut transcript = seq.to_vec();
+        transcript.extend([b'A'; POLY_A_MAX_LENGTH]);
+        transcript
+    }
+
+    #[test]
+    fn create() {
+        assert!(Transcript::new(-1.0, 0.0, 0.0, 0.0).is_err());
+        assert!(Transcript::new(100.0, 0.0, 101.0, 0.0).is_err());
+        assert!(Transcript::new(100.0, 0.0, 0.0, -1.0).is_err());
+
+        let model = Transcript::new(100.0, 0.0, 0.0, 0.0).unwrap();
+        assert!(!model.is_direct_rna());
+        assert!(model.direct_rna((20, 10), false).is_err());
+    }
+
+    #[test]
+    fn poly_a() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let model = Transcript::new(100.0, 0.0, 0.0, 0.0).unwrap();
+        assert_eq!(model.get_poly_a(&mut rng), 100);
+
+        let model = Transcript::new(1000.0, 0.0, 0.0, 0.0).unwrap();
+        assert_eq!(model.get_poly_a(&mut rng), POLY_A_MAX_LENGTH);
+
+        let model = Transcript::new(100.0, 30.0, 0.0, 0.0).unwrap();
+        let lengths: Vec<usize> = (0..1000).map(|_| model.get_poly_a(&mut rng)).collect();
+        let mean = lengths.iter().sum::<usize>() as f64 / 1000.0;
+        assert!((95.0..105.0).contains(&mean));
+    }
+
+    #[test]
+    fn molecule() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let seq = transcript(b"ACTGACTGAAAAAAAAAACTGACTGACTG");
+
+        assert_eq!(intra_priming_sites(&seq[..29]), vec![18]);
+
+        let model = Transcript::new(20.0, 0.0, 0.0, 0.0).unwrap();
+        assert_eq!(model.get_molecule(&seq, &mut rng), (0, 49));
+
+        let model = Transcript::new(20.0, 0.0, 0.0, 100.0).unwrap();
+        assert_eq!(model.get_molecule(&seq, &mut rng), (0, 18));
+
+        let model = Transcript::new(20.0, 0.0, 100.0, 0.0).unwrap();
+        let (begin, end) = model.get_molecule(&seq, &mut rng);
+        assert!(begin < 29);
+        assert_eq!(end, 49);
+    }
+
+    #[test]
+    fn direct_rna() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let seq = transcript(b"ACTGACTGAAAAAAAAAACTGACTGACTGACTGACTGACTG");
+
+        let model = Transcript::new(20.0, 0.0, 0.0, 100.0)
+            .unwrap()
+            .direct_rna((10, 15), true)
+            .unwrap();
+        assert!(model.is_direct_rna());
+        assert_eq!(model.max_five_prime_loss(), 15);
+
+        for _ in 0..100 {
+            let (begin, end) = model.get_molecule(&seq, &mut rng);
+            assert!((10..=15).contains(&begin));
+            assert_eq!(end, 61);
+        }
+
+        let mut read = b"ACTGt".to_vec();
+        let mut qual = b"01234".to_vec();
+        model.rna_read(&mut read, &mut qual);
+        assert_eq!(read, b"uGUCA");
+        assert_eq!(qual, b"43210");
+    }
+}
@@ -0,0 +1,238 @@
+//! Model of library preparation and transposase insertion of rapid kits
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::Result;
+use rand::distributions::Distribution;
+
+/* local use */
+use crate::error::Model;
+
+/* constant definition */
+/// Rapid adapter, add at 5' end of molecules by transposase
+pub const RAPID_ADAPTER: &[u8] = b"GTTTTCGCATTTATCGTGAAACGCTTTCGCGTTTTTCGTGCGCCGCTTCA";
+
+/// Target site preference of MuA transposase
+pub const MUA_MOTIF: &[u8] = b"CYSRG";
+
+/// Library preparation kit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Library {
+    /// Adapters are ligate at both ends of molecules
+    Ligation,
+    /// Transposase cut molecules and attach adapter at 5' end
+    Rapid,
+}
+
+impl FromStr for Library {
+    type Err = crate::error::Cli;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ligation" => Ok(Library::Ligation),
+            "rapid" => Ok(Library::Rapid),
+            _ => Err(crate::error::Cli::CantParseLibrary),
+        }
+    }
+}
+
+/// Struct to generate molecule boundaries by transposase insertion
+pub struct Transposase {
+    motif: Vec<u8>,
+    mismatch_weight: f64,
+    candidate_dist: rand_distr::Exp<f64>,
+}
+
+impl Transposase {
+    /// Create model, motif is an IUPAC sequence, a site match motif is `bias` times more likely than a site without any match, mean is mean distance between two insertions
+    pub fn new(motif: &[u8], bias: f64, mean: f64) -> Result<Self> {
+        if motif.is_empty()
+            || motif.iter().any(|x| iupac_bases(*x).is_none())
+            || bias < 1.0
+            || mean <= 0.0
+        {
+            anyhow::bail!(Model::TransposaseParamOutOfRange);
+        }
+
+        let motif = motif.to_ascii_uppercase();
+        let mismatch_weight = bias.powf(-1.0 / motif.len() as f64);
+
+        // mean acceptance of a candidate site in a random sequence, candidate are closer to keep mean distance between insertions
+        let acceptance: f64 = motif
+            .iter()
+            .map(|x| {
+                let match_prob = iupac_bases(*x).unwrap_or(4) as f64 / 4.0;
+                match_prob + (1.0 - match_prob) * mismatch_weight
+            })
+            .product();
+
+        Ok(Self {
+            motif,
+            mismatch_weight,
+            candidate_dist: rand_distr::Exp::new(1.0 / (mean * acceptance))?,
+        })
+    }
+
+    /// Probability to accept an insertion at this position, motif start at position
+    pub fn insertion_weight(&self, seq: &[u8], pos: usize) -> f64 {
+        self.motif
+            .iter()
+            .enumerate()
+            .map(|(i, code)| match seq.get((pos + i) % seq.len().max(1)) {
+                Some(base) if iupac_match(*code, *base) => 1.0,
+                _ => self.mismatch_weight,
+            })
+            .product()
+    }
+
+    /// Get next insertion after position, None if reference end is reach before on a linear reference
+    pub fn next_insertion<RNG>(
+        &self,
+        seq: &[u8],
+        circular: bool,
+        from: usize,
+        rng: &mut RNG,
+    ) -> Option<usize>
+    where
+        RNG: rand::Rng,
+    {
+        let mut pos = from;
+        // on circular reference search stop after one turn
+        while pos < from + seq.len() {
+            pos += (self.candidate_dist.sample(rng).ceil() as usize).max(1);
+            if (!circular && pos >= seq.len()) || pos >= from + seq.len() {
+                return None;
+            }
+
+            if rng.gen_bool(self.insertion_weight(seq, pos % seq.len())) {
+                return Some(pos);
+            }
+        }
+
+        None
+    }
+
+    /// Get begin and length of a molecule on forward strand, begin is an insertion and end is next insertion or reference end
+    pub fn get_molecule<RNG>(&self, seq: &[u8], circular: bool, rng: &mut RNG) -> (usize, usize)
+    where
+        RNG: rand::Rng,
+    {
+        if seq.is_empty() {
+            return (0, 0);
+        }
+
+        let from = rng.gen_range(0..seq.len());
+        let begin = self
+            .next_insertion(seq, circular, from, rng)
+            .map(|x| x % seq.len())
+            .unwrap_or(from);
+
+        let end = match self.next_insertion(seq, circular, begin, rng) {
+            Some(end) => end,
+            None if circular => begin + seq.len() - 1,
+            None => seq.len(),
+        };
+
+        (begin, end - begin)
+    }
+}
+
+/// Number of bases match by an IUPAC code
+fn iupac_bases(code: u8) -> Option<usize> {
+    match code.to_ascii_uppercase() {
+        b'A' | b'C' | b'G' | b'T' => Some(1),
+        b'R' | b'Y' | b'S' | b'W' | b'K' | b'M' => Some(2),
+        b'B' | b'D' | b'H' | b'V' => Some(3),
+        b'N' => Some(4),
+        _ => None,
+    }
+}
+
+/// Check if base match IUPAC code
+fn iupac_match(code: u8, base: u8) -> bool {
+    let matches: &[u8] = match code {
+        b'A' => b"A",
+        b'C' => b"C",
+        b'G' => b"G",
+        b'T' => b"T",
+        b'R' => b"AG",
+        b'Y' => b"CT",
+        b'S' => b"CG",
+        b'W' => b"AT",
+        b'K' => b"GT",
+        b'M' => b"AC",
+        b'B' => b"CGT",
+        b'D' => b"AGT",
+        b'H' => b"ACT",
+        b'V' => b"ACG",
+        b'N' => b"ACGT",
+        _ => b"",
+    };
+
+    matches.contains(&base.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn library() {
+        assert_eq!(Library::from_str("ligation").unwrap(), Library::Ligation);
+        assert_eq!(Library::from_str("rapid").unwrap(), Library::Rapid);
+        assert!(Library::from_str("pcr").is_err());
+    }
+
+    #[test]
+    fn param() {
+        assert!(Transposase::new(b"", 10.0, 1_000.0).is_err());
+        assert!(Transposase::new(b"CYZRG", 10.0, 1_000.0).is_err());
+        assert!(Transposase::new(MUA_MOTIF, 0.5, 1_000.0).is_err());
+        assert!(Transposase::new(MUA_MOTIF, 10.0, 0.0).is_err());
+        assert!(Transposase::new(MUA_MOTIF, 10.0, 1_000.0).is_ok());
+    }
+
+    #[test]
+    fn weight() {
+        let model = Transposase::new(MUA_MOTIF, 32.0, 1_000.0).unwrap();
+
+        assert_eq!(model.insertion_weight(b"AACTGGGA", 2), 1.0);
+        assert_eq!(model.insertion_weight(b"AActcggA", 2), 1.0);
+        assert!((model.insertion_weight(b"AAAAATAA", 2) - 1.0 / 32.0).abs() < 1e-9);
+        assert!((model.insertion_weight(b"AACTGTGA", 2) - 0.5).abs() < 1e-9);
+        // motif wrap around end of sequence
+        assert_eq!(model.insertion_weight(b"GAGCT", 3), 1.0);
+    }
+
+    #[test]
+    fn molecule() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let seq = crate::random_seq(200_000, &mut rng);
+        let model = Transposase::new(MUA_MOTIF, 32.0, 1_000.0).unwrap();
+
+        let molecules: Vec<(usize, usize)> = (0..2_000)
+            .map(|_| model.get_molecule(&seq, false, &mut rng))
+            .collect();
+
+        assert!(molecules
+            .iter()
+            .all(|(begin, length)| begin + length <= seq.len() && *length != 0));
+
+        let mean = molecules.iter().map(|x| x.1).sum::<usize>() as f64 / molecules.len() as f64;
+        assert!((800.0..1_200.0).contains(&mean));
+
+        // molecules start on motif more often than expected in a random sequence
+        let on_motif = molecules
+            .iter()
+            .filter(|(begin, _)| model.insertion_weight(&seq, *begin) == 1.0)
+            .count() as f64
+            / molecules.len() as f64;
+        assert!(on_motif > 0.03);
+
+        let (begin, length) = model.get_molecule(&seq[..100], true, &mut rng);
+        assert!(begin < 100 && length <= 100);
+    }
+}
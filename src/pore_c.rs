@@ -0,0 +1,402 @@
+//! Simulate Pore-C concatemers, references are digest at restriction sites and restriction fragments in contact are ligate together
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::{Context, Result};
+
+/* local use */
+use crate::error::PoreC as Error;
+use crate::references::References;
+
+/* constant definition */
+/// Minimal distance between two restriction fragments in cis contact
+pub const MIN_CONTACT_DISTANCE: usize = 1_000;
+/// Number of try to find a cis contact before choose a random position of reference
+const MAX_CONTACT_TRY: usize = 10;
+
+/// Restriction site of an enzyme, enzyme could be a name or a site sequence
+pub fn restriction_site(enzyme: &str) -> Result<Vec<u8>> {
+    match enzyme.to_ascii_lowercase().as_str() {
+        "dpnii" | "mboi" => Ok(b"GATC".to_vec()),
+        "nlaiii" => Ok(b"CATG".to_vec()),
+        "hindiii" => Ok(b"AAGCTT".to_vec()),
+        "ecori" => Ok(b"GAATTC".to_vec()),
+        site if !site.is_empty() && site.bytes().all(|x| b"acgt".contains(&x)) => {
+            Ok(site.to_ascii_uppercase().into_bytes())
+        }
+        _ => anyhow::bail!(Error::EnzymeParsing),
+    }
+}
+
+/// Parameters of Pore-C contacts
+pub struct PoreC {
+    site: Vec<u8>,
+    extension: f64,
+    decay: f64,
+    trans: f64,
+    insulation: f64,
+    domains: rustc_hash::FxHashMap<String, Vec<(usize, usize)>>,
+}
+
+impl PoreC {
+    /// Create Pore-C model
+    ///
+    /// Contacts is mean number of restriction fragments in a concatemer, cis contact probability decrease with distance to power decay, trans is percentage of contacts between two references and insulation percentage of cis contacts between two domains rejected
+    pub fn new(
+        site: Vec<u8>,
+        contacts: f64,
+        decay: f64,
+        trans: f64,
+        insulation: f64,
+    ) -> Result<Self> {
+        if contacts < 1.0
+            || decay < 0.0
+            || !(0.0..=100.0).contains(&trans)
+            || !(0.0..=100.0).contains(&insulation)
+        {
+            anyhow::bail!(Error::ParamOutOfRange);
+        }
+
+        Ok(Self {
+            site,
+            extension: (contacts - 1.0) / contacts,
+            decay,
+            trans: trans / 100.0,
+            insulation: insulation / 100.0,
+            domains: rustc_hash::FxHashMap::default(),
+        })
+    }
+
+    /// Read contact domains (TADs) in bed format, columns are reference, start and end
+    pub fn read_domains<R>(&mut self, input: R) -> Result<()>
+    where
+        R: std::io::Read,
+    {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .quoting(false)
+            .comment(Some(b'#'))
+            .from_reader(input);
+
+        for record in reader.records() {
+            let record = record?;
+            if record.len() < 3 {
+                anyhow::bail!(Error::DomainParsing);
+            }
+
+            let start = usize::from_str(&record[1]).with_context(|| Error::DomainParsing)?;
+            let end = usize::from_str(&record[2]).with_context(|| Error::DomainParsing)?;
+            if end <= start {
+                anyhow::bail!(Error::DomainParsing);
+            }
+
+            self.domains
+                .entry(record[0].to_string())
+                .or_default()
+                .push((start, end));
+        }
+
+        for domains in self.domains.values_mut() {
+            domains.sort_unstable();
+        }
+
+        Ok(())
+    }
+
+    /// Digest references at restriction sites
+    pub fn digest(&self, references: &References) -> Digest<'_> {
+        let sites = references
+            .sequences
+            .iter()
+            .map(|reference| {
+                let mut sites = vec![0];
+                sites.extend(
+                    reference
+                        .seq
+                        .windows(self.site.len())
+                        .enumerate()
+                        .filter(|(_, kmer)| kmer.eq_ignore_ascii_case(&self.site))
+                        .map(|(pos, _)| pos)
+                        .filter(|pos| *pos != 0),
+                );
+                sites.push(reference.seq.len());
+
+                sites
+            })
+            .collect();
+
+        let domains = references
+            .sequences
+            .iter()
+            .map(|reference| self.domains.get(&reference.id).cloned().unwrap_or_default())
+            .collect();
+
+        Digest {
+            model: self,
+            sites,
+            domains,
+        }
+    }
+}
+
+/// Restriction fragments and contact domains of each reference
+pub struct Digest<'a> {
+    model: &'a PoreC,
+    sites: Vec<Vec<usize>>,
+    domains: Vec<Vec<(usize, usize)>>,
+}
+
+impl<'a> Digest<'a> {
+    /// Number of restriction fragments of a reference
+    pub fn number_of_fragments(&self, ref_index: usize) -> usize {
+        self.sites[ref_index].len() - 1
+    }
+
+    /// Start and end of a restriction fragment
+    pub fn fragment(&self, ref_index: usize, index: usize) -> (usize, usize) {
+        (
+            self.sites[ref_index][index],
+            self.sites[ref_index][index + 1],
+        )
+    }
+
+    /// Index of restriction fragment contains a position
+    pub fn fragment_at(&self, ref_index: usize, pos: usize) -> usize {
+        let index = self.sites[ref_index].partition_point(|x| *x <= pos);
+
+        (index.max(1) - 1).min(self.number_of_fragments(ref_index) - 1)
+    }
+
+    /// Return true if two positions are in same contact domain
+    pub fn same_domain(&self, ref_index: usize, a: usize, b: usize) -> bool {
+        let domains = &self.domains[ref_index];
+        let domain = |pos: usize| {
+            let index = domains.partition_point(|(start, _)| *start <= pos);
+            (index > 0 && pos < domains[index - 1].1).then(|| index - 1)
+        };
+
+        matches!((domain(a), domain(b)), (Some(x), Some(y)) if x == y)
+    }
+
+    /// Get restriction fragments of a concatemer, reference index, strand and fragment index of each contact
+    ///
+    /// Each fragment is in contact with previous one, with a reference choose at random for trans contacts
+    pub fn concatemer<RNG>(
+        &self,
+        references: &References,
+        rng: &mut RNG,
+    ) -> Vec<(usize, char, usize)>
+    where
+        RNG: rand::Rng,
+    {
+        let mut contacts = Vec::new();
+
+        let (mut ref_index, strand) = references.choose_reference(rng);
+        let mut pos = references.choose_start(ref_index, strand, rng);
+        contacts.push((ref_index, strand, self.fragment_at(ref_index, pos)));
+
+        while rng.gen_bool(self.model.extension) {
+            let (start, end) = self.fragment(ref_index, contacts[contacts.len() - 1].2);
+            let anchor = (start + end) / 2;
+
+            let strand = if rng.gen_bool(self.model.trans) {
+                let (index, strand) = references.choose_reference(rng);
+                ref_index = index;
+                pos = references.choose_start(ref_index, strand, rng);
+                strand
+            } else {
+                pos = self.cis_contact(references, ref_index, anchor, rng);
+                if rng.gen_bool(0.5) {
+                    '+'
+                } else {
+                    '-'
+                }
+            };
+
+            contacts.push((ref_index, strand, self.fragment_at(ref_index, pos)));
+        }
+
+        contacts
+    }
+
+    /// Position in contact with anchor, distance follow a power law and contacts between domains could be rejected
+    fn cis_contact<RNG>(
+        &self,
+        references: &References,
+        ref_index: usize,
+        anchor: usize,
+        rng: &mut RNG,
+    ) -> usize
+    where
+        RNG: rand::Rng,
+    {
+        let length = references.sequences[ref_index].seq.len();
+
+        if length > MIN_CONTACT_DISTANCE {
+            for _ in 0..MAX_CONTACT_TRY {
+                let distance = contact_distance(self.model.decay, length, rng);
+                let pos = if rng.gen_bool(0.5) {
+                    anchor.checked_sub(distance)
+                } else {
+                    Some(anchor + distance).filter(|x| *x < length)
+                };
+
+                if let Some(pos) = pos {
+                    if self.same_domain(ref_index, anchor, pos)
+                        || !rng.gen_bool(self.model.insulation)
+                    {
+                        return pos;
+                    }
+                }
+            }
+        }
+
+        rng.gen_range(0..length)
+    }
+}
+
+/// Distance between two cis contacts, between MIN_CONTACT_DISTANCE and max, with probability proportional to distance power -decay
+pub fn contact_distance<RNG>(decay: f64, max: usize, rng: &mut RNG) -> usize
+where
+    RNG: rand::Rng,
+{
+    let min = MIN_CONTACT_DISTANCE as f64;
+    let max = (max as f64).max(min);
+    let u: f64 = rng.gen();
+
+    let distance = if (decay - 1.0).abs() < f64::EPSILON {
+        min * (max / min).powf(u)
+    } else {
+        let exponent = 1.0 - decay;
+        (min.powf(exponent) + u * (max.powf(exponent) - min.powf(exponent))).powf(1.0 / exponent)
+    };
+
+    distance as usize
+}
+
+/// Truth of a concatemer, reference id and index of each restriction fragment
+pub fn fragments_tag(references: &References, contacts: &[(usize, char, usize)]) -> String {
+    contacts
+        .iter()
+        .map(|(ref_index, _, index)| format!("{}:{}", references.sequences[*ref_index].id, index))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn site() {
+        assert_eq!(restriction_site("DpnII").unwrap(), b"GATC".to_vec());
+        assert_eq!(restriction_site("NlaIII").unwrap(), b"CATG".to_vec());
+        assert_eq!(restriction_site("gaattc").unwrap(), b"GAATTC".to_vec());
+        assert!(restriction_site("bépo").is_err());
+        assert!(restriction_site("").is_err());
+    }
+
+    #[test]
+    fn param() {
+        assert!(PoreC::new(b"GATC".to_vec(), 0.5, 1.0, 10.0, 50.0).is_err());
+        assert!(PoreC::new(b"GATC".to_vec(), 4.0, -1.0, 10.0, 50.0).is_err());
+        assert!(PoreC::new(b"GATC".to_vec(), 4.0, 1.0, 101.0, 50.0).is_err());
+        assert!(PoreC::new(b"GATC".to_vec(), 4.0, 1.0, 10.0, -1.0).is_err());
+    }
+
+    #[test]
+    fn digest() {
+        let references = References::from_stream(std::io::Cursor::new(
+            b">chr1\nGATCAAAAgatcAAGATCAA\n>chr2\nAAAA\n",
+        ))
+        .unwrap();
+        let model = PoreC::new(b"GATC".to_vec(), 4.0, 1.0, 10.0, 50.0).unwrap();
+        let digest = model.digest(&references);
+
+        assert_eq!(digest.number_of_fragments(0), 3);
+        assert_eq!(digest.fragment(0, 0), (0, 8));
+        assert_eq!(digest.fragment(0, 1), (8, 14));
+        assert_eq!(digest.fragment(0, 2), (14, 20));
+        assert_eq!(digest.fragment_at(0, 0), 0);
+        assert_eq!(digest.fragment_at(0, 8), 1);
+        assert_eq!(digest.fragment_at(0, 19), 2);
+
+        assert_eq!(digest.number_of_fragments(1), 1);
+        assert_eq!(digest.fragment(1, 0), (0, 4));
+    }
+
+    #[test]
+    fn domains() {
+        let references =
+            References::from_stream(std::io::Cursor::new(b">chr1\nGATCAAAAGATCAAGATCAA\n"))
+                .unwrap();
+        let mut model = PoreC::new(b"GATC".to_vec(), 4.0, 1.0, 10.0, 50.0).unwrap();
+        model
+            .read_domains(&b"chr1\t10\t15\nchr1\t0\t10\nchr2\t0\t10\n"[..])
+            .unwrap();
+        let digest = model.digest(&references);
+
+        assert!(digest.same_domain(0, 2, 9));
+        assert!(digest.same_domain(0, 10, 14));
+        assert!(!digest.same_domain(0, 9, 10));
+        assert!(!digest.same_domain(0, 16, 17));
+
+        assert!(model.read_domains(&b"chr1\t10\n"[..]).is_err());
+        assert!(model.read_domains(&b"chr1\t10\t5\n"[..]).is_err());
+    }
+
+    #[test]
+    fn distance() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        for decay in [0.0, 1.0, 1.5] {
+            let distances: Vec<usize> = (0..10_000)
+                .map(|_| contact_distance(decay, 1_000_000, &mut rng))
+                .collect();
+            assert!(distances
+                .iter()
+                .all(|x| (MIN_CONTACT_DISTANCE..=1_000_000).contains(x)));
+
+            let short = distances.iter().filter(|x| **x < 10_000).count() as f64 / 10_000.0;
+            match decay as usize {
+                0 => assert!(short < 0.05),
+                _ => assert!(short > 0.3),
+            }
+        }
+    }
+
+    #[test]
+    fn concatemer() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut fasta = b">chr1\n".to_vec();
+        fasta.extend(crate::random_seq(200_000, &mut rng));
+        fasta.extend(b"\n>chr2\n");
+        fasta.extend(crate::random_seq(200_000, &mut rng));
+        fasta.push(b'\n');
+        let references = References::from_stream(std::io::Cursor::new(fasta)).unwrap();
+
+        let model = PoreC::new(b"GATC".to_vec(), 4.0, 1.0, 0.0, 0.0).unwrap();
+        let digest = model.digest(&references);
+
+        let concatemers: Vec<Vec<(usize, char, usize)>> = (0..1_000)
+            .map(|_| digest.concatemer(&references, &mut rng))
+            .collect();
+        let mean = concatemers.iter().map(|x| x.len()).sum::<usize>() as f64 / 1_000.0;
+        assert!((3.7..4.3).contains(&mean));
+
+        for contacts in concatemers.iter() {
+            assert!(contacts.iter().all(|x| x.0 == contacts[0].0));
+            assert!(contacts
+                .iter()
+                .all(|x| x.2 < digest.number_of_fragments(x.0)));
+        }
+
+        let tag = fragments_tag(&references, &[(0, '+', 3), (1, '-', 10)]);
+        assert_eq!(tag, "chr1:3,chr2:10");
+    }
+}
@@ -8,17 +8,27 @@ use anyhow::Result;
 use rand::distributions::Distribution;
 
 /* local use */
+use crate::community;
+use crate::contaminant;
+use crate::copy_number;
 use crate::model;
+use crate::simulate::description::Label;
+use crate::strain;
+use crate::transcriptome;
+use crate::variant;
 
 type Text = Box<[u8]>;
 
 /// Store a reference sequence
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Reference {
     pub id: String,
     pub seq: Text,
     pub revcomp: Text,
     pub circular: bool,
+    pub haplotype: Option<variant::Haplotype>,
+    pub tags: Vec<(&'static str, String)>,
+    pub labels: Vec<Label>,
 }
 
 impl Reference {
@@ -31,14 +41,57 @@ impl Reference {
             seq,
             revcomp,
             circular,
+            haplotype: None,
+            tags: Vec::new(),
+            labels: Vec::new(),
         }
     }
+
+    /// Build a haplotype of this reference by apply edits
+    pub fn haplotype(&self, name: String, edits: &[variant::Edit]) -> Self {
+        let (seq, haplotype) = variant::haplotype::build(name, &self.seq, edits);
+
+        let mut new = Reference::new(self.id.clone(), seq.into_boxed_slice(), self.circular);
+        new.haplotype = Some(haplotype);
+        new.tags = self.tags.clone();
+        new.labels = self.labels.clone();
+
+        new
+    }
+
+    /// Get transcript id of a transcriptome reference
+    pub fn transcript(&self) -> Option<&String> {
+        self.labels.iter().find_map(|x| match x {
+            Label::Transcript(transcript) => Some(transcript),
+            _ => None,
+        })
+    }
+
+    /// Get barcode of sample of reference
+    pub fn barcode(&self) -> Option<&String> {
+        self.labels.iter().find_map(|x| match x {
+            Label::Barcode(barcode) => Some(barcode),
+            _ => None,
+        })
+    }
+}
+
+/// A group of sequences draw with a fixed probability, sequences are store after `offset` in references
+pub struct Spike {
+    pub offset: usize,
+    pub probability: f64,
+    pub dist: rand::distributions::WeightedIndex<f64>,
 }
 
 /// A collections of sequence
 pub struct References {
     pub sequences: Vec<Reference>,
+    pub weights: Vec<f64>,
     pub dist: rand::distributions::WeightedIndex<f64>,
+    pub genome_length: u64,
+    pub spikes: Vec<Spike>,
+    pub profiles: rustc_hash::FxHashMap<usize, copy_number::Profile>,
+    pub stranded: bool,
 }
 
 impl References {
@@ -51,11 +104,9 @@ impl References {
         R: std::io::Read,
     {
         let (seqs, prob) = References::read_reference(input)?;
+        let genome_length = seqs.iter().map(|x| x.seq.len() as u64).sum();
 
-        Ok(Self {
-            sequences: seqs,
-            dist: rand::distributions::WeightedIndex::new(prob)?,
-        })
+        References::build(seqs, prob, genome_length)
     }
 
     /// Same as from_stream but small sequence have increase weighted to fix bias.
@@ -72,27 +123,361 @@ impl References {
         let (seqs, mut prob) = References::read_reference(input)?;
 
         prob = References::adjust_depth(&seqs, prob, small_plasmid_bias, length_model, rng)?;
+        let genome_length = seqs.iter().map(|x| x.seq.len() as u64).sum();
+
+        References::build(seqs, prob, genome_length)
+    }
+
+    /// Build references from members of a community, weight of sequences follow member abundance
+    ///
+    /// Input is a fasta where community members not associate to a fasta file are search
+    pub fn from_community<R, RNG>(
+        community: &mut community::Community,
+        input: Option<R>,
+        small_plasmid_bias: bool,
+        length_model: &model::Length,
+        rng: &mut RNG,
+    ) -> Result<Self>
+    where
+        R: std::io::Read,
+        RNG: rand::Rng,
+    {
+        let taxa = if let Some(input) = input {
+            References::read_reference(input)?.0
+        } else {
+            Vec::new()
+        };
+
+        let (seqs, mut prob) = community.sequences(&taxa)?;
+
+        prob = References::adjust_depth(&seqs, prob, small_plasmid_bias, length_model, rng)?;
+        let genome_length = seqs.iter().map(|x| x.seq.len() as u64).sum();
+
+        References::build(seqs, prob, genome_length)
+    }
+
+    /// Build references from a population of strains, weight of strain sequences follow strain proportion
+    pub fn from_population<R, RNG>(
+        population: &mut strain::Population,
+        input: R,
+        small_plasmid_bias: bool,
+        length_model: &model::Length,
+        rng: &mut RNG,
+    ) -> Result<Self>
+    where
+        R: std::io::Read,
+        RNG: rand::Rng,
+    {
+        let (references, weights) = References::read_reference(input)?;
+        let genome_length = references.iter().map(|x| x.seq.len() as u64).sum();
+
+        let (seqs, mut prob) = population.sequences(&references, &weights)?;
+
+        prob = References::adjust_depth(&seqs, prob, small_plasmid_bias, length_model, rng)?;
+
+        References::build(seqs, prob, genome_length)
+    }
+
+    /// Build references from transcripts of an annotation, input is genome fasta
+    ///
+    /// Weight of transcript is his expression, genome length is sum of transcripts length
+    pub fn from_transcriptome<R>(annotation: &transcriptome::Annotation, input: R) -> Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let (genome, _) = References::read_reference(input)?;
+        let (seqs, prob) = annotation.sequences(&genome)?;
+
+        let genome_length = seqs
+            .iter()
+            .map(|x| {
+                x.seq
+                    .len()
+                    .saturating_sub(model::transcript::POLY_A_MAX_LENGTH) as u64
+            })
+            .sum();
+
+        References::build(seqs, prob, genome_length)
+    }
+
+    /// Merge references of samples, each sample is associate to a number of bases and his sequences are draw to produce this number of bases
+    ///
+    /// Genome length is the sum of samples genome length
+    pub fn from_samples<RNG>(
+        samples: Vec<(References, u64)>,
+        length_model: &model::Length,
+        rng: &mut RNG,
+    ) -> Result<Self>
+    where
+        RNG: rand::Rng,
+    {
+        let mut sequences = Vec::new();
+        let mut weights = Vec::new();
+        let mut genome_length = 0;
+
+        for (references, bases) in samples {
+            let length = References::mean_fragment_length(
+                &references.sequences,
+                &references.dist,
+                length_model,
+                rng,
+            );
+            let reads = bases as f64 / length;
+            let total: f64 = references.weights.iter().sum();
+
+            weights.extend(references.weights.iter().map(|x| x * reads / total));
+            sequences.extend(references.sequences);
+            genome_length += references.genome_length;
+        }
+
+        References::build(sequences, weights, genome_length)
+    }
+
+    /// Replace each reference by haplotypes build by apply edits
+    ///
+    /// Haplotypes keep weight of their reference, genome length isn't change
+    pub fn with_haplotypes(&self, edits: &variant::Edits) -> Result<Self> {
+        let mut sequences = Vec::with_capacity(self.sequences.len() * edits.ploidy());
+        let mut weights = Vec::with_capacity(self.sequences.len() * edits.ploidy());
+
+        self.haplotypes(edits, 1.0, None, &mut sequences, &mut weights)?;
+
+        References::build(sequences, weights, self.genome_length)
+    }
+
+    /// Replace each reference by haplotypes of each clone, clones are associate to a name, a fraction of cell and edits
+    ///
+    /// Haplotypes weight is multiply by clone fraction, haplotypes are tagged with clone name, genome length isn't change
+    pub fn with_clones(&self, clones: &[(String, f64, variant::Edits)]) -> Result<Self> {
+        let mut sequences = Vec::new();
+        let mut weights = Vec::new();
+
+        for (name, fraction, edits) in clones {
+            self.haplotypes(edits, *fraction, Some(name), &mut sequences, &mut weights)?;
+        }
+
+        References::build(sequences, weights, self.genome_length)
+    }
 
+    /// Build haplotypes of each reference and push them and their weight in sequences and weights
+    fn haplotypes(
+        &self,
+        edits: &variant::Edits,
+        fraction: f64,
+        clone: Option<&String>,
+        sequences: &mut Vec<Reference>,
+        weights: &mut Vec<f64>,
+    ) -> Result<()> {
+        let ploidy = edits.ploidy();
+        if ploidy == 0 {
+            anyhow::bail!(crate::error::Variant::PloidyMustBeUpperThan0);
+        }
+
+        for (reference, weight) in self.sequences.iter().zip(self.weights.iter()) {
+            for hap in 0..ploidy {
+                let mut haplotype =
+                    reference.haplotype((hap + 1).to_string(), &edits.get(&reference.id, hap));
+                if let Some(info) = haplotype.haplotype.as_mut() {
+                    info.repeats = edits.repeat_alleles(&reference.id, hap, &info.liftover);
+                }
+                if let Some(name) = clone {
+                    haplotype.tags.push(("clone", name.clone()));
+                }
+
+                weights.push(
+                    fraction * weight * haplotype.seq.len() as f64 / reference.seq.len() as f64,
+                );
+                sequences.push(haplotype);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn build(sequences: Vec<Reference>, weights: Vec<f64>, genome_length: u64) -> Result<Self> {
         Ok(Self {
-            sequences: seqs,
-            dist: rand::distributions::WeightedIndex::new(prob)?,
+            dist: rand::distributions::WeightedIndex::new(&weights)?,
+            sequences,
+            weights,
+            genome_length,
+            spikes: Vec::new(),
+            profiles: rustc_hash::FxHashMap::default(),
+            stranded: false,
         })
     }
 
-    /// Randomly get a reference index and strand according to depth
+    /// Replace weight of sequences, must be call before add spikes
+    pub fn set_weights(&mut self, weights: Vec<f64>) -> Result<()> {
+        self.dist = rand::distributions::WeightedIndex::new(&weights)?;
+        self.weights = weights;
+
+        Ok(())
+    }
+
+    /// Scale depth of reference intervals by their copy number relative to ploidy
+    ///
+    /// Weight of each sequence is multiply by his mean depth and fragment start are choose according to depth, must be call before add spikes
+    pub fn add_copy_numbers(
+        &mut self,
+        intervals: &[copy_number::Interval],
+        ploidy: usize,
+    ) -> Result<()> {
+        if ploidy == 0 {
+            anyhow::bail!(crate::error::Variant::PloidyMustBeUpperThan0);
+        }
+
+        for (index, reference) in self.sequences.iter().enumerate() {
+            if let Some(profile) = copy_number::Profile::new(reference, intervals, ploidy) {
+                self.weights[index] *= profile.mean();
+                self.profiles.insert(index, profile);
+            } else if intervals.iter().any(|x| {
+                x.ref_id == reference.id
+                    && x.copies == 0
+                    && x.start == 0
+                    && x.end
+                        >= reference
+                            .haplotype
+                            .as_ref()
+                            .map(|h| h.ref_length)
+                            .unwrap_or_else(|| reference.seq.len())
+            }) {
+                self.weights[index] = 0.0;
+            }
+        }
+
+        self.dist = rand::distributions::WeightedIndex::new(&self.weights)?;
+
+        Ok(())
+    }
+
+    /// Add contaminant sequences, each contaminant is draw with a probability compute to match his fraction of reads or bases
+    ///
+    /// Contaminant sequences didn't change genome length
+    pub fn add_contaminants<RNG>(
+        &mut self,
+        contaminants: &[contaminant::Contaminant],
+        small_plasmid_bias: bool,
+        length_model: &model::Length,
+        rng: &mut RNG,
+    ) -> Result<()>
+    where
+        RNG: rand::Rng,
+    {
+        let main_length =
+            References::mean_fragment_length(&self.sequences, &self.dist, length_model, rng);
+
+        let mut groups = Vec::with_capacity(contaminants.len());
+        let mut lengths = Vec::with_capacity(contaminants.len());
+        for contaminant in contaminants {
+            let (seqs, weights) = contaminant.sequences(small_plasmid_bias, length_model, rng)?;
+            let dist = rand::distributions::WeightedIndex::new(&weights)?;
+
+            lengths.push(References::mean_fragment_length(
+                &seqs,
+                &dist,
+                length_model,
+                rng,
+            ));
+            groups.push((seqs, weights, dist));
+        }
+
+        let probabilities = contaminant::probabilities(contaminants, main_length, &lengths)?;
+
+        for ((seqs, weights, dist), probability) in groups.into_iter().zip(probabilities) {
+            self.spikes.push(Spike {
+                offset: self.sequences.len(),
+                probability,
+                dist,
+            });
+            self.sequences.extend(seqs);
+            self.weights.extend(weights);
+        }
+
+        Ok(())
+    }
+
+    /// Randomly get a reference index and strand according to depth, strand is always forward if references are stranded
     pub fn choose_reference<RNG>(&self, rng: &mut RNG) -> (usize, char)
     where
         RNG: rand::Rng,
     {
+        if self.stranded {
+            return (self.choose_index(rng), '+');
+        }
+
         match ['+', '-'][rng.gen_range(0..=1) as usize] {
-            '+' => (self.dist.sample(rng), '+'),
-            '-' => (self.dist.sample(rng), '-'),
+            '+' => (self.choose_index(rng), '+'),
+            '-' => (self.choose_index(rng), '-'),
             _ => unreachable!(),
         }
     }
 
+    /// Randomly get start of a fragment on a strand of a reference, according to reference depth profile if it's set
+    pub fn choose_start<RNG>(&self, index: usize, strand: char, rng: &mut RNG) -> usize
+    where
+        RNG: rand::Rng,
+    {
+        let length = self.sequences[index].seq.len();
+
+        match self.profiles.get(&index) {
+            Some(profile) if strand == '-' => length - 1 - profile.sample(rng),
+            Some(profile) => profile.sample(rng),
+            None => rng.gen_range(0..length),
+        }
+    }
+
+    /// Randomly get a reference index, in main sequences or in a spike
+    fn choose_index<RNG>(&self, rng: &mut RNG) -> usize
+    where
+        RNG: rand::Rng,
+    {
+        if !self.spikes.is_empty() {
+            let mut value: f64 = rng.gen();
+
+            for spike in self.spikes.iter() {
+                if value < spike.probability {
+                    return spike.offset + spike.dist.sample(rng);
+                }
+                value -= spike.probability;
+            }
+        }
+
+        self.dist.sample(rng)
+    }
+
+    /// Estimate mean length of fragment generate from sequences, fragment is truncated at end of linear sequence and circular sequence shorter than fragment are skip
+    fn mean_fragment_length<RNG>(
+        sequences: &[Reference],
+        dist: &rand::distributions::WeightedIndex<f64>,
+        model: &model::Length,
+        rng: &mut RNG,
+    ) -> f64
+    where
+        RNG: rand::Rng,
+    {
+        let mut total = 0;
+        let mut count = 0;
+
+        for _ in 0..10_000 {
+            let length = model.get_length(rng);
+
+            for _ in 0..100 {
+                let reference = &sequences[dist.sample(rng)];
+                let ref_len = reference.seq.len() as u64;
+
+                if !reference.circular || length < ref_len {
+                    total += length.min(ref_len);
+                    count += 1;
+                    break;
+                }
+            }
+        }
+
+        total as f64 / count.max(1) as f64
+    }
+
     /// Adjust depth of reference to fix bias in small sequence representation
-    fn adjust_depth<RNG>(
+    pub(crate) fn adjust_depth<RNG>(
         sequences: &[Reference],
         mut weight: Vec<f64>,
         small_plasmid_bias: bool,
@@ -103,28 +488,41 @@ impl References {
         RNG: rand::Rng,
     {
         let lengths: Vec<u64> = (0..100_000).map(|_| model.get_length(rng)).collect();
-        let total: u64 = lengths.iter().sum();
+        // bases of molecules pass size selection
+        let selected = |length: u64| length as f64 * model.selection_probability(length);
+        let total: f64 = lengths.iter().map(|x| selected(*x)).sum();
 
         for (i, reference) in sequences.iter().enumerate() {
-            if !small_plasmid_bias && reference.circular {
-                let passing: u64 = lengths
+            let ref_len = reference.seq.len() as u64;
+
+            let passing: f64 = if !small_plasmid_bias && reference.circular {
+                let passing = lengths
                     .iter()
-                    .filter(|x| x <= &&(reference.seq.len() as u64))
+                    .filter(|x| x <= &&ref_len)
+                    .map(|x| selected(*x))
                     .sum();
-                if passing == 0 {
+                if passing == 0.0 && lengths.iter().all(|x| x > &ref_len) {
                     anyhow::bail!(crate::error::Cli::SmallPlasmidBias);
                 }
 
-                weight[i] *= total as f64 / passing as f64;
-            }
-
-            if !reference.circular {
-                let passing: u64 = lengths
+                passing
+            } else if !reference.circular {
+                lengths
                     .iter()
-                    .map(|x| u64::min(*x, reference.seq.len() as u64))
-                    .sum();
+                    .map(|x| selected(u64::min(*x, ref_len)))
+                    .sum()
+            } else {
+                total
+            };
 
-                weight[i] *= total as f64 / passing as f64;
+            if passing == 0.0 {
+                log::warn!(
+                    "No molecule of reference {} pass size selection, reference is ignored",
+                    reference.id
+                );
+                weight[i] = 0.0;
+            } else {
+                weight[i] *= total / passing;
             }
         }
 
@@ -132,7 +530,9 @@ impl References {
     }
 
     /// Read reference from stream
-    fn read_reference<R>(input: R) -> Result<(Vec<Reference>, Vec<f64>)>
+    ///
+    /// If sequence contains 'taxid=(\\S+)' in his description, sequence is tagged with this taxon id
+    pub(crate) fn read_reference<R>(input: R) -> Result<(Vec<Reference>, Vec<f64>)>
     where
         R: std::io::Read,
     {
@@ -141,6 +541,7 @@ impl References {
         let mut records = bio::io::fasta::Reader::new(input).records();
 
         let weight_re = regex::Regex::new(r"depth=([\d.]+)").unwrap(); // we ignore result this regex is static
+        let taxid_re = regex::Regex::new(r"taxid=(\S+)").unwrap(); // we ignore result this regex is static
 
         while let Some(Ok(record)) = records.next() {
             let weight = if let Some(d) = record.desc() {
@@ -159,11 +560,12 @@ impl References {
                 false
             };
 
-            me_seq.push(Reference::new(
-                record.id().into(),
-                record.seq().into(),
-                circular,
-            ));
+            let mut reference = Reference::new(record.id().into(), record.seq().into(), circular);
+            if let Some(c) = record.desc().and_then(|d| taxid_re.captures(d)) {
+                reference.tags.push(("taxid", c[1].to_string()));
+            }
+
+            me_seq.push(reference);
             me_pro.push(weight * record.seq().len() as f64);
         }
 
@@ -176,7 +578,7 @@ mod t {
     use super::*;
     use rand::SeedableRng;
 
-    static FASTA: &'static [u8] = b">random_seq_0
+    static FASTA: &[u8] = b">random_seq_0
 TCCTAACGTG
 >random_seq_1 depth=1.5
 TCACGATTAC
@@ -210,60 +612,90 @@ TCCCGCTGTC
                     seq: Box::new([84, 67, 67, 84, 65, 65, 67, 71, 84, 71]),
                     revcomp: Box::new([67, 65, 67, 71, 84, 84, 65, 71, 71, 65]),
                     circular: false,
+                    haplotype: None,
+                    tags: Vec::new(),
+                    labels: Vec::new(),
                 },
                 Reference {
                     id: "random_seq_1".to_string(),
                     seq: Box::new([84, 67, 65, 67, 71, 65, 84, 84, 65, 67]),
                     revcomp: Box::new([71, 84, 65, 65, 84, 67, 71, 84, 71, 65]),
                     circular: false,
+                    haplotype: None,
+                    tags: Vec::new(),
+                    labels: Vec::new(),
                 },
                 Reference {
                     id: "random_seq_2".to_string(),
                     seq: Box::new([67, 67, 84, 65, 84, 67, 67, 71, 65, 84]),
                     revcomp: Box::new([65, 84, 67, 71, 71, 65, 84, 65, 71, 71]),
                     circular: true,
+                    haplotype: None,
+                    tags: Vec::new(),
+                    labels: Vec::new(),
                 },
                 Reference {
                     id: "random_seq_3".to_string(),
                     seq: Box::new([84, 71, 67, 65, 65, 71, 65, 84, 67, 65]),
                     revcomp: Box::new([84, 71, 65, 84, 67, 84, 84, 71, 67, 65]),
                     circular: false,
+                    haplotype: None,
+                    tags: Vec::new(),
+                    labels: Vec::new(),
                 },
                 Reference {
                     id: "random_seq_4".to_string(),
                     seq: Box::new([84, 65, 71, 67, 67, 71, 84, 71, 71, 84]),
                     revcomp: Box::new([65, 67, 67, 65, 67, 71, 71, 67, 84, 65]),
                     circular: false,
+                    haplotype: None,
+                    tags: Vec::new(),
+                    labels: Vec::new(),
                 },
                 Reference {
                     id: "random_seq_5".to_string(),
                     seq: Box::new([67, 71, 67, 84, 84, 84, 71, 84, 71, 65]),
                     revcomp: Box::new([84, 67, 65, 67, 65, 65, 65, 71, 67, 71]),
                     circular: false,
+                    haplotype: None,
+                    tags: Vec::new(),
+                    labels: Vec::new(),
                 },
                 Reference {
                     id: "random_seq_6".to_string(),
                     seq: Box::new([67, 65, 67, 65, 84, 71, 71, 71, 67, 71]),
                     revcomp: Box::new([67, 71, 67, 67, 67, 65, 84, 71, 84, 71]),
                     circular: true,
+                    haplotype: None,
+                    tags: Vec::new(),
+                    labels: Vec::new(),
                 },
                 Reference {
                     id: "random_seq_7".to_string(),
                     seq: Box::new([65, 84, 67, 84, 65, 65, 84, 71, 67, 71]),
                     revcomp: Box::new([67, 71, 67, 65, 84, 84, 65, 71, 65, 84]),
                     circular: false,
+                    haplotype: None,
+                    tags: Vec::new(),
+                    labels: Vec::new(),
                 },
                 Reference {
                     id: "random_seq_8".to_string(),
                     seq: Box::new([67, 71, 71, 65, 65, 67, 84, 67, 65, 71]),
                     revcomp: Box::new([67, 84, 71, 65, 71, 84, 84, 67, 67, 71]),
                     circular: true,
+                    haplotype: None,
+                    tags: Vec::new(),
+                    labels: Vec::new(),
                 },
                 Reference {
                     id: "random_seq_9".to_string(),
                     seq: Box::new([84, 67, 67, 67, 71, 67, 84, 71, 84, 67]),
                     revcomp: Box::new([71, 65, 67, 65, 71, 67, 71, 71, 71, 65]),
                     circular: false,
+                    haplotype: None,
+                    tags: Vec::new(),
+                    labels: Vec::new(),
                 }
             ]
         );
@@ -293,6 +725,34 @@ TCCCGCTGTC
         );
     }
 
+    #[test]
+    fn samples() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let length = model::Length::new(5.0, 1.0).unwrap();
+
+        let first = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let second = References::from_stream(&b">other\nACTGACTGACTGACTGACTG\n"[..]).unwrap();
+
+        let refs = References::from_samples(vec![(first, 1000), (second, 3000)], &length, &mut rng)
+            .unwrap();
+        assert_eq!(refs.sequences.len(), 11);
+        assert_eq!(refs.genome_length, 120);
+
+        let count = (0..10_000)
+            .filter(|_| refs.choose_reference(&mut rng).0 == 10)
+            .count();
+        assert!((7_000..8_000).contains(&count));
+    }
+
+    #[test]
+    fn get_reference_stranded() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        refs.stranded = true;
+
+        assert!((0..100).all(|_| refs.choose_reference(&mut rng).1 == '+'));
+    }
+
     #[test]
     fn read_reference_adjust() {
         let mut rng = rand::rngs::StdRng::seed_from_u64(42);
@@ -323,4 +783,117 @@ TCCCGCTGTC
             seqs
         );
     }
+
+    #[test]
+    fn contaminants() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let mut refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+
+        refs.add_contaminants(
+            &[contaminant::Contaminant::from_str("tests/data/ref_100000.fasta:10%reads").unwrap()],
+            false,
+            &length,
+            &mut rng,
+        )
+        .unwrap();
+
+        assert_eq!(refs.sequences.len(), 11);
+        assert_eq!(refs.genome_length, 100);
+        assert_eq!(refs.spikes.len(), 1);
+        assert_eq!(refs.spikes[0].offset, 10);
+        assert_eq!(refs.spikes[0].probability, 0.1);
+        assert_eq!(
+            refs.sequences[10].labels,
+            vec![Label::Contaminant("ref_100000".to_string())]
+        );
+
+        let nb_contaminant = (0..10_000)
+            .filter(|_| refs.choose_reference(&mut rng).0 == 10)
+            .count();
+        assert!((900..1100).contains(&nb_contaminant));
+    }
+
+    #[test]
+    fn haplotypes() {
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let variants = vec![
+            variant::Variant {
+                ref_id: "random_seq_0".to_string(),
+                pos: 2,
+                reference: b"C".to_vec(),
+                alternatives: vec![b"A".to_vec()],
+                genotype: vec![1, 0],
+            },
+            variant::Variant {
+                ref_id: "random_seq_0".to_string(),
+                pos: 6,
+                reference: b"C".to_vec(),
+                alternatives: vec![b"CTTTTT".to_vec()],
+                genotype: vec![0, 1],
+            },
+        ];
+
+        let mut edits = variant::Edits::new(2);
+        edits.add_variants(&variants);
+
+        let haps = refs.with_haplotypes(&edits).unwrap();
+
+        assert_eq!(haps.sequences.len(), 20);
+        assert_eq!(haps.genome_length, 100);
+
+        assert_eq!(&haps.sequences[0].seq[..], b"TCATAACGTG");
+        assert_eq!(&haps.sequences[1].seq[..], b"TCCTAACTTTTTGTG");
+        assert_eq!(haps.sequences[1].id, "random_seq_0");
+        assert_eq!(haps.sequences[1].haplotype.as_ref().unwrap().name, "2");
+        assert_eq!(&haps.sequences[2].seq[..], b"TCACGATTAC");
+        assert_eq!(&haps.sequences[3].seq[..], b"TCACGATTAC");
+
+        assert_eq!(haps.weights[0], 10.0);
+        assert_eq!(haps.weights[1], 15.0);
+        assert_eq!(haps.weights[2], 15.0);
+
+        assert!(References::from_stream(std::io::Cursor::new(FASTA))
+            .unwrap()
+            .with_haplotypes(&variant::Edits::new(0))
+            .is_err());
+    }
+
+    #[test]
+    fn copy_numbers() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+
+        refs.add_copy_numbers(
+            &[
+                copy_number::Interval {
+                    ref_id: "random_seq_0".to_string(),
+                    start: 0,
+                    end: 5,
+                    copies: 6,
+                },
+                copy_number::Interval {
+                    ref_id: "random_seq_3".to_string(),
+                    start: 0,
+                    end: 10,
+                    copies: 0,
+                },
+            ],
+            2,
+        )
+        .unwrap();
+
+        assert_eq!(refs.weights[0], 20.0);
+        assert_eq!(refs.weights[3], 0.0);
+        assert_eq!(refs.profiles.len(), 1);
+
+        assert!((0..1000).all(|_| refs.choose_reference(&mut rng).0 != 3));
+        let starts: Vec<usize> = (0..1000)
+            .map(|_| refs.choose_start(0, '+', &mut rng))
+            .collect();
+        assert!(starts.iter().filter(|x| **x < 5).count() > 650);
+        assert!((0..1000).all(|_| refs.choose_start(0, '-', &mut rng) < 10));
+
+        assert!(refs.add_copy_numbers(&[], 0).is_err());
+    }
 }
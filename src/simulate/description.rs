@@ -14,6 +14,87 @@ pub enum ReadType {
     Random,
 }
 
+/// Read of a duplex pair, template and complement store name of other read of pair when it's write
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DuplexRead {
+    Template(Option<String>),
+    Complement(Option<String>),
+    Consensus,
+}
+
+impl DuplexRead {
+    /// Name of other read of pair
+    pub fn pair(&self) -> Option<&str> {
+        match self {
+            DuplexRead::Template(pair) | DuplexRead::Complement(pair) => pair.as_deref(),
+            DuplexRead::Consensus => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DuplexRead {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DuplexRead::Template(_) => write!(f, "template"),
+            DuplexRead::Complement(_) => write!(f, "complement"),
+            DuplexRead::Consensus => write!(f, "consensus"),
+        }
+    }
+}
+
+/// Label of a reference or a read, read is count in truth of group of his labels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Label {
+    Genome(String),
+    Contaminant(String),
+    Transcript(String),
+    Gene(String),
+    Cell(String),
+    Umi(String),
+    Barcode(String),
+    Amplicon(String),
+}
+
+impl std::fmt::Display for Label {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Label::Genome(genome) => write!(f, "genome={}", genome),
+            Label::Contaminant(label) => write!(f, "contaminant={}", label),
+            Label::Transcript(transcript) => write!(f, "transcript={}", transcript),
+            Label::Gene(gene) => write!(f, "gene={}", gene),
+            Label::Cell(cell) => write!(f, "cell={}", cell),
+            Label::Umi(umi) => write!(f, "umi={}", umi),
+            Label::Barcode(barcode) => write!(f, "barcode={}", barcode),
+            Label::Amplicon(amplicon) => write!(f, "amplicon={}", amplicon),
+        }
+    }
+}
+
+/// Foldback part of a read, reverse complement of fragment end after an optional hairpin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Foldback {
+    /// Length of reverse complement part
+    pub length: usize,
+    /// Length of hairpin, set when read is generate
+    pub hairpin: Option<usize>,
+}
+
+/// Polymerase error of a PCR copy, position in fragment and shift of base, new base is set when error is apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcrError {
+    pub position: usize,
+    pub shift: u8,
+    pub base: Option<u8>,
+}
+
+/// Store position of a haplotype fragment on original reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lifted {
+    pub haplotype: String,
+    pub start: usize,
+    pub end: usize,
+}
+
 /// Store information about origin of read
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Origin {
@@ -22,6 +103,7 @@ pub struct Origin {
     pub start: usize,
     pub end: usize,
     pub read_type: ReadType,
+    pub lifted: Option<Lifted>,
 }
 
 impl Origin {
@@ -32,6 +114,7 @@ impl Origin {
             start,
             end,
             read_type: ReadType::Real,
+            lifted: None,
         }
     }
 
@@ -42,6 +125,7 @@ impl Origin {
             start: 0,
             end: length,
             read_type: ReadType::Junk,
+            lifted: None,
         }
     }
 
@@ -52,6 +136,7 @@ impl Origin {
             start: 0,
             end: length,
             read_type: ReadType::Random,
+            lifted: None,
         }
     }
 }
@@ -61,11 +146,18 @@ impl std::fmt::Display for Origin {
         match self.read_type {
             ReadType::Junk => write!(f, "junk_seq"),
             ReadType::Random => write!(f, "random_seq"),
-            ReadType::Real => write!(
-                f,
-                "{},{}strand,{}-{}",
-                self.ref_id, self.strand, self.start, self.end
-            ),
+            ReadType::Real => match &self.lifted {
+                Some(lifted) => write!(
+                    f,
+                    "{},{}strand,{}-{},haplotype={}",
+                    self.ref_id, self.strand, lifted.start, lifted.end, lifted.haplotype
+                ),
+                None => write!(
+                    f,
+                    "{},{}strand,{}-{}",
+                    self.ref_id, self.strand, self.start, self.end
+                ),
+            },
         }
     }
 }
@@ -74,37 +166,87 @@ impl std::fmt::Display for Origin {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Description {
     pub origin: Origin,
-    pub chimera: Option<Origin>,
+    pub chimera: Vec<Origin>,
     pub length: usize,
     pub identity: f64,
+    pub tags: Vec<(&'static str, String)>,
+    /// Labels of references of read
+    pub labels: Vec<Label>,
+    /// Length of fragment without error, set only if it's different than origin (repeat expansion or contraction)
+    pub fragment_length: Option<usize>,
+    /// Read is a part of a duplex pair
+    pub duplex: Option<DuplexRead>,
+    /// Read is a foldback read
+    pub foldback: Option<Foldback>,
+    /// Polymerase errors of a PCR copy
+    pub pcr_errors: Vec<PcrError>,
 }
 
 impl Description {
-    pub fn new(origin: Origin, chimera: Option<Origin>, length: usize, identity: f64) -> Self {
+    pub fn new(origin: Origin, chimera: Vec<Origin>, length: usize, identity: f64) -> Self {
         Description {
             origin,
             chimera,
             length,
             identity,
+            tags: Vec::new(),
+            labels: Vec::new(),
+            fragment_length: None,
+            duplex: None,
+            foldback: None,
+            pcr_errors: Vec::new(),
         }
     }
 }
 
 impl std::fmt::Display for Description {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        if let Some(chimera) = &self.chimera {
-            write!(f, "{} chimera {} ", self.origin, chimera)?;
-        } else {
-            write!(f, "{} ", self.origin)?;
+        write!(f, "{} ", self.origin)?;
+        for chimera in self.chimera.iter() {
+            write!(f, "chimera {} ", chimera)?;
         }
 
         write!(
             f,
             "length={} error-free_length={} read_identity={}%",
-            self.origin.end - self.origin.start,
+            self.fragment_length
+                .unwrap_or(self.origin.end - self.origin.start),
             self.length,
             self.identity
-        )
+        )?;
+
+        if let Some(foldback) = &self.foldback {
+            write!(f, " foldback={}", foldback.length)?;
+            if let Some(hairpin) = foldback.hairpin {
+                write!(f, " foldback_hairpin={}", hairpin)?;
+            }
+        }
+        if let Some(duplex) = &self.duplex {
+            write!(f, " duplex={}", duplex)?;
+            if let Some(pair) = duplex.pair() {
+                write!(f, " duplex_pair={}", pair)?;
+            }
+        }
+        if !self.pcr_errors.is_empty() {
+            let applied: Vec<String> = self
+                .pcr_errors
+                .iter()
+                .filter_map(|x| {
+                    x.base
+                        .map(|base| format!("{}:{}", x.position, base as char))
+                })
+                .collect();
+            write!(f, " pcr_errors={}", applied.join(","))?;
+        }
+
+        for label in self.labels.iter() {
+            write!(f, " {}", label)?;
+        }
+        for (key, value) in self.tags.iter() {
+            write!(f, " {}={}", key, value)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -129,20 +271,29 @@ mod t {
         assert_eq!("junk_seq", format!("{}", Origin::junk(100)));
 
         assert_eq!("random_seq", format!("{}", Origin::random(100)));
+
+        test.read_type = ReadType::Real;
+        test.lifted = Some(Lifted {
+            haplotype: "2".to_string(),
+            start: 90,
+            end: 410,
+        });
+
+        assert_eq!("bépo,+strand,90-410,haplotype=2", format!("{}", test));
     }
 
     #[test]
     fn description() {
         let ori = Origin::reference("bépo".to_string(), '+', 100, 400);
 
-        let mut des = Description::new(ori.clone(), None, 301, 99.99);
+        let mut des = Description::new(ori.clone(), Vec::new(), 301, 99.99);
 
         assert_eq!(
             "bépo,+strand,100-400 length=300 error-free_length=301 read_identity=99.99%",
             format!("{}", des)
         );
 
-        des.chimera = Some(ori);
+        des.chimera = vec![ori.clone()];
 
         assert_eq!("bépo,+strand,100-400 chimera bépo,+strand,100-400 length=300 error-free_length=301 read_identity=99.99%", format!("{}", des));
 
@@ -150,7 +301,11 @@ mod t {
 
         assert_eq!("junk_seq chimera bépo,+strand,100-400 length=300 error-free_length=301 read_identity=99.99%", format!("{}", des));
 
-        des.chimera = None;
+        des.chimera.push(ori);
+
+        assert_eq!("junk_seq chimera bépo,+strand,100-400 chimera bépo,+strand,100-400 length=300 error-free_length=301 read_identity=99.99%", format!("{}", des));
+
+        des.chimera.clear();
 
         assert_eq!(
             "junk_seq length=300 error-free_length=301 read_identity=99.99%",
@@ -163,5 +318,63 @@ mod t {
             "random_seq length=300 error-free_length=301 read_identity=99.99%",
             format!("{}", des)
         );
+
+        des.tags.push(("repeat", "chr1:10-22,CAG,4,12".to_string()));
+
+        assert_eq!(
+            "random_seq length=300 error-free_length=301 read_identity=99.99% repeat=chr1:10-22,CAG,4,12",
+            format!("{}", des)
+        );
+
+        des.fragment_length = Some(306);
+
+        assert_eq!(
+            "random_seq length=306 error-free_length=301 read_identity=99.99% repeat=chr1:10-22,CAG,4,12",
+            format!("{}", des)
+        );
+
+        des.duplex = Some(DuplexRead::Complement(Some("template_read".to_string())));
+
+        assert_eq!(
+            "random_seq length=306 error-free_length=301 read_identity=99.99% duplex=complement duplex_pair=template_read repeat=chr1:10-22,CAG,4,12",
+            format!("{}", des)
+        );
+
+        des.duplex = None;
+        des.foldback = Some(Foldback {
+            length: 20,
+            hairpin: Some(0),
+        });
+
+        assert_eq!(
+            "random_seq length=306 error-free_length=301 read_identity=99.99% foldback=20 foldback_hairpin=0 repeat=chr1:10-22,CAG,4,12",
+            format!("{}", des)
+        );
+
+        des.foldback = None;
+        des.pcr_errors = vec![
+            PcrError {
+                position: 3,
+                shift: 1,
+                base: Some(b'T'),
+            },
+            PcrError {
+                position: 400,
+                shift: 2,
+                base: None,
+            },
+        ];
+
+        assert_eq!(
+            "random_seq length=306 error-free_length=301 read_identity=99.99% pcr_errors=3:T repeat=chr1:10-22,CAG,4,12",
+            format!("{}", des)
+        );
+        des.pcr_errors.clear();
+        des.labels.push(Label::Contaminant("phix".to_string()));
+
+        assert_eq!(
+            "random_seq length=306 error-free_length=301 read_identity=99.99% contaminant=phix repeat=chr1:10-22,CAG,4,12",
+            format!("{}", des)
+        );
     }
 }
@@ -0,0 +1,138 @@
+//! Simulate ONT duplex reads, template and complement strand of a molecule are sequenced one after the other
+
+/* standard use */
+
+/* crate use */
+use anyhow::Result;
+
+/* local use */
+use super::description::{Description, DuplexRead};
+use crate::model;
+use crate::references::Reference;
+
+/// Struct to store duplex parameter
+pub struct Duplex {
+    rate: f64,
+    consensus_identity: Option<model::Identity>,
+}
+
+impl Duplex {
+    /// Create duplex parameter, rate is a percentage, if consensus identity is set a duplex consensus read is produce for each pair
+    pub fn new(rate: f64, consensus_identity: Option<model::Identity>) -> Self {
+        Self {
+            rate: rate / 100.0,
+            consensus_identity,
+        }
+    }
+
+    /// Fraction of molecules sequenced on both strand
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Identity model of duplex consensus reads
+    pub fn consensus_identity(&self) -> Option<&model::Identity> {
+        self.consensus_identity.as_ref()
+    }
+}
+
+/// Return true if read is the template of a duplex pair
+pub fn is_template(description: &Description) -> bool {
+    matches!(description.duplex, Some(DuplexRead::Template(_)))
+}
+
+/// Description of complement read of a template, same molecule on other strand
+pub fn complement(template: &Description, reference: &Reference, identity: f64) -> Description {
+    let mut description = template.clone();
+    let length = reference.seq.len();
+
+    let origin = &mut description.origin;
+    origin.strand = if origin.strand == '+' { '-' } else { '+' };
+    let start = length - origin.end;
+    origin.end = length - origin.start;
+    origin.start = start;
+    if let Some(haplotype) = &reference.haplotype {
+        origin.lifted = Some(haplotype.lift(origin.strand, origin.start, origin.end, length));
+    }
+
+    description.identity = identity;
+    description.duplex = Some(DuplexRead::Complement(None));
+
+    description
+}
+
+/// Description of duplex consensus read of a template
+pub fn consensus(template: &Description, identity: f64) -> Description {
+    let mut description = template.clone();
+
+    description.identity = identity;
+    description.duplex = Some(DuplexRead::Consensus);
+
+    description
+}
+
+/// Write name of template and complement reads of each pair
+pub fn write_truth<W>(output: &mut W, pairs: &[(String, String)]) -> Result<()>
+where
+    W: std::io::Write,
+{
+    writeln!(output, "#template\tcomplement")?;
+    for (template, complement) in pairs {
+        writeln!(output, "{}\t{}", template, complement)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use crate::simulate::description::Origin;
+
+    #[test]
+    fn description() {
+        let reference = Reference::new(
+            "chr1".to_string(),
+            b"ACGTACGTAC".to_vec().into_boxed_slice(),
+            false,
+        );
+        let mut template = Description::new(
+            Origin::reference("chr1".to_string(), '+', 2, 6),
+            Vec::new(),
+            4,
+            0.9,
+        );
+        assert!(!is_template(&template));
+        template.duplex = Some(DuplexRead::Template(None));
+        assert!(is_template(&template));
+
+        let complement = complement(&template, &reference, 0.8);
+        assert_eq!(
+            complement.origin,
+            Origin::reference("chr1".to_string(), '-', 4, 8)
+        );
+        assert_eq!(complement.identity, 0.8);
+        assert_eq!(complement.duplex, Some(DuplexRead::Complement(None)));
+        assert!(!is_template(&complement));
+
+        let consensus = consensus(&template, 0.99);
+        assert_eq!(consensus.origin, template.origin);
+        assert_eq!(consensus.identity, 0.99);
+        assert_eq!(consensus.duplex, Some(DuplexRead::Consensus));
+    }
+
+    #[test]
+    fn truth() {
+        let mut output = Vec::new();
+        write_truth(&mut output, &[("a".to_string(), "b".to_string())]).unwrap();
+
+        assert_eq!(output, b"#template\tcomplement\na\tb\n");
+    }
+
+    #[test]
+    fn param() {
+        let duplex = Duplex::new(10.0, None);
+        assert_eq!(duplex.rate(), 0.1);
+        assert!(duplex.consensus_identity().is_none());
+    }
+}
@@ -227,7 +227,7 @@ where
             continue;
         }
 
-        real_edit += change.edit() as f64;
+        real_edit += change.edit();
 
         err.extend(&seq[pos_in_raw..change.begin()]);
         cig.extend(std::iter::repeat(b'=').take(change.begin() - pos_in_raw));
@@ -352,13 +352,13 @@ mod t {
         let first = Change::from_seq(3, 15, b"GGCCGAT".to_vec(), raw);
         let second = Change::from_seq(6, 13, b"AGACCA".to_vec(), raw);
 
-        assert_eq!(first.contain(&second), true);
+        assert!(first.contain(&second));
 
         let ray = b"TTTGCACTTGTTGCCACAGG";
         let first = Change::from_seq(2, 9, b"TGCCCTT".to_vec(), ray);
         let second = Change::from_seq(11, 18, b"TGCCTCA".to_vec(), ray);
 
-        assert_eq!(first.contain(&second), false);
+        assert!(!first.contain(&second));
     }
 
     #[test]
@@ -369,13 +369,13 @@ mod t {
         let first = Change::from_seq(3, 10, b"GGCCGAT".to_vec(), raw);
         let second = Change::from_seq(6, 13, b"AGACCA".to_vec(), raw);
 
-        assert_eq!(first.overlap(&second), true);
+        assert!(first.overlap(&second));
 
         let ray = b"TTTGCACTTGTTGCCACAGG";
         let first = Change::from_seq(2, 9, b"TGCCCTT".to_vec(), ray);
         let second = Change::from_seq(11, 18, b"TGCCTCA".to_vec(), ray);
 
-        assert_eq!(first.overlap(&second), false);
+        assert!(!first.overlap(&second));
     }
 
     #[test]
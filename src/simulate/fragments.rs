@@ -7,7 +7,107 @@
 /* local use */
 use crate::model;
 use crate::references::*;
-use crate::simulate::description::{Description, Origin, ReadType};
+use crate::simulate::description::{Description, DuplexRead, Foldback, Label, Origin, ReadType};
+
+/* constant definition */
+/// Maximal number of fragments draw to get one which pass size selection
+pub const MAX_SELECTION_STEP: usize = 10_000;
+
+/// Remaining quantity of a reference, a reference isn't choose when his target is reach
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Remaining {
+    Unlimited,
+    Bases(u64),
+    Reads(u64),
+}
+
+impl Remaining {
+    /// Target of reference is reach
+    pub fn is_reached(&self) -> bool {
+        matches!(self, Remaining::Bases(0) | Remaining::Reads(0))
+    }
+
+    /// Remove a read of this length of remaining quantity
+    pub fn consume(&mut self, length: u64) {
+        match self {
+            Remaining::Bases(n) => *n = n.saturating_sub(length),
+            Remaining::Reads(n) => *n = n.saturating_sub(1),
+            Remaining::Unlimited => (),
+        }
+    }
+}
+
+/// All references which could be choose have reach their target
+pub fn targets_reached(targets: &[Remaining], weights: &[f64]) -> bool {
+    targets
+        .iter()
+        .enumerate()
+        .all(|(i, target)| target.is_reached() || weights.get(i) == Some(&0.0))
+}
+
+/// Remaining number of reads and quantity of each reference, unlimited by default
+#[derive(Debug, Clone, Default)]
+pub struct Quota {
+    reads: Option<u64>,
+    targets: Option<Vec<Remaining>>,
+}
+
+impl Quota {
+    /// Create a quota from a number of reads and targets of references
+    pub fn new(reads: Option<u64>, targets: Option<&[Remaining]>) -> Self {
+        Self {
+            reads,
+            targets: targets.map(|x| x.to_vec()),
+        }
+    }
+
+    /// Quota don't limit number of reads
+    pub fn is_unlimited(&self) -> bool {
+        self.reads.is_none() && self.targets.is_none()
+    }
+
+    /// Number of reads is reach or all references which could be choose have reach their target
+    pub fn is_reached(&self, weights: &[f64]) -> bool {
+        self.reads == Some(0)
+            || self
+                .targets
+                .as_deref()
+                .map(|x| targets_reached(x, weights))
+                .unwrap_or(false)
+    }
+
+    /// Reference could still be choose
+    pub fn is_open(&self, index: usize) -> bool {
+        self.targets
+            .as_deref()
+            .and_then(|x| x.get(index))
+            .map(|x| !x.is_reached())
+            .unwrap_or(true)
+    }
+
+    /// Number of reads of this reference which could still be produce, a target in bases accept reads until it's reach
+    pub fn available(&self, index: usize, read_type: &ReadType) -> u64 {
+        let target = match self.targets.as_deref().and_then(|x| x.get(index)) {
+            Some(Remaining::Reads(n)) if *read_type == ReadType::Real => *n,
+            Some(Remaining::Bases(0)) if *read_type == ReadType::Real => 0,
+            _ => u64::MAX,
+        };
+
+        self.reads.unwrap_or(u64::MAX).min(target)
+    }
+
+    /// Count a read of this reference, only real reads are count in reference target
+    pub fn consume(&mut self, index: usize, read_type: &ReadType, length: u64) {
+        if let Some(reads) = self.reads.as_mut() {
+            *reads = reads.saturating_sub(1);
+        }
+        if *read_type == ReadType::Real {
+            if let Some(target) = self.targets.as_deref_mut().and_then(|x| x.get_mut(index)) {
+                target.consume(length);
+            }
+        }
+    }
+}
 
 /// An iterator produce fragment, a description and a seed
 pub struct Fragments<'a, R>
@@ -21,6 +121,22 @@ where
     references: &'a References,
     length_model: &'a model::Length,
     identity_model: &'a model::Identity,
+    transcript_model: Option<&'a model::Transcript>,
+    cells: Option<&'a crate::single_cell::Cells>,
+    duplicates: Vec<(usize, Vec<usize>, Description)>,
+    chimera_model: Option<&'a model::Chimera>,
+    digest: Option<&'a crate::pore_c::Digest<'a>>,
+    targets: Option<&'a crate::amplicon::Targets<'a>>,
+    pcr: Option<&'a super::pcr::Pcr>,
+    transposase: Option<&'a model::Transposase>,
+    shearing: Option<&'a model::Shearing>,
+    parent_molecule: usize,
+    quota: Quota,
+    pcr_family: usize,
+    last_molecule: Option<(usize, Origin)>,
+    duplex: Option<&'a super::duplex::Duplex>,
+    foldback_rate: f64,
+    selection_failed: bool,
     rng: &'a mut R,
 }
 
@@ -48,10 +164,94 @@ where
             references,
             length_model,
             identity_model,
+            transcript_model: None,
+            cells: None,
+            duplicates: Vec::new(),
+            chimera_model: None,
+            digest: None,
+            targets: None,
+            pcr: None,
+            transposase: None,
+            shearing: None,
+            parent_molecule: 0,
+            quota: Quota::default(),
+            pcr_family: 0,
+            last_molecule: None,
+            duplex: None,
+            foldback_rate: 0.0,
+            selection_failed: false,
             rng,
         }
     }
 
+    /// Generate cDNA molecules with transcript model for references tagged as transcript
+    pub fn transcript_model(mut self, model: &'a model::Transcript) -> Self {
+        self.transcript_model = Some(model);
+        self
+    }
+
+    /// Assign fragment of transcripts to cells, each fragment get an UMI and is produce as many time as his number of duplicates
+    pub fn cells(mut self, cells: &'a crate::single_cell::Cells) -> Self {
+        self.cells = Some(cells);
+        self
+    }
+
+    /// Set number of parts of chimeras and how they are join, without model a chimera have two random parts
+    pub fn chimera_model(mut self, model: &'a model::Chimera) -> Self {
+        self.chimera_model = Some(model);
+        self
+    }
+
+    /// Produce Pore-C concatemers, read of reference is restriction fragments in contact
+    pub fn pore_c(mut self, digest: &'a crate::pore_c::Digest<'a>) -> Self {
+        self.digest = Some(digest);
+        self
+    }
+
+    /// Produce whole amplicons, read of reference is an amplicon between his primers and length model isn't use
+    pub fn amplicons(mut self, targets: &'a crate::amplicon::Targets<'a>) -> Self {
+        self.targets = Some(targets);
+        self
+    }
+
+    /// Amplify molecules of reference by PCR, reads of a duplicate group are produce one after the other
+    pub fn pcr(mut self, pcr: &'a super::pcr::Pcr) -> Self {
+        self.pcr = Some(pcr);
+        self
+    }
+
+    /// Molecules boundaries are transposase insertions, length model isn't use for reads of references
+    pub fn transposase(mut self, transposase: &'a model::Transposase) -> Self {
+        self.transposase = Some(transposase);
+        self
+    }
+
+    /// Reads are fragments of sheared parent molecules, fragments of a parent are produce one after the other
+    pub fn shearing(mut self, shearing: &'a model::Shearing) -> Self {
+        self.shearing = Some(shearing);
+        self
+    }
+
+    /// Number of reads and quantity of references to generate, a reference isn't choose when his target is reach and iteration stop when quota is reach
+    ///
+    /// Quota is count on generated reads, reads remove at writing must be count by caller
+    pub fn quota(mut self, quota: Quota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Molecules sequenced on both strands, complement read length is count in target
+    pub fn duplex(mut self, duplex: &'a super::duplex::Duplex) -> Self {
+        self.duplex = Some(duplex);
+        self
+    }
+
+    /// Rate of foldback reads, molecule is read follow by reverse complement of his end, rate is a fraction
+    pub fn foldback(mut self, rate: f64) -> Self {
+        self.foldback_rate = rate;
+        self
+    }
+
     /// Get the read type
     pub fn get_read_type(&mut self) -> ReadType {
         if self.rng.gen_bool(self.junk_rate) {
@@ -68,50 +268,453 @@ where
         self.rng.gen_bool(self.chimera_rate)
     }
 
-    /// Produce a fragment
+    /// Choose a reference and a strand, a reference which reach his target is ignored
+    fn choose_reference(&mut self) -> (usize, char) {
+        loop {
+            let (index, strand) = self.references.choose_reference(self.rng);
+            if self.quota.is_open(index) {
+                return (index, strand);
+            }
+        }
+    }
+
+    /// Produce a fragment, fragment is draw again until it pass size selection
+    ///
+    /// After MAX_SELECTION_STEP rejected fragments iteration stop, check it with selection_failed
     pub fn generate_fragment(&mut self) -> (usize, usize, Origin) {
+        for _ in 0..MAX_SELECTION_STEP {
+            let fragment = self.draw_fragment();
+            if self.length_model.keep(fragment.1 as u64, self.rng) {
+                return fragment;
+            }
+        }
+
+        self.selection_failed = true;
+        self.draw_fragment()
+    }
+
+    /// True if size selection reject MAX_SELECTION_STEP consecutive fragments
+    pub fn selection_failed(&self) -> bool {
+        self.selection_failed
+    }
+
+    /// Produce a fragment without size selection
+    fn draw_fragment(&mut self) -> (usize, usize, Origin) {
         let read_type = self.get_read_type();
         let length = self.length_model.get_length(self.rng) as usize;
 
         match read_type {
             ReadType::Real => {
-                let (mut ref_index, mut strand) = self.references.choose_reference(self.rng);
+                let (mut ref_index, mut strand) = self.choose_reference();
                 let mut reference = &self.references.sequences[ref_index];
 
                 while !fragment_is_possible(length, reference.seq.len(), reference.circular) {
-                    let (r, s) = self.references.choose_reference(self.rng);
+                    let (r, s) = self.choose_reference();
                     ref_index = r;
                     strand = s;
                     reference = &self.references.sequences[ref_index];
                 }
 
-                let try_begin = self.rng.gen_range(0..reference.seq.len()) as usize;
-                let (begin, end, real_length) = if try_begin + length < reference.seq.len() {
-                    (try_begin, try_begin + length, length)
-                } else if reference.circular {
-                    (
-                        try_begin,
-                        length - (reference.seq.len() - try_begin),
-                        length,
-                    )
+                let try_begin = self.references.choose_start(ref_index, strand, self.rng);
+                let (begin, end, real_length) = if let Some(model) = self
+                    .transcript_model
+                    .filter(|_| reference.transcript().is_some())
+                {
+                    let (begin, end) = model.get_molecule(&reference.seq, self.rng);
+                    if strand == '-' {
+                        (
+                            reference.seq.len() - end,
+                            reference.seq.len() - begin,
+                            end - begin,
+                        )
+                    } else {
+                        (begin, end, end - begin)
+                    }
+                } else if let Some(model) = self.transposase {
+                    let (begin, length) =
+                        model.get_molecule(&reference.seq, reference.circular, self.rng);
+                    let (begin, end) = strand_bounds(begin, length, reference.seq.len(), strand);
+
+                    (begin, end, length)
                 } else {
-                    (
-                        try_begin,
-                        reference.seq.len() - 1,
-                        reference.seq.len() - try_begin,
-                    )
+                    fragment_bounds(try_begin, length, reference.seq.len(), reference.circular)
                 };
 
                 (
                     ref_index,
                     real_length,
-                    Origin::reference(reference.id.clone(), strand, begin, end),
+                    origin(reference, strand, begin, end),
                 )
             }
             ReadType::Junk => (0, length, Origin::junk(length)),
             ReadType::Random => (0, length, Origin::random(length)),
         }
     }
+
+    /// Amplify a molecule, first copy is return and other copies are produce after
+    ///
+    /// Copies get tag `pcr_family` and their polymerase errors, a copy with a template switch is a chimera with end of previous molecule
+    pub fn amplify(
+        &mut self,
+        first_index: usize,
+        chimera_index: Vec<usize>,
+        description: Description,
+    ) -> (usize, Vec<usize>, Description) {
+        let pcr = match self.pcr {
+            Some(pcr) if description.origin.read_type == ReadType::Real => pcr,
+            _ => return (first_index, chimera_index, description),
+        };
+
+        let references = self.references;
+        let length = origin_length(&description.origin, &references.sequences[first_index]);
+        let molecules = pcr.amplify(length, self.rng);
+
+        let family = self.pcr_family.to_string();
+        self.pcr_family += 1;
+        let partner = self
+            .last_molecule
+            .replace((first_index, description.origin.clone()));
+
+        let mut copies = Vec::with_capacity(molecules.len());
+        for (i, mut molecule) in molecules.into_iter().enumerate() {
+            let mut copy = description.clone();
+            let mut index = chimera_index.clone();
+            if i != 0 {
+                copy.identity = self.identity_model.get_identity(self.rng);
+            }
+
+            match (molecule.switch, &partner) {
+                (Some(pos), Some((p_index, p_origin)))
+                    if copy.chimera.is_empty()
+                        && p_origin.read_type == ReadType::Real
+                        && copy.origin.start < copy.origin.end
+                        && p_origin.start < p_origin.end =>
+                {
+                    let reference = &references.sequences[first_index];
+                    let partner_ref = &references.sequences[*p_index];
+                    let p_start = (p_origin.start + pos * (p_origin.end - p_origin.start) / length)
+                        .min(p_origin.end - 1);
+
+                    copy.origin = origin(
+                        reference,
+                        copy.origin.strand,
+                        copy.origin.start,
+                        copy.origin.start + pos,
+                    );
+                    copy.chimera =
+                        vec![origin(partner_ref, p_origin.strand, p_start, p_origin.end)];
+                    copy.length = pos + p_origin.end - p_start;
+                    copy.tags.extend(partner_ref.tags.iter().cloned());
+                    copy.labels.extend(partner_ref.labels.iter().cloned());
+                    copy.tags.push(("pcr_switch", pos.to_string()));
+                    index = vec![*p_index];
+
+                    molecule.errors.retain(|(p, _)| *p < pos);
+                }
+                _ => (),
+            }
+
+            copy.tags.push(("pcr_family", family.clone()));
+            copy.pcr_errors = super::pcr::errors(&molecule);
+
+            copies.push((first_index, index, copy));
+        }
+
+        let first = copies.remove(0);
+        self.duplicates.extend(copies.into_iter().rev());
+
+        first
+    }
+
+    /// Produce an amplicon, with amplicon id in labels
+    pub fn generate_amplicon(
+        &mut self,
+        targets: &crate::amplicon::Targets<'_>,
+    ) -> (usize, Description) {
+        let read_type = self.get_read_type();
+        if read_type != ReadType::Real {
+            return (0, self.generate_artifact(read_type));
+        }
+
+        let (ref_index, begin, end, id) = targets.choose(self.rng);
+        let reference = &self.references.sequences[ref_index];
+        let length = reference.seq.len();
+
+        let origin = if self.rng.gen_bool(0.5) {
+            origin(reference, '+', begin, end)
+        } else {
+            origin(reference, '-', length - end, length - begin)
+        };
+        let mut description = Description::new(
+            origin,
+            Vec::new(),
+            end - begin,
+            self.identity_model.get_identity(self.rng),
+        );
+        description.tags.extend(reference.tags.iter().cloned());
+        description.labels.extend(reference.labels.iter().cloned());
+        description.labels.push(Label::Amplicon(id.to_string()));
+
+        (ref_index, description)
+    }
+
+    /// Produce a junk or random read, length follow length model
+    fn generate_artifact(&mut self, read_type: ReadType) -> Description {
+        let length = self.length_model.get_length(self.rng) as usize;
+        let origin = if read_type == ReadType::Junk {
+            Origin::junk(length)
+        } else {
+            Origin::random(length)
+        };
+
+        Description::new(
+            origin,
+            Vec::new(),
+            length,
+            self.identity_model.get_identity(self.rng),
+        )
+    }
+
+    /// Shear a parent molecule, first fragment is return and other fragments are produce after
+    ///
+    /// Fragments get tags `parent_molecule` and `parent_origin`, strand of each fragment is random
+    pub fn generate_parent(&mut self, shearing: &model::Shearing) -> (usize, Description) {
+        let read_type = self.get_read_type();
+        if read_type != ReadType::Real {
+            return (0, self.generate_artifact(read_type));
+        }
+
+        let references = self.references;
+        let (ref_index, _) = self.choose_reference();
+        let reference = &references.sequences[ref_index];
+        let ref_len = reference.seq.len();
+
+        let parent_begin = references.choose_start(ref_index, '+', self.rng);
+        let parent_length = if reference.circular {
+            shearing.get_parent_length(self.rng).min(ref_len)
+        } else {
+            shearing
+                .get_parent_length(self.rng)
+                .min(ref_len - parent_begin)
+        }
+        .max(1);
+
+        let id = self.parent_molecule.to_string();
+        self.parent_molecule += 1;
+        let parent_origin = format!(
+            "{}:{}-{}",
+            reference.id,
+            parent_begin,
+            (parent_begin + parent_length - 1) % ref_len + 1
+        );
+
+        let mut fragments: Vec<(usize, Description)> = shearing
+            .fragments(parent_length, self.rng)
+            .into_iter()
+            .map(|(begin, end)| {
+                let strand = if self.rng.gen_bool(0.5) { '+' } else { '-' };
+                let length = end - begin;
+                let (begin, end) =
+                    strand_bounds((parent_begin + begin) % ref_len, length, ref_len, strand);
+
+                let mut description = Description::new(
+                    origin(reference, strand, begin, end),
+                    Vec::new(),
+                    length,
+                    self.identity_model.get_identity(self.rng),
+                );
+                description.tags.extend(reference.tags.iter().cloned());
+                description.labels.extend(reference.labels.iter().cloned());
+                description.tags.push(("parent_molecule", id.clone()));
+                description
+                    .tags
+                    .push(("parent_origin", parent_origin.clone()));
+
+                (ref_index, description)
+            })
+            .collect();
+
+        let first = fragments.remove(0);
+        self.duplicates.extend(
+            fragments
+                .into_iter()
+                .rev()
+                .map(|(index, description)| (index, Vec::new(), description)),
+        );
+
+        first
+    }
+
+    /// Produce a Pore-C concatemer, restriction fragments are store as origin and chimera of description
+    pub fn generate_concatemer(
+        &mut self,
+        digest: &crate::pore_c::Digest<'_>,
+    ) -> (usize, Vec<usize>, Description) {
+        let read_type = self.get_read_type();
+        if read_type != ReadType::Real {
+            return (0, Vec::new(), self.generate_artifact(read_type));
+        }
+
+        let references = self.references;
+        let contacts = digest.concatemer(references, self.rng);
+        let mut origins: Vec<Origin> = contacts
+            .iter()
+            .map(|(ref_index, strand, index)| {
+                let reference = &references.sequences[*ref_index];
+                let (begin, end) = digest.fragment(*ref_index, *index);
+                if *strand == '-' {
+                    let length = reference.seq.len();
+                    origin(reference, '-', length - end, length - begin)
+                } else {
+                    origin(reference, '+', begin, end)
+                }
+            })
+            .collect();
+        let length = origins.iter().map(|x| x.end - x.start).sum();
+
+        let first = origins.remove(0);
+        let mut description = Description::new(
+            first,
+            origins,
+            length,
+            self.identity_model.get_identity(self.rng),
+        );
+        for (ref_index, _, _) in contacts.iter() {
+            let reference = &references.sequences[*ref_index];
+            description.tags.extend(reference.tags.iter().cloned());
+            description.labels.extend(reference.labels.iter().cloned());
+        }
+        description.tags.push((
+            "restriction_fragments",
+            crate::pore_c::fragments_tag(references, &contacts),
+        ));
+
+        (
+            contacts[0].0,
+            contacts[1..].iter().map(|x| x.0).collect(),
+            description,
+        )
+    }
+
+    /// Produce next part of a chimera, position depend of previous part and junction type
+    pub fn generate_chimera_part(
+        &mut self,
+        previous_index: usize,
+        previous: &Origin,
+    ) -> (usize, usize, Origin) {
+        let junction = self
+            .chimera_model
+            .map(|x| x.junction())
+            .unwrap_or(model::chimera::Junction::Random);
+        if junction == model::chimera::Junction::Random || previous.read_type != ReadType::Real {
+            return self.generate_fragment();
+        }
+
+        let references = self.references;
+        let reference = &references.sequences[previous_index];
+        let ref_len = reference.seq.len();
+        let length = self.length_model.get_length(self.rng) as usize;
+        if ref_len < 2 || !fragment_is_possible(length, ref_len, reference.circular) {
+            return self.generate_fragment();
+        }
+
+        // origin of '-' strand is in reverse complement coordinate
+        let (strand, begin) = match junction {
+            model::chimera::Junction::Adjacent => (previous.strand, previous.end),
+            _ => {
+                let anchor = if previous.strand == '+' {
+                    previous.start
+                } else {
+                    reverse_position(previous.start.min(ref_len - 1), ref_len)
+                };
+                let forward = self.rng.gen_bool(0.5);
+
+                // a linear fragment can't start on last base of its strand
+                let last = if reference.circular { 0 } else { 1 };
+                let (min, max) = if forward {
+                    (0, ref_len - 1 - last)
+                } else {
+                    (last, ref_len - 1)
+                };
+                let low = anchor
+                    .saturating_sub(model::chimera::PROXIMITY_DISTANCE)
+                    .max(min);
+                let high = (anchor + model::chimera::PROXIMITY_DISTANCE).min(max);
+                let pos = self.rng.gen_range(low.min(high)..=high);
+
+                if forward {
+                    ('+', pos)
+                } else {
+                    ('-', reverse_position(pos, ref_len))
+                }
+            }
+        };
+
+        if begin >= ref_len || (!reference.circular && begin + 1 >= ref_len) {
+            return self.generate_fragment();
+        }
+
+        let (begin, end, real_length) = fragment_bounds(begin, length, ref_len, reference.circular);
+
+        (
+            previous_index,
+            real_length,
+            origin(reference, strand, begin, end),
+        )
+    }
+}
+
+fn fragment_bounds(
+    begin: usize,
+    length: usize,
+    ref_len: usize,
+    circular: bool,
+) -> (usize, usize, usize) {
+    if begin + length < ref_len {
+        (begin, begin + length, length)
+    } else if circular {
+        (begin, length - (ref_len - begin), length)
+    } else {
+        (begin, ref_len - 1, ref_len - begin)
+    }
+}
+
+fn origin(reference: &Reference, strand: char, begin: usize, end: usize) -> Origin {
+    let mut origin = Origin::reference(reference.id.clone(), strand, begin, end);
+    if let Some(haplotype) = &reference.haplotype {
+        origin.lifted = Some(haplotype.lift(strand, begin, end, reference.seq.len()));
+    }
+
+    origin
+}
+
+/// Bounds of a fragment found on forward strand, in coordinate of strand
+fn strand_bounds(begin: usize, length: usize, ref_len: usize, strand: char) -> (usize, usize) {
+    let begin = if strand == '-' {
+        (ref_len - (begin + length) % ref_len) % ref_len
+    } else {
+        begin
+    };
+
+    if begin + length > ref_len {
+        (begin, begin + length - ref_len)
+    } else {
+        (begin, begin + length)
+    }
+}
+
+/// Position on other strand, forward to reverse complement or reverse complement to forward
+fn reverse_position(position: usize, ref_len: usize) -> usize {
+    ref_len - 1 - position
+}
+
+/// Length of a fragment of reference
+fn origin_length(origin: &Origin, reference: &Reference) -> usize {
+    if origin.start <= origin.end {
+        origin.end - origin.start
+    } else {
+        reference.seq.len() - origin.start + origin.end
+    }
 }
 
 fn fragment_is_possible(frag_len: usize, ref_len: usize, circular: bool) -> bool {
@@ -122,27 +725,80 @@ fn fragment_is_possible(frag_len: usize, ref_len: usize, circular: bool) -> bool
     }
 }
 
-impl<'a, R> Iterator for Fragments<'a, R>
+impl<'a, R> Fragments<'a, R>
 where
     R: rand::Rng,
 {
-    // ((ref_index, chimeric_ref_index), Description, Seed)
-    type Item = (usize, usize, Description, u64);
-
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Produce next fragment, without read count and reference targets
+    fn next_fragment(&mut self) -> Option<(usize, Vec<usize>, Description, u64)> {
         if self.target == 0 {
             return None;
         }
 
+        if let Some((first_index, chimera_index, description)) = self.duplicates.pop() {
+            self.target = self.target.saturating_sub(description.length as u64);
+
+            return Some((first_index, chimera_index, description, self.rng.next_u64()));
+        }
+
+        if let Some(targets) = self.targets {
+            let (first_index, description) = self.generate_amplicon(targets);
+            let (first_index, chimera_index, description) =
+                self.amplify(first_index, Vec::new(), description);
+            self.target = self.target.saturating_sub(description.length as u64);
+
+            return Some((first_index, chimera_index, description, self.rng.next_u64()));
+        }
+
+        if let Some(shearing) = self.shearing {
+            let (first_index, description) = self.generate_parent(shearing);
+            self.target = self.target.saturating_sub(description.length as u64);
+
+            return Some((first_index, Vec::new(), description, self.rng.next_u64()));
+        }
+
+        if let Some(digest) = self.digest {
+            let (first_index, chimera_index, description) = self.generate_concatemer(digest);
+            self.target = self.target.saturating_sub(description.length as u64);
+
+            return Some((first_index, chimera_index, description, self.rng.next_u64()));
+        }
+
         let (first_index, first_length, first_origin) = self.generate_fragment();
-        let (second_index, second_length, second_origin) = if self.is_chimera() {
-            let tmp = self.generate_fragment();
-            (tmp.0, tmp.1, Some(tmp.2))
+        let mut chimera_index: Vec<usize> = Vec::new();
+        let mut chimera_origin: Vec<Origin> = Vec::new();
+        let mut chimera_length = 0;
+        if self.is_chimera() {
+            loop {
+                let (index, length, origin) = {
+                    let previous_index = chimera_index.last().copied().unwrap_or(first_index);
+                    let previous = chimera_origin.last().unwrap_or(&first_origin);
+                    self.generate_chimera_part(previous_index, previous)
+                };
+                chimera_index.push(index);
+                chimera_origin.push(origin);
+                chimera_length += length;
+
+                match self.chimera_model {
+                    Some(model) if model.add_part(self.rng) => continue,
+                    _ => break,
+                }
+            }
+        }
+
+        // length of reverse complement part of a foldback read
+        let foldback = if self.foldback_rate != 0.0
+            && chimera_origin.is_empty()
+            && first_origin.read_type == ReadType::Real
+            && first_length != 0
+            && self.rng.gen_bool(self.foldback_rate)
+        {
+            Some(self.rng.gen_range(1..=first_length))
         } else {
-            (0, 0, None)
+            None
         };
 
-        let tt_length = first_length + second_length;
+        let tt_length = first_length + chimera_length + foldback.unwrap_or(0);
 
         if tt_length as u64 > self.target {
             self.target = 0;
@@ -150,17 +806,113 @@ where
             self.target -= tt_length as u64;
         }
 
-        Some((
-            first_index,
-            second_index,
-            Description::new(
-                first_origin,
-                second_origin,
-                tt_length,
-                self.identity_model.get_identity(self.rng),
-            ),
-            self.rng.next_u64(),
-        ))
+        let mut description = Description::new(
+            first_origin,
+            chimera_origin,
+            tt_length,
+            self.identity_model.get_identity(self.rng),
+        );
+        description.foldback = foldback.map(|length| Foldback {
+            length,
+            hairpin: None,
+        });
+        if description.origin.read_type == ReadType::Real {
+            let reference = &self.references.sequences[first_index];
+
+            description.tags.extend(reference.tags.iter().cloned());
+            description.labels.extend(reference.labels.iter().cloned());
+            if let Some(model) = self
+                .transcript_model
+                .filter(|_| reference.transcript().is_some())
+            {
+                description.tags.extend(crate::transcriptome::molecule_tags(
+                    &description.origin,
+                    reference,
+                    model.max_five_prime_loss(),
+                ));
+            }
+        }
+        for (index, origin) in chimera_index.iter().zip(description.chimera.iter()) {
+            if origin.read_type == ReadType::Real {
+                let reference = &self.references.sequences[*index];
+                description.tags.extend(reference.tags.iter().cloned());
+                description.labels.extend(reference.labels.iter().cloned());
+            }
+        }
+
+        if let Some(cells) = self
+            .cells
+            .filter(|_| description.origin.read_type == ReadType::Real)
+        {
+            if let Some(cell) = cells.choose_cell(first_index, self.rng) {
+                description
+                    .labels
+                    .push(Label::Cell(cells.barcode(cell).to_string()));
+                description
+                    .labels
+                    .push(Label::Umi(crate::single_cell::get_umi(self.rng)));
+
+                for _ in 1..cells.get_duplicates(self.rng) {
+                    let mut duplicate = description.clone();
+                    duplicate.identity = self.identity_model.get_identity(self.rng);
+                    self.duplicates
+                        .push((first_index, chimera_index.clone(), duplicate));
+                }
+            }
+        }
+
+        let (first_index, chimera_index, mut description) =
+            self.amplify(first_index, chimera_index, description);
+
+        if let Some(duplex) = self.duplex {
+            if duplex.rate() != 0.0
+                && description.origin.read_type == ReadType::Real
+                && description.chimera.is_empty()
+                && foldback.is_none()
+                && self.rng.gen_bool(duplex.rate())
+            {
+                // complement is count in target, consensus is write in another output
+                self.target = self.target.saturating_sub(description.length as u64);
+                description.duplex = Some(DuplexRead::Template(None));
+            }
+        }
+
+        Some((first_index, chimera_index, description, self.rng.next_u64()))
+    }
+}
+
+impl<'a, R> Iterator for Fragments<'a, R>
+where
+    R: rand::Rng,
+{
+    // (ref_index, ref_index of each chimera part, Description, Seed)
+    type Item = (usize, Vec<usize>, Description, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.selection_failed || self.quota.is_reached(&self.references.weights) {
+            return None;
+        }
+
+        let fragment = self.next_fragment()?;
+        if self.selection_failed {
+            return None;
+        }
+
+        // complement of a duplex template is count in quota
+        let reads = if super::duplex::is_template(&fragment.2) {
+            2
+        } else {
+            1
+        };
+        for _ in 0..reads {
+            self.quota.consume(
+                fragment.0,
+                &fragment.2.origin.read_type,
+                fragment.2.length as u64,
+            );
+        }
+
+        Some(fragment)
     }
 }
 
@@ -171,7 +923,7 @@ mod t {
     use std::io::Seek;
     use std::io::Write;
 
-    static FASTA: &'static [u8] = b">random_seq_0
+    static FASTA: &[u8] = b">random_seq_0
 TCCTAACGTG
 >random_seq_1 depth=1.5
 TCACGATTAC
@@ -277,7 +1029,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '+',
                         start: 7,
                         end: 9,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -288,7 +1041,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '*',
                         start: 0,
                         end: 4,
-                        read_type: ReadType::Random
+                        read_type: ReadType::Random,
+                        lifted: None
                     }
                 ),
                 (
@@ -299,7 +1053,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '+',
                         start: 5,
                         end: 9,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -310,7 +1065,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '*',
                         start: 0,
                         end: 9,
-                        read_type: ReadType::Junk
+                        read_type: ReadType::Junk,
+                        lifted: None
                     }
                 ),
                 (
@@ -321,7 +1077,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '+',
                         start: 16,
                         end: 20,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -332,7 +1089,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '*',
                         start: 0,
                         end: 17,
-                        read_type: ReadType::Random
+                        read_type: ReadType::Random,
+                        lifted: None
                     }
                 ),
                 (
@@ -343,7 +1101,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '-',
                         start: 0,
                         end: 7,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -354,7 +1113,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '+',
                         start: 8,
                         end: 9,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -365,7 +1125,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '+',
                         start: 2,
                         end: 9,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -376,7 +1137,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '+',
                         start: 5,
                         end: 3,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -387,7 +1149,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '*',
                         start: 0,
                         end: 6,
-                        read_type: ReadType::Junk
+                        read_type: ReadType::Junk,
+                        lifted: None
                     }
                 ),
                 (
@@ -398,7 +1161,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '-',
                         start: 48,
                         end: 54,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -409,7 +1173,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '-',
                         start: 6,
                         end: 9,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -420,7 +1185,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '-',
                         start: 25,
                         end: 34,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -431,7 +1197,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '*',
                         start: 0,
                         end: 7,
-                        read_type: ReadType::Junk
+                        read_type: ReadType::Junk,
+                        lifted: None
                     }
                 ),
                 (
@@ -442,7 +1209,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '*',
                         start: 0,
                         end: 8,
-                        read_type: ReadType::Random
+                        read_type: ReadType::Random,
+                        lifted: None
                     }
                 ),
                 (
@@ -453,7 +1221,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '-',
                         start: 1,
                         end: 9,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -464,7 +1233,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '*',
                         start: 0,
                         end: 7,
-                        read_type: ReadType::Random
+                        read_type: ReadType::Random,
+                        lifted: None
                     }
                 ),
                 (
@@ -475,7 +1245,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '-',
                         start: 0,
                         end: 6,
-                        read_type: ReadType::Real
+                        read_type: ReadType::Real,
+                        lifted: None
                     }
                 ),
                 (
@@ -486,7 +1257,8 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                         strand: '*',
                         start: 0,
                         end: 8,
-                        read_type: ReadType::Random
+                        read_type: ReadType::Random,
+                        lifted: None
                     }
                 )
             ],
@@ -509,195 +1281,268 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
             &mut rng,
         );
 
-        let frags: Vec<(usize, usize, Description, u64)> = fragments.take(10).collect();
+        let frags: Vec<(usize, Vec<usize>, Description, u64)> = fragments.take(10).collect();
 
         assert_eq!(
             vec![
                 (
                     7,
-                    0,
+                    Vec::new(),
                     Description {
                         origin: Origin {
                             ref_id: "random_seq_7".to_string(),
                             strand: '+',
                             start: 7,
                             end: 9,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 3,
-                        identity: 0.9023903395427547
+                        identity: 0.9023903395427547,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     17195042692806716983
                 ),
                 (
                     0,
-                    6,
+                    vec![6],
                     Description {
                         origin: Origin {
                             ref_id: "random_seq_0".to_string(),
                             strand: '+',
                             start: 5,
                             end: 9,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: Some(Origin {
+                        chimera: vec![Origin {
                             ref_id: "random_seq_6".to_string(),
                             strand: '+',
                             start: 4,
                             end: 2,
-                            read_type: ReadType::Real
-                        }),
+                            read_type: ReadType::Real,
+                            lifted: None
+                        }],
                         length: 13,
-                        identity: 0.785919024034962
+                        identity: 0.785919024034962,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     7410303534117827570
                 ),
                 (
                     10,
-                    0,
+                    Vec::new(),
                     Description {
                         origin: Origin {
                             ref_id: "random_seq_10".to_string(),
                             strand: '-',
                             start: 35,
                             end: 44,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 9,
-                        identity: 0.8336097597069272
+                        identity: 0.8336097597069272,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     657338316926129147
                 ),
                 (
                     7,
-                    0,
+                    Vec::new(),
                     Description {
                         origin: Origin {
                             ref_id: "random_seq_7".to_string(),
                             strand: '+',
                             start: 8,
                             end: 9,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 2,
-                        identity: 0.7943651602000301
+                        identity: 0.7943651602000301,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     10605392195150115091
                 ),
                 (
                     10,
-                    10,
+                    vec![10],
                     Description {
                         origin: Origin {
                             ref_id: "random_seq_10".to_string(),
                             strand: '+',
                             start: 96,
                             end: 99,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: Some(Origin {
+                        chimera: vec![Origin {
                             ref_id: "random_seq_10".to_string(),
                             strand: '+',
                             start: 68,
                             end: 74,
-                            read_type: ReadType::Real
-                        }),
+                            read_type: ReadType::Real,
+                            lifted: None
+                        }],
                         length: 10,
-                        identity: 0.9166196996085733
+                        identity: 0.9166196996085733,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     11312190434313393638
                 ),
                 (
                     10,
-                    0,
+                    Vec::new(),
                     Description {
                         origin: Origin {
                             ref_id: "random_seq_10".to_string(),
                             strand: '+',
                             start: 25,
                             end: 33,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 8,
-                        identity: 0.8409338668084709
+                        identity: 0.8409338668084709,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     5274222100112014305
                 ),
                 (
                     7,
-                    0,
+                    Vec::new(),
                     Description {
                         origin: Origin {
                             ref_id: "random_seq_7".to_string(),
                             strand: '+',
                             start: 7,
                             end: 9,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 3,
-                        identity: 0.9103369460151146
+                        identity: 0.9103369460151146,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     10567391463651436578
                 ),
                 (
                     10,
-                    0,
+                    Vec::new(),
                     Description {
                         origin: Origin {
                             ref_id: "random_seq_10".to_string(),
                             strand: '+',
                             start: 81,
                             end: 88,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 7,
-                        identity: 0.8210852839903914
+                        identity: 0.8210852839903914,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     12595372283568864177
                 ),
                 (
                     0,
-                    0,
+                    Vec::new(),
                     Description {
                         origin: Origin {
                             ref_id: "".to_string(),
                             strand: '*',
                             start: 0,
                             end: 8,
-                            read_type: ReadType::Random
+                            read_type: ReadType::Random,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 8,
-                        identity: 0.8383956529757561
+                        identity: 0.8383956529757561,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     14078074552533106200
                 ),
                 (
                     0,
-                    6,
+                    vec![6],
                     Description {
                         origin: Origin {
                             ref_id: "".to_string(),
                             strand: '*',
                             start: 0,
                             end: 4,
-                            read_type: ReadType::Random
+                            read_type: ReadType::Random,
+                            lifted: None
                         },
-                        chimera: Some(Origin {
+                        chimera: vec![Origin {
                             ref_id: "random_seq_6".to_string(),
                             strand: '-',
                             start: 2,
                             end: 0,
-                            read_type: ReadType::Real
-                        }),
+                            read_type: ReadType::Real,
+                            lifted: None
+                        }],
                         length: 12,
-                        identity: 0.8815059110082734
+                        identity: 0.8815059110082734,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     14485571221210959617
                 )
@@ -706,6 +1551,690 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
         );
     }
 
+    #[test]
+    fn reference_tags() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        for reference in refs.sequences.iter_mut() {
+            reference.labels.push(Label::Genome(reference.id.clone()));
+        }
+
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+
+        for (first, second, description, _) in Fragments::new(
+            10_000,
+            (10.0, 10.0, 50.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        ) {
+            let mut expected = Vec::new();
+            if description.origin.read_type == ReadType::Real {
+                expected.push(Label::Genome(refs.sequences[first].id.clone()));
+            }
+            for (index, origin) in second.iter().zip(description.chimera.iter()) {
+                if origin.read_type == ReadType::Real {
+                    expected.push(Label::Genome(refs.sequences[*index].id.clone()));
+                }
+            }
+
+            assert_eq!(expected, description.labels);
+        }
+    }
+
+    #[test]
+    fn cells() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        refs.sequences[0]
+            .labels
+            .push(Label::Transcript("random_seq_0".to_string()));
+
+        let mut cells = crate::single_cell::Cells::new(
+            crate::single_cell::random_barcodes(2, &mut rng),
+            0.0,
+            3.0,
+        )
+        .unwrap();
+        let weights = cells.expression(&refs, &mut rng).unwrap();
+        refs.set_weights(weights).unwrap();
+
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+
+        let mut umis: rustc_hash::FxHashMap<String, usize> = rustc_hash::FxHashMap::default();
+        for (first, _, description, _) in
+            Fragments::new(10_000, (0.0, 0.0, 0.0), &refs, &length, &identity, &mut rng)
+                .cells(&cells)
+        {
+            let umi = description.labels.iter().find_map(|x| match x {
+                Label::Umi(umi) => Some(umi),
+                _ => None,
+            });
+            if first == 0 {
+                assert!(description.labels.iter().any(|x| matches!(
+                    x,
+                    Label::Cell(cell) if cell == cells.barcode(0) || cell == cells.barcode(1)
+                )));
+                *umis.entry(umi.unwrap().clone()).or_default() += 1;
+            } else {
+                assert!(umi.is_none());
+            }
+        }
+
+        let mean = umis.values().sum::<usize>() as f64 / umis.len() as f64;
+        assert!((2.5..3.5).contains(&mean));
+    }
+
+    #[test]
+    fn chimera_parts() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+        let chimera = model::Chimera::new(4.0, model::chimera::Junction::Random).unwrap();
+
+        let mut nb_chimera = 0;
+        let mut nb_parts = 0;
+        for (_, chimera_index, description, _) in Fragments::new(
+            100_000,
+            (0.0, 0.0, 50.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .chimera_model(&chimera)
+        {
+            assert_eq!(chimera_index.len(), description.chimera.len());
+            if !description.chimera.is_empty() {
+                nb_chimera += 1;
+                nb_parts += description.chimera.len() + 1;
+            }
+        }
+
+        let mean = nb_parts as f64 / nb_chimera as f64;
+        assert!((3.7..4.3).contains(&mean));
+    }
+
+    #[test]
+    fn chimera_junction() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+
+        let chimera = model::Chimera::new(3.0, model::chimera::Junction::Adjacent).unwrap();
+        let mut fragments = Fragments::new(
+            10_000,
+            (0.0, 0.0, 100.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .chimera_model(&chimera);
+
+        let previous = Origin::reference("random_seq_10".to_string(), '+', 10, 20);
+        for _ in 0..100 {
+            let (index, _, origin) = fragments.generate_chimera_part(10, &previous);
+            assert_eq!(index, 10);
+            assert_eq!(origin.strand, '+');
+            assert_eq!(origin.start, 20);
+        }
+
+        let previous = Origin::reference("random_seq_10".to_string(), '-', 10, 20);
+        for _ in 0..100 {
+            let (index, _, origin) = fragments.generate_chimera_part(10, &previous);
+            assert_eq!(index, 10);
+            assert_eq!(origin.strand, '-');
+            assert_eq!(origin.start, 20);
+        }
+
+        let chimera = model::Chimera::new(3.0, model::chimera::Junction::Proximal).unwrap();
+        let mut fragments = Fragments::new(
+            10_000,
+            (0.0, 0.0, 100.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .chimera_model(&chimera);
+        for _ in 0..100 {
+            let (index, _, origin) = fragments.generate_chimera_part(10, &previous);
+            assert_eq!(index, 10);
+            assert_eq!(origin.ref_id, "random_seq_10");
+        }
+
+        assert_eq!(reverse_position(0, 100), 99);
+        assert_eq!(reverse_position(99, 100), 0);
+        assert_eq!(reverse_position(reverse_position(20, 100), 100), 20);
+
+        let junk = Origin::junk(10);
+        let parts: Vec<usize> = (0..100)
+            .map(|_| fragments.generate_chimera_part(10, &junk).0)
+            .collect();
+        assert!(parts.iter().any(|x| *x != 10));
+    }
+
+    #[test]
+    fn chimera_proximal() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut fasta = b">random_seq_0\n".to_vec();
+        fasta.extend(crate::random_seq(30_000, &mut rng));
+        fasta.push(b'\n');
+        let refs = References::from_stream(std::io::Cursor::new(fasta)).unwrap();
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+        let chimera = model::Chimera::new(3.0, model::chimera::Junction::Proximal).unwrap();
+        let mut fragments = Fragments::new(
+            10_000,
+            (0.0, 0.0, 100.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .chimera_model(&chimera);
+
+        // anchor of a '-' part is its start in reverse complement coordinate
+        for start in [0, 15_000, 29_999] {
+            let previous = Origin::reference("random_seq_0".to_string(), '-', start, start + 8);
+            let anchor = 29_999 - start;
+            for _ in 0..200 {
+                let (_, _, origin) = fragments.generate_chimera_part(0, &previous);
+                let forward = if origin.strand == '-' {
+                    29_999 - origin.start
+                } else {
+                    origin.start
+                };
+                assert!(
+                    forward.max(anchor) - forward.min(anchor) <= model::chimera::PROXIMITY_DISTANCE
+                );
+            }
+        }
+
+        // last base of reverse complement is anchored on first base of forward
+        let previous = Origin::reference("random_seq_0".to_string(), '-', 29_999, 29_999);
+        for _ in 0..200 {
+            let (_, _, origin) = fragments.generate_chimera_part(0, &previous);
+            if origin.strand == '-' {
+                assert!(origin.start >= 29_999 - model::chimera::PROXIMITY_DISTANCE);
+            } else {
+                assert!(origin.start <= model::chimera::PROXIMITY_DISTANCE);
+            }
+        }
+    }
+
+    #[test]
+    fn pore_c() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut fasta = FASTA.to_vec();
+        fasta.extend(b">random_seq_11\n");
+        fasta.extend(crate::random_seq(20_000, &mut rng));
+        fasta.push(b'\n');
+        let refs = References::from_stream(std::io::Cursor::new(fasta)).unwrap();
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+        let pore_c = crate::pore_c::PoreC::new(b"GATC".to_vec(), 3.0, 1.0, 10.0, 0.0).unwrap();
+        let digest = pore_c.digest(&refs);
+
+        for (first, chimera_index, description, _) in Fragments::new(
+            1_000,
+            (10.0, 10.0, 0.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .pore_c(&digest)
+        {
+            assert_eq!(chimera_index.len(), description.chimera.len());
+            if description.origin.read_type != ReadType::Real {
+                assert!(description.chimera.is_empty());
+                continue;
+            }
+
+            let parts: Vec<&Origin> = std::iter::once(&description.origin)
+                .chain(description.chimera.iter())
+                .collect();
+            let indexes: Vec<usize> = std::iter::once(first).chain(chimera_index).collect();
+            let tag = &description
+                .tags
+                .iter()
+                .find(|(k, _)| *k == "restriction_fragments")
+                .unwrap()
+                .1;
+
+            assert_eq!(tag.split(',').count(), parts.len());
+            for ((origin, index), truth) in parts.iter().zip(indexes).zip(tag.split(',')) {
+                let fragment: usize = truth.rsplit(':').next().unwrap().parse().unwrap();
+                let length = refs.sequences[index].seq.len();
+                let (start, end) = digest.fragment(index, fragment);
+                if origin.strand == '-' {
+                    assert_eq!((length - end, length - start), (origin.start, origin.end));
+                } else {
+                    assert_eq!((start, end), (origin.start, origin.end));
+                }
+            }
+            assert_eq!(
+                description.length,
+                parts.iter().map(|x| x.end - x.start).sum::<usize>()
+            );
+        }
+    }
+
+    #[test]
+    fn amplicons() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+        let mut scheme = crate::amplicon::Scheme::from_stream(
+            &b"random_seq_10\t10\t14\tamp_1_LEFT\t1\nrandom_seq_10\t40\t44\tamp_1_RIGHT\t1\n"[..],
+        )
+        .unwrap();
+        scheme
+            .set_efficiency(0.0, 0.0, (50.0, 50.0), &mut rng)
+            .unwrap();
+        let targets = scheme.targets(&refs).unwrap();
+
+        for (first, chimera_index, description, _) in Fragments::new(
+            1_000,
+            (10.0, 10.0, 50.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .amplicons(&targets)
+        {
+            assert!(chimera_index.is_empty());
+            assert!(description.chimera.is_empty());
+            if description.origin.read_type != ReadType::Real {
+                continue;
+            }
+
+            assert_eq!(first, 10);
+            assert_eq!(description.length, 34);
+            assert_eq!(
+                description.labels,
+                vec![Label::Amplicon("amp_1".to_string())]
+            );
+            if description.origin.strand == '+' {
+                assert_eq!((description.origin.start, description.origin.end), (10, 44));
+            } else {
+                assert_eq!((description.origin.start, description.origin.end), (56, 90));
+            }
+        }
+    }
+
+    #[test]
+    fn transposase() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+        let transposase =
+            model::Transposase::new(model::transposase::MUA_MOTIF, 10.0, 20.0).unwrap();
+
+        let fragments: Vec<(usize, Description)> =
+            Fragments::new(5_000, (0.0, 0.0, 0.0), &refs, &length, &identity, &mut rng)
+                .transposase(&transposase)
+                .map(|(first, _, description, _)| (first, description))
+                .collect();
+
+        assert!(fragments.iter().any(|(_, x)| x.length > 12));
+        for (first, description) in fragments {
+            let ref_len = refs.sequences[first].seq.len();
+            assert!(description.origin.start < ref_len);
+            assert!(description.origin.end <= ref_len);
+            assert_eq!(
+                origin_length(&description.origin, &refs.sequences[first]),
+                description.length
+            );
+        }
+    }
+
+    #[test]
+    fn shearing() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+        let shearing = model::Shearing::new(80.0, 10.0, 10.0).unwrap();
+
+        let fragments: Vec<(usize, Description)> =
+            Fragments::new(5_000, (0.0, 0.0, 0.0), &refs, &length, &identity, &mut rng)
+                .shearing(&shearing)
+                .map(|(first, _, description, _)| (first, description))
+                .collect();
+
+        let tag = |description: &Description, key: &str| {
+            description
+                .tags
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+        // forward bounds of a fragment
+        let forward = |first: usize, description: &Description| {
+            let ref_len = refs.sequences[first].seq.len();
+            if description.origin.strand == '-' {
+                (
+                    ref_len - description.origin.end,
+                    ref_len - description.origin.start,
+                )
+            } else {
+                (description.origin.start, description.origin.end)
+            }
+        };
+
+        let mut nb_shared = 0;
+        for window in fragments.windows(2) {
+            let (first_a, a) = &window[0];
+            let (first_b, b) = &window[1];
+            if tag(a, "parent_molecule") != tag(b, "parent_molecule") {
+                continue;
+            }
+
+            // adjacent fragments of a parent share breakpoint
+            assert_eq!(first_a, first_b);
+            assert_eq!(tag(a, "parent_origin"), tag(b, "parent_origin"));
+            let (_, a_end) = forward(*first_a, a);
+            let (b_begin, _) = forward(*first_b, b);
+            if refs.sequences[*first_a].circular {
+                assert_eq!(a_end % refs.sequences[*first_a].seq.len(), b_begin);
+            } else {
+                assert_eq!(a_end, b_begin);
+            }
+            nb_shared += 1;
+        }
+        assert!(nb_shared > 0);
+    }
+
+    #[test]
+    fn size_selection() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let length = model::Length::new(8.0, 4.0)
+            .unwrap()
+            .size_selection(model::SizeSelection {
+                min: 8.0,
+                max: Some(12.0),
+                sharpness: 0.0,
+            });
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+
+        let lengths: Vec<usize> = Fragments::new(
+            5_000,
+            (10.0, 10.0, 0.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .map(|(_, _, description, _)| description.length)
+        .collect();
+
+        assert!(lengths.iter().all(|x| (8..=12).contains(x)));
+        assert!(lengths.iter().sum::<usize>() >= 5_000);
+
+        // transposase molecules ignore length model
+        let transposase = model::Transposase::new(b"G", 1.0, 3.0).unwrap();
+        let length = model::Length::new(8.0, 4.0)
+            .unwrap()
+            .size_selection(model::SizeSelection {
+                min: 1_000.0,
+                max: None,
+                sharpness: 0.0,
+            });
+        let mut fragments =
+            Fragments::new(5_000, (0.0, 0.0, 0.0), &refs, &length, &identity, &mut rng)
+                .transposase(&transposase);
+
+        assert!(fragments.next().is_none());
+        assert!(fragments.selection_failed());
+        assert!(fragments.next().is_none());
+    }
+
+    #[test]
+    fn quantity() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+
+        let number = Fragments::new(
+            u64::MAX,
+            (10.0, 10.0, 0.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .quota(Quota::new(Some(20), None))
+        .count();
+        assert_eq!(number, 20);
+
+        let mut targets = vec![Remaining::Bases(0); refs.sequences.len()];
+        targets[7] = Remaining::Bases(30);
+        targets[10] = Remaining::Reads(5);
+        let fragments: Vec<(usize, Description)> = Fragments::new(
+            u64::MAX,
+            (0.0, 0.0, 0.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .quota(Quota::new(None, Some(&targets)))
+        .map(|(first, _, description, _)| (first, description))
+        .collect();
+
+        assert!(fragments
+            .iter()
+            .all(|(first, _)| *first == 7 || *first == 10));
+        assert_eq!(
+            fragments.iter().filter(|(first, _)| *first == 10).count(),
+            5
+        );
+        let bases: usize = fragments
+            .iter()
+            .filter(|(first, _)| *first == 7)
+            .map(|(_, description)| description.length)
+            .sum();
+        assert!((30..40).contains(&bases));
+
+        // duplex template count for complement read but not for consensus read
+        let duplex = crate::simulate::duplex::Duplex::new(
+            100.0,
+            Some(model::Identity::new(99.0, 100.0, 1.0).unwrap()),
+        );
+        let fragments: Vec<Description> = Fragments::new(
+            u64::MAX,
+            (0.0, 0.0, 0.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .duplex(&duplex)
+        .quota(Quota::new(Some(10), None))
+        .map(|(_, _, description, _)| description)
+        .collect();
+        assert_eq!(fragments.len(), 5);
+    }
+
+    #[test]
+    fn quota() {
+        let mut targets = vec![Remaining::Bases(0); 3];
+        targets[1] = Remaining::Bases(30);
+        targets[2] = Remaining::Reads(2);
+        let weights = vec![1.0; 3];
+
+        let mut quota = Quota::new(Some(3), Some(&targets));
+        assert!(!quota.is_unlimited());
+        assert!(!quota.is_open(0));
+        assert!(quota.is_open(1));
+        assert_eq!(quota.available(0, &ReadType::Real), 0);
+        assert_eq!(quota.available(0, &ReadType::Junk), 3);
+        assert_eq!(quota.available(1, &ReadType::Real), 3);
+        assert_eq!(quota.available(2, &ReadType::Real), 2);
+
+        quota.consume(2, &ReadType::Real, 10);
+        quota.consume(0, &ReadType::Junk, 10);
+        assert_eq!(quota.available(2, &ReadType::Real), 1);
+        assert_eq!(quota.available(1, &ReadType::Real), 1);
+        assert!(!quota.is_reached(&weights));
+
+        quota.consume(1, &ReadType::Real, 10);
+        assert!(quota.is_reached(&weights));
+
+        let mut quota = Quota::new(None, Some(&targets));
+        quota.consume(1, &ReadType::Real, 40);
+        quota.consume(2, &ReadType::Real, 10);
+        quota.consume(2, &ReadType::Real, 10);
+        assert!(quota.is_reached(&weights));
+
+        assert!(Quota::default().is_unlimited());
+        assert_eq!(Quota::default().available(0, &ReadType::Real), u64::MAX);
+    }
+
+    #[test]
+    fn pcr() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+        let pcr = crate::simulate::pcr::Pcr::new(10, 90.0, 1.0, 20.0, 3.0).unwrap();
+
+        let fragments: Vec<(usize, Vec<usize>, Description)> =
+            Fragments::new(2_000, (0.0, 0.0, 0.0), &refs, &length, &identity, &mut rng)
+                .pcr(&pcr)
+                .map(|(first, chimera_index, description, _)| (first, chimera_index, description))
+                .collect();
+
+        let family = |description: &Description| {
+            description
+                .tags
+                .iter()
+                .find(|(key, _)| *key == "pcr_family")
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+
+        // reads of a family are consecutive
+        let mut families: Vec<String> = fragments.iter().map(|(_, _, x)| family(x)).collect();
+        let nb_read = families.len();
+        families.dedup();
+        let nb_family = families.len();
+        families.sort();
+        families.dedup();
+        assert_eq!(families.len(), nb_family);
+        assert!(nb_read > nb_family);
+
+        let mut nb_switch = 0;
+        for (_, chimera_index, description) in fragments.iter() {
+            if description.tags.iter().any(|(key, _)| *key == "pcr_switch") {
+                nb_switch += 1;
+                assert_eq!(chimera_index.len(), 1);
+                assert_eq!(description.chimera.len(), 1);
+                assert_eq!(
+                    description.length,
+                    description.origin.end - description.origin.start + description.chimera[0].end
+                        - description.chimera[0].start
+                );
+            } else {
+                assert!(chimera_index.is_empty());
+            }
+        }
+        assert!(nb_switch > 0);
+    }
+
+    #[test]
+    fn foldback() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+
+        let descriptions: Vec<Description> = Fragments::new(
+            10_000,
+            (10.0, 10.0, 10.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .foldback(0.5)
+        .map(|(_, _, description, _)| description)
+        .collect();
+
+        let mut nb_foldback = 0;
+        for description in descriptions.iter() {
+            if let Some(foldback) = description.foldback {
+                let back = foldback.length;
+                let forward = description.length - back;
+
+                assert!(back >= 1 && back <= forward);
+                assert_eq!(description.origin.read_type, ReadType::Real);
+                assert!(description.chimera.is_empty());
+                nb_foldback += 1;
+            }
+        }
+
+        let fraction = nb_foldback as f64 / descriptions.len() as f64;
+        assert!((0.3..0.45).contains(&fraction));
+    }
+
+    #[test]
+    fn duplex() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let length = model::Length::new(8.0, 2.0).unwrap();
+        let identity = model::Identity::new(85.0, 95.0, 5.0).unwrap();
+
+        let duplex = crate::simulate::duplex::Duplex::new(50.0, None);
+        let descriptions: Vec<Description> = Fragments::new(
+            10_000,
+            (10.0, 10.0, 10.0),
+            &refs,
+            &length,
+            &identity,
+            &mut rng,
+        )
+        .duplex(&duplex)
+        .map(|(_, _, description, _)| description)
+        .collect();
+
+        let templates: Vec<&Description> = descriptions
+            .iter()
+            .filter(|d| crate::simulate::duplex::is_template(d))
+            .collect();
+        assert!(templates
+            .iter()
+            .all(|d| d.origin.read_type == ReadType::Real && d.chimera.is_empty()));
+
+        let fraction = templates.len() as f64 / descriptions.len() as f64;
+        assert!((0.3..0.45).contains(&fraction));
+
+        // complement reads are count in target, consensus reads aren't
+        let consensus = model::Identity::new(99.0, 100.0, 1.0).unwrap();
+        let duplex = crate::simulate::duplex::Duplex::new(100.0, Some(consensus));
+        let bases: usize =
+            Fragments::new(10_000, (0.0, 0.0, 0.0), &refs, &length, &identity, &mut rng)
+                .duplex(&duplex)
+                .map(|(_, _, description, _)| 2 * description.length)
+                .sum();
+
+        assert!((10_000..10_100).contains(&bases));
+    }
+
     #[test]
     fn shape() {
         let mut rng = rand::rngs::StdRng::seed_from_u64(42);
@@ -827,9 +2356,9 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
 
             lengths[ori1].push((des.origin.end - des.origin.start) as f64);
 
-            if let Some(chimera) = des.chimera {
+            if let Some(chimera) = des.chimera.first() {
                 type_count[3] += 1;
-                lengths[ori2].push((chimera.end - chimera.start) as f64);
+                lengths[ori2[0]].push((chimera.end - chimera.start) as f64);
             }
 
             identitys.push(des.identity);
@@ -2,12 +2,16 @@
 
 /* mod declaration */
 pub mod description;
+pub mod duplex;
 pub mod error;
 pub mod fragments;
+pub mod pacbio;
+pub mod pcr;
 pub mod quality;
+mod setup;
 
 /* standard use */
-use std::io::Write;
+use std::borrow::Cow;
 
 /* crate use */
 use anyhow::{Context, Result};
@@ -19,9 +23,14 @@ use rayon::prelude::*;
 use crate::cli;
 use crate::model;
 use crate::references::*;
-use description::{Description, Origin, ReadType};
+use crate::variant;
+use description::{Description, DuplexRead, Label, Origin, ReadType};
 use fragments::Fragments;
 
+/* constant definition */
+/// Max number of consecutive generation steps without any written read
+const MAX_EMPTY_STEP: usize = 100;
+
 #[cfg(not(tarpaulin_include))]
 /// main simulate function
 pub fn simulate(params: cli::simulate::Command) -> Result<()> {
@@ -36,24 +45,85 @@ pub fn simulate(params: cli::simulate::Command) -> Result<()> {
         )
     };
 
-    log::info!("Start init lenght model");
-    let length = model::Length::new(params.length.0 as f64, params.length.1 as f64)
-        .with_context(|| "Init length model")?;
-    log::info!("End init lenght model");
+    let (length, polymerase) = setup::length_models(&params, &mut main_rng)?;
 
     log::info!("Start read reference");
-    let references = References::from_stream_adjusted_weight(
-        niffler::get_reader(Box::new(std::io::BufReader::new(
-            std::fs::File::open(params.reference_path).with_context(|| "Read reference file")?,
-        )))
-        .with_context(|| "Read reference file niffler")?
-        .0,
-        params.small_plasmid_bias,
+    let mut community = setup::read_community(&params, &mut main_rng)?;
+    let mut population = setup::read_population(&params)?;
+    let annotation = setup::read_annotation(&params)?;
+    let samples = setup::read_samples(&params)?;
+
+    let (references, samples_base) = setup::read_references(
+        &params,
+        community.as_mut(),
+        population.as_mut(),
+        annotation.as_ref(),
+        samples.as_ref().map(|(samples, _)| samples.as_slice()),
         &length,
         &mut main_rng,
     )?;
+
+    if let Some(population) = &population {
+        log::info!("Start write strains truth");
+        population
+            .write_truth(&mut std::io::BufWriter::new(
+                std::fs::File::create(format!("{}_strains.tsv", params.truth_prefix))
+                    .with_context(|| "Create strains truth")?,
+            ))
+            .with_context(|| "Write strains truth")?;
+        log::info!("End write strains truth");
+    }
     log::info!("End read reference");
 
+    let (mut references, normal) = setup::build_haplotypes(&params, references, &mut main_rng)?;
+
+    if params.direct_rna {
+        references.stranded = true;
+    }
+
+    let cells = setup::build_cells(&params, &mut references, &mut main_rng)?;
+
+    if let Some(copy_number) = &params.copy_number {
+        log::info!("Start read copy number");
+        let intervals = crate::copy_number::from_stream(
+            std::fs::File::open(copy_number).with_context(|| "Read copy number file")?,
+        )
+        .with_context(|| "Parse copy number file")?;
+
+        references
+            .add_copy_numbers(&intervals, params.ploidy)
+            .with_context(|| "Add copy number")?;
+        log::info!("End read copy number");
+    }
+
+    let reference_targets = if let Some(value) = &params.quantity_per_reference {
+        log::info!("Start set quantity per reference");
+        let quantities = cli::simulate::reference_quantities(value)
+            .with_context(|| "Parse quantity per reference")?;
+        let (targets, weights) = reference_targets(&references, &quantities, &length)?;
+        references
+            .set_weights(weights)
+            .with_context(|| "Set weight of references with a quantity")?;
+        log::info!("End set quantity per reference");
+
+        Some(targets)
+    } else {
+        None
+    };
+
+    if !params.contaminants.is_empty() {
+        log::info!("Start read contaminants");
+        references
+            .add_contaminants(
+                &params.contaminants,
+                params.small_plasmid_bias,
+                &length,
+                &mut main_rng,
+            )
+            .with_context(|| "Add contaminants")?;
+        log::info!("End read contaminants");
+    }
+
     log::info!("Start init identity model");
     let identity = model::Identity::new(
         params.identity.0 as f64,
@@ -63,17 +133,36 @@ pub fn simulate(params: cli::simulate::Command) -> Result<()> {
     .with_context(|| "Init identity model")?;
     log::info!("End init length model");
 
-    log::info!("Start init adapter model");
-    let adapter = model::Adapter::new(
-        params.start_adapter_seq.as_bytes().to_vec(),
-        params.end_adapter_seq.as_bytes().to_vec(),
-        params.start_adapter.0 as f64,
-        params.start_adapter.1 as f64,
-        params.end_adapter.0 as f64,
-        params.end_adapter.1 as f64,
-    )
-    .with_context(|| "Init adapter model")?;
-    log::info!("End init adapter model");
+    let (adapter, reverse_adapter) = setup::adapter_models(&params, annotation.is_some())?;
+
+    let setup::Library {
+        chimera,
+        transposase,
+        shearing,
+        scheme,
+        pcr,
+        pore_c,
+    } = setup::library_models(&params, &mut main_rng)?;
+
+    let transcript = setup::transcript_model(&params, annotation.is_some())?;
+
+    let (samples, barcoding) = if let Some((samples, kit)) = samples {
+        (
+            Some(samples),
+            Some(
+                crate::barcode::Barcoding::new(
+                    kit,
+                    params.barcode_truncation,
+                    params.unclassified_rate,
+                )
+                .with_context(|| "Init barcoding model")?,
+            ),
+        )
+    } else {
+        (None, None)
+    };
+
+    let (smrtbell, pass_accuracy) = setup::pacbio_models(&params, polymerase)?;
 
     log::info!("Start init glitches model");
     let glitches = model::Glitch::new(
@@ -84,62 +173,68 @@ pub fn simulate(params: cli::simulate::Command) -> Result<()> {
     .with_context(|| "Init glitches model")?;
     log::info!("End init glitches model");
 
-    log::info!("Start read error model");
-    let error = if params.error_model == *"random" {
-        log::info!("Use random error model");
-        model::Error::random(7)
-    } else {
-        log::info!("Use file error model");
-        let error_path = crate::cli::simulate::found_model(params.error_model, "error".to_string())
-            .with_context(|| "Get path of error model")?;
-        model::Error::from_stream(
-            niffler::get_reader(Box::new(std::io::BufReader::new(
-                std::fs::File::open(error_path).with_context(|| "Open error model")?,
-            )))
-            .with_context(|| "Open error model")?
-            .0,
-            &mut main_rng,
-        )
-        .with_context(|| "Init error model")?
-    };
-    let k = error.k();
-    log::info!("End read error model");
-
-    log::info!("Start read quality score model");
-    let qscore = if params.qscore_model == *"ideal" {
-        log::info!("Use ideal quality score model");
-        model::Quality::ideal()
-    } else if params.qscore_model == *"random" {
-        log::info!("Use random quality score model");
-        model::Quality::random()
-    } else {
-        log::info!("Use file error model");
-        let qscore_path =
-            crate::cli::simulate::found_model(params.qscore_model, "qscore".to_string())
-                .with_context(|| "Get path of qscore model")?;
-        model::Quality::from_stream(
-            niffler::get_reader(Box::new(std::io::BufReader::new(
-                std::fs::File::open(qscore_path).with_context(|| "Open qscore model")?,
-            )))
-            .with_context(|| "Open qscore model")?
-            .0,
-        )?
+    let error = setup::error_model(&params, &mut main_rng)?;
+
+    let qscore = setup::qscore_model(&params)?;
+
+    let len_ref = references.genome_length;
+    // number of reads and reference targets stop generation, number of base is unlimited
+    let reads = params.quantity.as_ref().and_then(|x| x.number_of_reads());
+    let total_base = match (samples_base, params.quantity.as_ref()) {
+        (Some(bases), _) => bases,
+        (None, Some(quantity)) => quantity.number_of_base(len_ref),
+        (None, None) if reference_targets.is_some() => u64::MAX,
+        (None, None) => unreachable!(), // clap require quantity, quantity per reference or samples
     };
-    log::info!("End read quality score model");
-
-    let len_ref = references
-        .sequences
-        .iter()
-        .map(|x| x.seq.len() as u64)
-        .sum();
-    let total_base = params.quantity.number_of_base(len_ref);
-    let base_limit = if let Some(limit) = params.nb_base_store {
+    let base_limit = if let Some(limit) = &params.nb_base_store {
+        if limit.number_of_reads().is_some() {
+            anyhow::bail!(crate::error::Cli::QuantityInReads(
+                "number_base_store".to_string()
+            ));
+        }
         limit.number_of_base(len_ref)
     } else {
         total_base
     };
-    let mut base_produce = 0;
-    log::info!("Target number of base {}", total_base);
+    if let Some(reads) = reads {
+        log::info!("Target number of reads {}", reads);
+    } else if total_base != u64::MAX {
+        log::info!("Target number of base {}", total_base);
+    }
+
+    let duplex = setup::duplex_model(&params)?;
+    let mut duplex_output = if let Some(path) = &params.duplex_output {
+        Some(std::io::BufWriter::new(
+            std::fs::File::create(path).with_context(|| "Open duplex output file")?,
+        ))
+    } else {
+        None
+    };
+
+    let models = Models {
+        length: &length,
+        identity: &identity,
+        adapter: &adapter,
+        reverse_adapter: reverse_adapter.as_ref(),
+        transcript: transcript.as_ref(),
+        chimera: &chimera,
+        pore_c: pore_c.as_ref(),
+        scheme: scheme.as_ref(),
+        pcr: pcr.as_ref(),
+        transposase: transposase.as_ref(),
+        shearing: shearing.as_ref(),
+        reads,
+        reference_targets: reference_targets.as_deref(),
+        cells: cells.as_ref(),
+        barcoding: barcoding.as_ref(),
+        smrtbell: smrtbell.as_ref(),
+        pass_accuracy: pass_accuracy.as_ref(),
+        duplex: duplex.as_ref(),
+        foldback: params.foldback,
+        error: &error,
+        glitches: &glitches,
+        qscore: &qscore,
+    };
 
     let mut output: std::io::BufWriter<Box<dyn std::io::Write>> =
         if let Some(output_path) = params.output_path {
@@ -150,80 +245,613 @@ pub fn simulate(params: cli::simulate::Command) -> Result<()> {
             std::io::BufWriter::new(Box::new(std::io::stdout()))
         };
 
+    let simulated = if let Some(mode) = params.pacbio {
+        let mut pacbio_output = pacbio::Output::new(
+            &mut output,
+            params.bam_path.as_deref(),
+            params.movie.clone(),
+            mode,
+        )?;
+        let simulated = pacbio::write_reads(
+            &references,
+            &models,
+            (params.junk, params.random, params.chimera),
+            (total_base, base_limit),
+            &mut pacbio_output,
+            &mut main_rng,
+        )?;
+        pacbio_output.finish()?;
+
+        simulated
+    } else {
+        write_reads(
+            &references,
+            &models,
+            (params.junk, params.random, params.chimera),
+            (total_base, base_limit),
+            &mut output,
+            duplex_output.as_mut().map(|x| x as &mut dyn std::io::Write),
+            &mut main_rng,
+        )?
+    };
+
+    if let (Some(normal), Some(normal_path)) = (normal, params.normal_output) {
+        log::info!("Start generate matched normal reads");
+        let mut normal_output = std::io::BufWriter::new(
+            std::fs::File::create(normal_path).with_context(|| "Open normal output file")?,
+        );
+        let normal_models = Models {
+            cells: None,
+            duplex: None,
+            ..models
+        };
+        if let Some(mode) = params.pacbio {
+            pacbio::write_reads(
+                &normal,
+                &normal_models,
+                (params.junk, params.random, params.chimera),
+                (total_base, base_limit),
+                &mut pacbio::Output::new(&mut normal_output, None, params.movie, mode)?,
+                &mut main_rng,
+            )?;
+        } else {
+            write_reads(
+                &normal,
+                &normal_models,
+                (params.junk, params.random, params.chimera),
+                (total_base, base_limit),
+                &mut normal_output,
+                None,
+                &mut main_rng,
+            )?;
+        }
+        log::info!("End generate matched normal reads");
+    }
+
+    if let Some(annotation) = annotation {
+        log::info!("Start write transcripts truth");
+        annotation
+            .write_truth(
+                &mut std::io::BufWriter::new(
+                    std::fs::File::create(format!("{}_transcripts.tsv", params.truth_prefix))
+                        .with_context(|| "Create transcripts truth")?,
+                ),
+                &simulated.transcript,
+            )
+            .with_context(|| "Write transcripts truth")?;
+        log::info!("End write transcripts truth");
+    }
+
+    if let Some(samples) = samples {
+        log::info!("Start write samples truth");
+        crate::barcode::write_truth(
+            &mut std::io::BufWriter::new(
+                std::fs::File::create(format!("{}_samples.tsv", params.truth_prefix))
+                    .with_context(|| "Create samples truth")?,
+            ),
+            &samples,
+            &simulated.sample,
+        )
+        .with_context(|| "Write samples truth")?;
+        log::info!("End write samples truth");
+    }
+
+    if let Some(cells) = cells {
+        log::info!("Start write cells truth");
+        cells
+            .write_truth(
+                &mut std::io::BufWriter::new(
+                    std::fs::File::create(format!("{}_cells.tsv", params.truth_prefix))
+                        .with_context(|| "Create cells truth")?,
+                ),
+                &simulated.cell,
+            )
+            .with_context(|| "Write cells truth")?;
+        log::info!("End write cells truth");
+    }
+
+    if let Some(scheme) = scheme {
+        log::info!("Start write amplicons truth");
+        scheme
+            .write_truth(
+                &mut std::io::BufWriter::new(
+                    std::fs::File::create(format!("{}_amplicons.tsv", params.truth_prefix))
+                        .with_context(|| "Create amplicons truth")?,
+                ),
+                &simulated.amplicon,
+            )
+            .with_context(|| "Write amplicons truth")?;
+        log::info!("End write amplicons truth");
+    }
+
+    if duplex.is_some() {
+        log::info!("Start write duplex truth");
+        duplex::write_truth(
+            &mut std::io::BufWriter::new(
+                std::fs::File::create(format!("{}_duplex.tsv", params.truth_prefix))
+                    .with_context(|| "Create duplex truth")?,
+            ),
+            &simulated.duplex,
+        )
+        .with_context(|| "Write duplex truth")?;
+        log::info!("End write duplex truth");
+    }
+
+    if let Some(community) = community {
+        log::info!("Start write community truth");
+        community
+            .write_truth(
+                &mut std::io::BufWriter::new(
+                    std::fs::File::create(format!("{}_abundance.tsv", params.truth_prefix))
+                        .with_context(|| "Create community truth")?,
+                ),
+                &simulated.genome,
+            )
+            .with_context(|| "Write community truth")?;
+        log::info!("End write community truth");
+    }
+
+    if !params.contaminants.is_empty() {
+        log::info!("Start write contaminant truth");
+        crate::contaminant::write_truth(
+            &mut std::io::BufWriter::new(
+                std::fs::File::create(format!("{}_contaminant.tsv", params.truth_prefix))
+                    .with_context(|| "Create contaminant truth")?,
+            ),
+            &params.contaminants,
+            &simulated.contaminant,
+            simulated.total,
+        )
+        .with_context(|| "Write contaminant truth")?;
+        log::info!("End write contaminant truth");
+    }
+
+    Ok(())
+}
+
+type Seq = Vec<u8>;
+type Quality = Vec<u8>;
+type Read = (Description, Seq, Quality);
+
+/// Remaining quantity and weight of each reference from quantity per reference
+///
+/// A quantity is split between sequences with same id, weights of references with a target are scale to reach targets together, other references are ignored
+fn reference_targets(
+    references: &References,
+    quantities: &[cli::simulate::ReferenceQuantity],
+    length: &model::Length,
+) -> Result<(Vec<fragments::Remaining>, Vec<f64>)> {
+    let mut targets = vec![fragments::Remaining::Bases(0); references.sequences.len()];
+    let mut weights = vec![0.0; references.weights.len()];
+
+    for target in quantities {
+        let indexs: Vec<usize> = references
+            .sequences
+            .iter()
+            .enumerate()
+            .filter(|(_, reference)| reference.id == target.id)
+            .map(|(index, _)| index)
+            .collect();
+        if indexs.is_empty() {
+            anyhow::bail!(crate::error::Cli::UnknownReference(target.id.clone()));
+        }
+
+        let total_weight: f64 = indexs.iter().map(|x| references.weights[*x]).sum();
+        for index in indexs.iter() {
+            let share = if total_weight != 0.0 {
+                references.weights[*index] / total_weight
+            } else {
+                1.0 / indexs.len() as f64
+            };
+            let ref_len = references.sequences[*index].seq.len() as u64;
+
+            let (remaining, bases) = match target.quantity.number_of_reads() {
+                Some(reads) => {
+                    let reads = (reads as f64 * share).round();
+                    (
+                        fragments::Remaining::Reads(reads as u64),
+                        reads * length.mean(),
+                    )
+                }
+                None if target.quantity.is_coverage() => {
+                    let bases = target.quantity.number_of_base(ref_len);
+                    (fragments::Remaining::Bases(bases), bases as f64)
+                }
+                None => {
+                    let bases = (target.quantity.number_of_base(ref_len) as f64 * share).round();
+                    (fragments::Remaining::Bases(bases as u64), bases)
+                }
+            };
+
+            targets[*index] = remaining;
+            weights[*index] = references.weights[*index] * bases / ref_len.max(1) as f64;
+        }
+    }
+
+    Ok((targets, weights))
+}
+
+/// Complement read and optional consensus read of a duplex pair
+type DuplexReads = (Read, Option<Read>);
+
+/// Models use to generate reads
+struct Models<'a> {
+    length: &'a model::Length,
+    identity: &'a model::Identity,
+    adapter: &'a model::Adapter,
+    reverse_adapter: Option<&'a model::Adapter>,
+    transcript: Option<&'a model::Transcript>,
+    chimera: &'a model::Chimera,
+    pore_c: Option<&'a crate::pore_c::PoreC>,
+    scheme: Option<&'a crate::amplicon::Scheme>,
+    pcr: Option<&'a pcr::Pcr>,
+    transposase: Option<&'a model::Transposase>,
+    shearing: Option<&'a model::Shearing>,
+    reads: Option<u64>,
+    reference_targets: Option<&'a [fragments::Remaining]>,
+    cells: Option<&'a crate::single_cell::Cells>,
+    barcoding: Option<&'a crate::barcode::Barcoding>,
+    smrtbell: Option<&'a model::Smrtbell>,
+    pass_accuracy: Option<&'a model::PassAccuracy>,
+    duplex: Option<&'a duplex::Duplex>,
+    foldback: f64,
+    error: &'a model::Error,
+    glitches: &'a model::Glitch,
+    qscore: &'a model::Quality,
+}
+
+/// Number of reads and bases produce, in total and for each genome, contaminant, transcript, cell, sample and amplicon, and names of duplex pairs
+#[derive(Debug, Default)]
+struct Simulated {
+    total: (u64, u64),
+    genome: rustc_hash::FxHashMap<String, (u64, u64)>,
+    contaminant: rustc_hash::FxHashMap<String, (u64, u64)>,
+    transcript: rustc_hash::FxHashMap<String, (u64, u64)>,
+    cell: rustc_hash::FxHashMap<String, (u64, u64)>,
+    sample: rustc_hash::FxHashMap<String, (u64, u64)>,
+    amplicon: rustc_hash::FxHashMap<String, (u64, u64)>,
+    duplex: Vec<(String, String)>,
+}
+
+impl Simulated {
+    /// Count a read in total and in group of first label of each kind
+    fn add(&mut self, description: &Description, read_length: u64) {
+        self.total.0 += 1;
+        self.total.1 += read_length;
+
+        for (index, label) in description.labels.iter().enumerate() {
+            if description.labels[..index]
+                .iter()
+                .any(|x| std::mem::discriminant(x) == std::mem::discriminant(label))
+            {
+                continue;
+            }
+
+            let (group, value) = match label {
+                Label::Genome(value) => (&mut self.genome, value),
+                Label::Contaminant(value) => (&mut self.contaminant, value),
+                Label::Transcript(value) => (&mut self.transcript, value),
+                Label::Cell(value) => (&mut self.cell, value),
+                Label::Barcode(value) => (&mut self.sample, value),
+                Label::Amplicon(value) => (&mut self.amplicon, value),
+                Label::Gene(_) | Label::Umi(_) => continue,
+            };
+            let count = group.entry(value.clone()).or_default();
+            count.0 += 1;
+            count.1 += read_length;
+        }
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Generate reads from references and write them in output
+///
+/// Rates are junk, random and chimera rate, bases are total number of base and number of base store in ram
+fn write_reads<W>(
+    references: &References,
+    models: &Models,
+    rates: (f64, f64, f64),
+    bases: (u64, u64),
+    output: &mut W,
+    mut duplex_output: Option<&mut dyn std::io::Write>,
+    rng: &mut rand::rngs::StdRng,
+) -> Result<Simulated>
+where
+    W: std::io::Write,
+{
+    let (total_base, base_limit) = bases;
+
+    let mut base_produce = 0;
+    let mut simulated = Simulated::default();
+    let digest = models.pore_c.map(|x| x.digest(references));
+    let targets = models
+        .scheme
+        .map(|x| x.targets(references))
+        .transpose()
+        .with_context(|| "Find amplicons in references")?;
+
+    // quota count only written reads, each step generate remaining quota
+    let mut quota = fragments::Quota::new(models.reads, models.reference_targets);
+    let mut empty_step = 0;
     while base_produce < total_base {
+        if quota.is_reached(&references.weights) {
+            break;
+        }
+
         let base_loop = if base_limit > total_base - base_produce {
             total_base - base_produce
         } else {
             base_limit
         };
 
-        base_produce += base_loop;
+        if quota.is_unlimited() {
+            base_produce += base_loop;
+        }
 
         log::info!("Start generate {} bases", base_loop);
-        let sequences: Vec<(Description, Seq, Quality)> = Fragments::new(
+        let mut fragments = Fragments::new(
             base_loop,
-            (params.junk, params.random, params.chimera),
-            &references,
-            &length,
-            &identity,
-            &mut main_rng,
+            rates,
+            references,
+            models.length,
+            models.identity,
+            rng,
         )
-        .par_bridge()
-        .map(|(ref_idx, ref_idx2, description, seed)| {
-            generate_read(
-                (
-                    &references.sequences[ref_idx],
-                    &references.sequences[ref_idx2],
-                ),
-                description,
-                &adapter,
-                &error,
-                &glitches,
-                &qscore,
-                rand::rngs::StdRng::seed_from_u64(seed),
-            )
-            .unwrap()
-        })
-        .collect();
+        .chimera_model(models.chimera);
+        if let Some(digest) = digest.as_ref() {
+            fragments = fragments.pore_c(digest);
+        }
+        if let Some(targets) = targets.as_ref() {
+            fragments = fragments.amplicons(targets);
+        }
+        if let Some(pcr) = models.pcr {
+            fragments = fragments.pcr(pcr);
+        }
+        if !quota.is_unlimited() {
+            fragments = fragments.quota(quota.clone());
+        }
+        if let Some(shearing) = models.shearing {
+            fragments = fragments.shearing(shearing);
+        }
+        if let Some(transposase) = models.transposase {
+            fragments = fragments.transposase(transposase);
+        }
+        if let Some(transcript) = models.transcript {
+            fragments = fragments.transcript_model(transcript);
+        }
+        if let Some(cells) = models.cells {
+            fragments = fragments.cells(cells);
+        }
+
+        if let Some(duplex) = models.duplex {
+            fragments = fragments.duplex(duplex);
+        }
+        if models.foldback != 0.0 {
+            fragments = fragments.foldback(models.foldback / 100.0);
+        }
+
+        let sequences: Vec<(usize, u64, Read, Option<DuplexReads>)> = fragments
+            .by_ref()
+            .par_bridge()
+            .map(|(ref_idx, chimera_idx, description, seed)| {
+                let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+                let fragment_length = description.length as u64;
+                let read_refs: Vec<&Reference> = std::iter::once(ref_idx)
+                    .chain(chimera_idx)
+                    .map(|idx| &references.sequences[idx])
+                    .collect();
+
+                // complement and consensus of a duplex template get their own seed
+                let duplex = if duplex::is_template(&description) {
+                    Some((description.clone(), rng.gen::<u64>(), rng.gen::<u64>()))
+                } else {
+                    None
+                };
+
+                let read = simulate_read(&read_refs, description, models, rng);
+
+                let duplex_reads = duplex.map(|(template, complement_seed, consensus_seed)| {
+                    let mut rng = rand::rngs::StdRng::seed_from_u64(complement_seed);
+                    let description = duplex::complement(
+                        &template,
+                        read_refs[0],
+                        models.identity.get_identity(&mut rng),
+                    );
+                    let complement = simulate_read(&read_refs, description, models, rng);
+
+                    let consensus =
+                        models
+                            .duplex
+                            .and_then(|x| x.consensus_identity())
+                            .map(|identity| {
+                                let mut rng = rand::rngs::StdRng::seed_from_u64(consensus_seed);
+                                let description =
+                                    duplex::consensus(&template, identity.get_identity(&mut rng));
+                                simulate_read(&read_refs, description, models, rng)
+                            });
+
+                    (complement, consensus)
+                });
+
+                (ref_idx, fragment_length, read, duplex_reads)
+            })
+            .collect();
+        if fragments.selection_failed() {
+            anyhow::bail!(crate::error::Model::SizeSelectionNoFragment(
+                fragments::MAX_SELECTION_STEP
+            ));
+        }
         log::info!("End generate sequences");
 
         log::info!("Start write {} bases", base_loop);
-        for (comment, seq, qual) in sequences {
-            if seq.len() <= 14 {
+        let mut written = 0;
+        for (ref_idx, fragment_length, mut read, duplex_reads) in sequences {
+            let read_type = read.0.origin.read_type.clone();
+            let available = quota.available(ref_idx, &read_type);
+            if read.1.len() <= 14 || available == 0 {
                 continue;
             }
+            let name = read_name(&read.0, rng);
+
+            let mut records = 1;
+            match duplex_reads {
+                Some((mut complement, consensus)) if complement.1.len() > 14 && available >= 2 => {
+                    let complement_name = read_name(&complement.0, rng);
+
+                    read.0.duplex = Some(DuplexRead::Template(Some(complement_name.clone())));
+                    complement.0.duplex = Some(DuplexRead::Complement(Some(name.clone())));
+
+                    write_read(output, &name, read, models, &mut simulated)?;
+                    write_read(output, &complement_name, complement, models, &mut simulated)?;
+                    records += 1;
+
+                    if let (Some(consensus), Some(duplex_output)) =
+                        (consensus, duplex_output.as_mut())
+                    {
+                        if consensus.1.len() > 14 {
+                            write_read(
+                                duplex_output,
+                                &format!("{};{}", name, complement_name),
+                                consensus,
+                                models,
+                                &mut Simulated::default(),
+                            )?;
+                        }
+                    }
+
+                    simulated.duplex.push((name, complement_name));
+                }
+                _ => {
+                    // template without his complement isn't a duplex read
+                    read.0.duplex = None;
+                    write_read(output, &name, read, models, &mut simulated)?
+                }
+            }
 
-            writeln!(
-                output,
-                "@{} {}\n{}\n+ {}\n{}",
-                uuid::Uuid::new_v3(
-                    &uuid::Uuid::NAMESPACE_X500,
-                    &main_rng.gen::<u128>().to_be_bytes()
-                )
-                .to_hyphenated(),
-                comment,
-                std::str::from_utf8(&seq[k..(seq.len() - k)])
-                    .with_context(|| "Write read in output file")?, // begin and end of fragment is just random base
-                comment,
-                std::str::from_utf8(&qual[k..seq.len() - k])
-                    .with_context(|| "Write read in output file")?
-            )
-            .with_context(|| "Write read in output file")?;
+            for _ in 0..records {
+                quota.consume(ref_idx, &read_type, fragment_length);
+            }
+            written += records;
         }
         log::info!("End write sequences");
+
+        if !quota.is_unlimited() && written == 0 {
+            empty_step += 1;
+            if empty_step == MAX_EMPTY_STEP {
+                anyhow::bail!(crate::error::Model::NoReadWritten(MAX_EMPTY_STEP));
+            }
+        } else {
+            empty_step = 0;
+        }
     }
 
+    Ok(simulated)
+}
+
+/// Name of a read, an uuid prefixed by contaminant_ for contaminant reads
+fn read_name(description: &Description, rng: &mut rand::rngs::StdRng) -> String {
+    format!(
+        "{}{}",
+        if description
+            .labels
+            .iter()
+            .any(|x| matches!(x, Label::Contaminant(_)))
+        {
+            "contaminant_"
+        } else {
+            ""
+        },
+        uuid::Uuid::new_v3(
+            &uuid::Uuid::NAMESPACE_X500,
+            &rng.gen::<u128>().to_be_bytes()
+        )
+        .to_hyphenated()
+    )
+}
+
+/// Write a read in output and count it, random bases at begin and end of read are remove
+fn write_read<W>(
+    output: &mut W,
+    name: &str,
+    read: Read,
+    models: &Models,
+    simulated: &mut Simulated,
+) -> Result<()>
+where
+    W: std::io::Write + ?Sized,
+{
+    let k = models.error.k();
+    let (comment, mut seq, mut qual) = read;
+
+    if let Some(transcript) = models.transcript.filter(|x| x.is_direct_rna()) {
+        let end = seq.len() - k;
+        transcript.rna_read(&mut seq[k..end], &mut qual[k..end]);
+    }
+
+    let read_length = (seq.len() - 2 * k) as u64;
+    simulated.add(&comment, read_length);
+
+    writeln!(
+        output,
+        "@{} {}\n{}\n+ {}\n{}",
+        name,
+        comment,
+        std::str::from_utf8(&seq[k..(seq.len() - k)])
+            .with_context(|| "Write read in output file")?, // begin and end of fragment is just random base
+        comment,
+        std::str::from_utf8(&qual[k..seq.len() - k])
+            .with_context(|| "Write read in output file")?
+    )
+    .with_context(|| "Write read in output file")?;
+
     Ok(())
 }
 
-type Seq = Vec<u8>;
-type Quality = Vec<u8>;
+/// Choose adapters of a read and generate it
+fn simulate_read(
+    read_refs: &[&Reference],
+    mut description: Description,
+    models: &Models,
+    mut rng: rand::rngs::StdRng,
+) -> Read {
+    let adapter = match models.reverse_adapter {
+        Some(reverse) if description.origin.strand == '-' => reverse,
+        _ => models.adapter,
+    };
+    let adapter = match crate::single_cell::barcodes(&description) {
+        Some((start, end)) => Cow::Owned(adapter.with_barcodes(start, end)),
+        None => Cow::Borrowed(adapter),
+    };
+    let adapters = match models.barcoding {
+        Some(barcoding) => barcoding.adapters(&mut description, read_refs, &adapter, &mut rng),
+        None => vec![adapter.into_owned()],
+    };
+    let adapter_models: Vec<&model::Adapter> = adapters.iter().collect();
+
+    generate_read(
+        read_refs,
+        description,
+        &adapter_models,
+        models.error,
+        models.glitches,
+        models.qscore,
+        rng,
+    )
+    .unwrap()
+}
+
+/// Apply PCR polymerase errors of description on fragment
+fn add_pcr_errors(fragment: &mut [u8], description: &mut Description) {
+    pcr::add_errors(fragment, &mut description.pcr_errors);
+}
 
 /// Function realy generate read
+///
+/// References and adapter models are given for first part and each chimeric part, last adapter model is use for remaining parts
 fn generate_read<R>(
-    references: (&Reference, &Reference),
+    references: &[&Reference],
     mut description: Description,
-    adapter_model: &model::Adapter,
+    adapter_models: &[&model::Adapter],
     error_model: &model::Error,
     glitch_model: &model::Glitch,
     qscore_model: &model::Quality,
@@ -235,7 +863,15 @@ where
     let k = error_model.k();
 
     // Estimate size of final fragment all edit is consider as insertion -> it's overestimation
-    let mut estimate_length = 2 * k + adapter_model.max_len() * 2 + description.length;
+    let adapter_model = |i: usize| adapter_models[i.min(adapter_models.len() - 1)];
+    let mut estimate_length = 2 * k
+        + (description.chimera.len() + 1)
+            * adapter_models
+                .iter()
+                .map(|x| x.max_len())
+                .max()
+                .unwrap_or(0)
+        + description.length;
     estimate_length +=
         error::number_of_edit(description.identity, estimate_length).round() as usize;
 
@@ -243,29 +879,56 @@ where
     let mut raw_fragment = Vec::with_capacity(estimate_length);
     raw_fragment.extend(crate::random_seq(k, &mut rng));
 
-    let start_adapter = adapter_model.get_start(&mut rng);
+    let start_adapter = adapter_model(0).get_start(&mut rng);
     raw_fragment.extend(&start_adapter);
 
-    add_fragment(
+    let fragment_start = raw_fragment.len();
+    let mut tags = add_fragment(
         &mut raw_fragment,
         &description.origin,
-        references.0,
+        references[0],
         &mut rng,
     );
+    add_pcr_errors(&mut raw_fragment[fragment_start..], &mut description);
+    set_fragment_length(&mut description, raw_fragment.len() - fragment_start);
 
-    // Add chimeric part
-    if let Some(ref chimera) = description.chimera {
-        if rng.gen_bool(crate::CHIMERA_END_ADAPTER_CHANCE) {
-            raw_fragment.extend(adapter_model.get_end(&mut rng));
+    // Add foldback part, an optional hairpin follow by reverse complement of fragment end
+    if let Some(foldback) = description.foldback.as_mut() {
+        let back = foldback.length;
+        let forward = raw_fragment[fragment_start..].to_vec();
+
+        let hairpin = if rng.gen_bool(crate::FOLDBACK_HAIRPIN_CHANCE) {
+            crate::random_seq(rng.gen_range(crate::FOLDBACK_HAIRPIN_LENGTH), &mut rng)
+        } else {
+            Vec::new()
+        };
+        raw_fragment.extend(&hairpin);
+        raw_fragment.extend(bio::alphabets::dna::revcomp(
+            &forward[forward.len() - back.min(forward.len())..],
+        ));
+
+        foldback.hairpin = Some(hairpin.len());
+    }
+
+    // Add chimeric parts, junction could contains an end and a start adapter
+    for (i, chimera) in description.chimera.iter().enumerate() {
+        if rng.gen_bool(adapter_model(i).chimera_end_chance()) {
+            raw_fragment.extend(adapter_model(i).get_end(&mut rng));
         }
-        if rng.gen_bool(crate::CHIMERA_START_ADAPTER_CHANCE) {
-            raw_fragment.extend(adapter_model.get_start(&mut rng));
+        if rng.gen_bool(adapter_model(i + 1).chimera_start_chance()) {
+            raw_fragment.extend(adapter_model(i + 1).get_start(&mut rng));
         }
 
-        add_fragment(&mut raw_fragment, chimera, references.1, &mut rng);
+        tags.extend(add_fragment(
+            &mut raw_fragment,
+            chimera,
+            references[i + 1],
+            &mut rng,
+        ));
     }
+    description.tags.extend(tags);
 
-    let end_adapter = adapter_model.get_end(&mut rng);
+    let end_adapter = adapter_model(description.chimera.len()).get_end(&mut rng);
     raw_fragment.extend(&end_adapter);
 
     raw_fragment.extend(crate::random_seq(k, &mut rng));
@@ -292,19 +955,45 @@ where
     Ok((description, err_fragment, quality))
 }
 
+/// Set fragment length if repeat edit change it
+fn set_fragment_length(description: &mut Description, length: usize) {
+    let origin = &description.origin;
+    if origin.start > origin.end || origin.end - origin.start != length {
+        description.fragment_length = Some(length);
+    }
+}
+
 fn add_fragment<RNG>(
     raw_fragment: &mut Vec<u8>,
     origin: &Origin,
     reference: &Reference,
     rng: &mut RNG,
-) where
+) -> Vec<(&'static str, String)>
+where
     RNG: rand::Rng,
 {
     match origin.read_type {
         ReadType::Junk => add_junk(raw_fragment, origin.end, rng),
         ReadType::Random => add_random(raw_fragment, origin.end, rng),
-        ReadType::Real => add_real_fragment(raw_fragment, origin, reference),
+        ReadType::Real => match reference
+            .haplotype
+            .as_ref()
+            .filter(|h| !h.repeats.is_empty())
+        {
+            Some(haplotype) => {
+                return add_repeat_fragment(
+                    raw_fragment,
+                    origin,
+                    reference,
+                    &haplotype.repeats,
+                    rng,
+                )
+            }
+            None => add_real_fragment(raw_fragment, origin, reference),
+        },
     }
+
+    Vec::new()
 }
 
 fn add_junk<RNG>(raw_fragment: &mut Vec<u8>, length: usize, rng: &mut RNG)
@@ -338,11 +1027,74 @@ fn add_real_fragment(raw_fragment: &mut Vec<u8>, origin: &Origin, reference: &Re
     }
 }
 
+/// Add a haplotype fragment, number of copy of repeats fully contains in fragment change if mosaicism is set and short tandem repeat could stutter
+///
+/// Return a tag for each repeat overlap by fragment and for each stutter
+fn add_repeat_fragment<RNG>(
+    raw_fragment: &mut Vec<u8>,
+    origin: &Origin,
+    reference: &Reference,
+    repeats: &[variant::RepeatAllele],
+    rng: &mut RNG,
+) -> Vec<(&'static str, String)>
+where
+    RNG: rand::Rng,
+{
+    if origin.start > origin.end {
+        add_real_fragment(raw_fragment, origin, reference);
+        return Vec::new();
+    }
+
+    // fragment coordinate on forward strand
+    let (start, end) = if origin.strand == '+' {
+        (origin.start, origin.end)
+    } else {
+        (
+            reference.seq.len() - origin.end,
+            reference.seq.len() - origin.start,
+        )
+    };
+
+    let mut tags = Vec::new();
+    let mut fragment = Vec::with_capacity(end - start);
+    let mut pos = start;
+    for repeat in repeats.iter().filter(|r| r.start < end && r.end() > start) {
+        if repeat.start >= pos && repeat.end() <= end {
+            let copies = repeat.somatic_copies(rng);
+            let read_copies = repeat.stutter(copies, rng);
+
+            fragment.extend(&reference.seq[pos..repeat.start]);
+            fragment.extend(repeat.motif.repeat(read_copies));
+            pos = repeat.end();
+
+            tags.push(("repeat", repeat.description(copies)));
+            if read_copies != copies {
+                tags.push((
+                    "repeat_stutter",
+                    format!("{},{}", repeat.locus, read_copies as i64 - copies as i64),
+                ));
+            }
+        } else {
+            tags.push(("repeat", repeat.description(repeat.copies)));
+        }
+    }
+    fragment.extend(&reference.seq[pos..end]);
+
+    if origin.strand == '-' {
+        raw_fragment.extend(bio::alphabets::dna::revcomp(&fragment));
+    } else {
+        raw_fragment.extend(fragment);
+    }
+
+    tags
+}
+
 #[cfg(test)]
 mod t {
     use super::*;
     use rand::SeedableRng;
     use std::io::Seek;
+    use std::io::Write;
 
     fn init() {
         let _ = env_logger::builder()
@@ -370,7 +1122,7 @@ mod t {
         assert_eq!(b"TTAGATTATAGTACGGTATAGTGGTTACTATGTAGCCTAAGTGGCGCCCGTTGTAGAGGAATCCACTTATATAACACAGGTATAATCCGGACGGCATGCG".to_vec(), seq);
     }
 
-    static FASTA: &'static [u8] = b">random_seq_0
+    static FASTA: &[u8] = b">random_seq_0
 TCCTAACGTG
 >random_seq_1 depth=1.5
 TCACGATTAC
@@ -430,6 +1182,202 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
         );
     }
 
+    #[test]
+    fn read_repeat() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let reference = Reference::new(
+            "chr1".to_string(),
+            b"TTTTCACACATTTT".to_vec().into_boxed_slice(),
+            false,
+        );
+        let mut edits = variant::Edits::new(1);
+        edits.add_repeats(&[variant::Repeat {
+            ref_id: "chr1".to_string(),
+            start: 4,
+            end: 10,
+            motif: b"CA".to_vec(),
+            copies: vec![4],
+            mosaicism: 0.0,
+        }]);
+
+        let mut haplotype = reference.haplotype("1".to_string(), &edits.get("chr1", 0));
+        let info = haplotype.haplotype.as_mut().unwrap();
+        info.repeats = edits.repeat_alleles("chr1", 0, &info.liftover);
+
+        let mut seq = Vec::new();
+        let tags = add_fragment(
+            &mut seq,
+            &Origin::reference("chr1".to_string(), '+', 2, 14),
+            &haplotype,
+            &mut rng,
+        );
+        assert_eq!(b"TTCACACACATT".to_vec(), seq);
+        assert_eq!(vec![("repeat", "chr1:4-10,CA,4,8".to_string())], tags);
+
+        let mut seq = Vec::new();
+        let tags = add_fragment(
+            &mut seq,
+            &Origin::reference("chr1".to_string(), '-', 0, 8),
+            &haplotype,
+            &mut rng,
+        );
+        assert_eq!(b"AAAATGTG".to_vec(), seq);
+        assert_eq!(vec![("repeat", "chr1:4-10,CA,4,8".to_string())], tags);
+
+        haplotype.haplotype.as_mut().unwrap().repeats[0].mosaicism = 3.0;
+        let lengths: Vec<usize> = (0..20)
+            .map(|_| {
+                let mut seq = Vec::new();
+                add_fragment(
+                    &mut seq,
+                    &Origin::reference("chr1".to_string(), '+', 0, 16),
+                    &haplotype,
+                    &mut rng,
+                );
+                seq.len()
+            })
+            .collect();
+
+        assert!(lengths.iter().all(|x| (x - 8) % 2 == 0));
+        assert!(lengths.iter().any(|x| *x != 16));
+
+        // length in read description is length of fragment after repeat edit
+        let adapter = model::Adapter::new(Vec::new(), Vec::new(), 0.0, 0.0, 0.0, 0.0).unwrap();
+        let error = model::Error::random(7);
+        let qscore = model::Quality::random();
+        let glitches = model::Glitch::new(0.0, 0.0, 0.0).unwrap();
+        let description = Description::new(
+            Origin::reference("chr1".to_string(), '+', 0, 16),
+            Vec::new(),
+            16,
+            1.0,
+        );
+
+        let mut edited = 0;
+        for seed in 0..20 {
+            let (description, seq, _) = generate_read(
+                &[&haplotype],
+                description.clone(),
+                &[&adapter],
+                &error,
+                &glitches,
+                &qscore,
+                rand::rngs::StdRng::seed_from_u64(seed),
+            )
+            .unwrap();
+
+            let fragment = seq.len() - 14;
+            if fragment != 16 {
+                edited += 1;
+                assert_eq!(description.fragment_length, Some(fragment));
+            } else {
+                assert_eq!(description.fragment_length, None);
+            }
+            assert!(format!("{}", description).contains(&format!(" length={} ", fragment)));
+        }
+        assert_ne!(edited, 0);
+    }
+
+    #[test]
+    fn foldback_read() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let reference = Reference::new(
+            "chr1".to_string(),
+            crate::random_seq(100, &mut rng).into_boxed_slice(),
+            false,
+        );
+        let adapter = model::Adapter::new(Vec::new(), Vec::new(), 0.0, 0.0, 0.0, 0.0).unwrap();
+        let error = model::Error::random(7);
+        let qscore = model::Quality::random();
+        let glitches = model::Glitch::new(0.0, 0.0, 0.0).unwrap();
+
+        let mut description = Description::new(
+            Origin::reference("chr1".to_string(), '+', 10, 60),
+            Vec::new(),
+            70,
+            1.0,
+        );
+        description.foldback = Some(description::Foldback {
+            length: 20,
+            hairpin: None,
+        });
+
+        for seed in 0..10 {
+            let (description, seq, _) = generate_read(
+                &[&reference],
+                description.clone(),
+                &[&adapter],
+                &error,
+                &glitches,
+                &qscore,
+                rand::rngs::StdRng::seed_from_u64(seed),
+            )
+            .unwrap();
+
+            let hairpin = description.foldback.and_then(|x| x.hairpin).unwrap();
+            assert!(hairpin == 0 || crate::FOLDBACK_HAIRPIN_LENGTH.contains(&hairpin));
+
+            let read = &seq[7..seq.len() - 7];
+            assert_eq!(read.len(), 70 + hairpin);
+            assert_eq!(&read[..50], &reference.seq[10..60]);
+            assert_eq!(
+                read[50 + hairpin..].to_vec(),
+                bio::alphabets::dna::revcomp(&reference.seq[40..60])
+            );
+        }
+    }
+
+    #[test]
+    fn quantity_per_reference() {
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let length = model::Length::new(10.0, 2.0).unwrap();
+        let quantities = cli::simulate::reference_quantities(
+            "random_seq_0:3x,random_seq_1:100reads,random_seq_10:120",
+        )
+        .unwrap();
+
+        let (targets, weights) = reference_targets(&refs, &quantities, &length).unwrap();
+        assert_eq!(targets[0], fragments::Remaining::Bases(30));
+        assert_eq!(targets[1], fragments::Remaining::Reads(100));
+        assert_eq!(targets[10], fragments::Remaining::Bases(120));
+        assert_eq!(targets[2], fragments::Remaining::Bases(0));
+        assert_eq!(weights[2], 0.0);
+        // weight is proportional to target depth
+        assert!((weights[0] / refs.weights[0] - 3.0).abs() < 1e-9);
+        assert!((weights[1] / refs.weights[1] - 100.0).abs() < 1e-9);
+        assert!((weights[10] / refs.weights[10] - 1.2).abs() < 1e-9);
+
+        let unknown = cli::simulate::reference_quantities("chr1:3x").unwrap();
+        assert!(reference_targets(&refs, &unknown, &length).is_err());
+    }
+
+    #[test]
+    fn simulated() {
+        let mut description = Description::new(
+            Origin::reference("chr1".to_string(), '+', 0, 10),
+            vec![Origin::reference("chr2".to_string(), '+', 0, 10)],
+            20,
+            90.0,
+        );
+        description.labels = vec![
+            Label::Genome("A".to_string()),
+            Label::Gene("G1".to_string()),
+            Label::Genome("B".to_string()),
+            Label::Contaminant("phix".to_string()),
+        ];
+
+        let mut simulated = Simulated::default();
+        simulated.add(&description, 20);
+        simulated.add(&description, 10);
+
+        assert_eq!(simulated.total, (2, 30));
+        assert_eq!(simulated.genome.get("A"), Some(&(2, 30)));
+        assert_eq!(simulated.genome.get("B"), None);
+        assert_eq!(simulated.contaminant.get("phix"), Some(&(2, 30)));
+        assert!(simulated.transcript.is_empty());
+    }
+
     #[test]
     fn produce_read() {
         let mut rng = rand::rngs::StdRng::seed_from_u64(42);
@@ -457,11 +1405,15 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
             &identity,
             &mut rng,
         )
-        .map(|(ref_idx, ref_idx2, description, seed)| {
+        .map(|(ref_idx, chimera_idx, description, seed)| {
+            let read_refs: Vec<&Reference> = std::iter::once(ref_idx)
+                .chain(chimera_idx)
+                .map(|idx| &refs.sequences[idx])
+                .collect();
             generate_read(
-                (&refs.sequences[ref_idx], &refs.sequences[ref_idx2]),
+                &read_refs,
                 description,
-                &adapter,
+                &[&adapter],
                 &error,
                 &glitches,
                 &qscore,
@@ -481,11 +1433,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '+',
                             start: 7,
                             end: 9,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 18,
-                        identity: 90.0
+                        identity: 90.0,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![65, 84, 84, 84, 71, 65, 84, 65, 67, 71, 71, 84, 84, 84, 65, 67, 67, 67],
                     vec![46, 49, 48, 51, 36, 43, 43, 39, 45, 38, 36, 34, 39, 48, 52, 49, 53, 39]
@@ -497,17 +1456,25 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '+',
                             start: 5,
                             end: 9,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: Some(Origin {
+                        chimera: vec![Origin {
                             ref_id: "random_seq_6".to_string(),
                             strand: '+',
                             start: 4,
                             end: 2,
-                            read_type: ReadType::Real
-                        }),
+                            read_type: ReadType::Real,
+                            lifted: None
+                        }],
                         length: 33,
-                        identity: 76.66666666666666
+                        identity: 76.66666666666666,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         67, 67, 84, 65, 67, 67, 65, 84, 84, 65, 67, 71, 84, 84, 84, 65, 67, 84, 71,
@@ -525,11 +1492,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '-',
                             start: 35,
                             end: 44,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 28,
-                        identity: 81.4814814814815
+                        identity: 81.4814814814815,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         71, 84, 65, 71, 84, 65, 84, 71, 84, 65, 67, 71, 65, 84, 71, 84, 71, 67, 65,
@@ -547,11 +1521,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '+',
                             start: 8,
                             end: 9,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 24,
-                        identity: 88.0
+                        identity: 88.0,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         71, 84, 65, 65, 71, 67, 67, 84, 65, 67, 71, 84, 65, 67, 84, 71, 67, 65, 84,
@@ -569,17 +1550,25 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '+',
                             start: 96,
                             end: 99,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: Some(Origin {
+                        chimera: vec![Origin {
                             ref_id: "random_seq_10".to_string(),
                             strand: '+',
                             start: 68,
                             end: 74,
-                            read_type: ReadType::Real
-                        }),
+                            read_type: ReadType::Real,
+                            lifted: None
+                        }],
                         length: 22,
-                        identity: 91.66666666666666
+                        identity: 91.66666666666666,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         67, 71, 67, 84, 71, 84, 65, 84, 84, 71, 84, 67, 71, 84, 67, 84, 84, 84, 65,
@@ -597,11 +1586,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '+',
                             start: 25,
                             end: 33,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 29,
-                        identity: 86.66666666666667
+                        identity: 86.66666666666667,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         84, 65, 65, 67, 65, 65, 84, 67, 67, 71, 65, 84, 84, 84, 71, 67, 84, 67, 71,
@@ -619,11 +1615,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '+',
                             start: 7,
                             end: 9,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 16,
-                        identity: 88.23529411764706
+                        identity: 88.23529411764706,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![67, 84, 65, 84, 65, 65, 84, 84, 71, 67, 84, 65, 71, 71, 84, 65],
                     vec![51, 49, 44, 47, 35, 35, 36, 53, 53, 47, 37, 48, 40, 35, 53, 43]
@@ -635,11 +1638,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '+',
                             start: 81,
                             end: 88,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 32,
-                        identity: 78.125
+                        identity: 78.125,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         65, 84, 67, 71, 84, 67, 65, 84, 65, 67, 65, 71, 65, 65, 67, 65, 67, 84, 67,
@@ -657,11 +1667,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '+',
                             start: 32,
                             end: 40,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 33,
-                        identity: 74.19354838709677
+                        identity: 74.19354838709677,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         71, 71, 84, 67, 67, 65, 84, 71, 84, 67, 71, 67, 65, 84, 65, 65, 84, 67, 65,
@@ -679,11 +1696,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '-',
                             start: 58,
                             end: 66,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 25,
-                        identity: 96.0
+                        identity: 96.0,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         67, 84, 67, 84, 84, 65, 65, 84, 65, 67, 84, 65, 84, 71, 65, 84, 67, 65, 65,
@@ -701,11 +1725,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '-',
                             start: 2,
                             end: 9,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 25,
-                        identity: 82.6086956521739
+                        identity: 82.6086956521739,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         71, 84, 71, 65, 67, 71, 65, 65, 65, 84, 84, 65, 71, 65, 65, 84, 65, 67, 84,
@@ -723,11 +1754,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '-',
                             start: 2,
                             end: 8,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 28,
-                        identity: 89.65517241379311
+                        identity: 89.65517241379311,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         71, 67, 71, 67, 71, 84, 71, 84, 65, 71, 84, 84, 71, 84, 71, 71, 65, 71, 84,
@@ -745,11 +1783,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '*',
                             start: 0,
                             end: 9,
-                            read_type: ReadType::Random
+                            read_type: ReadType::Random,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 22,
-                        identity: 95.65217391304348
+                        identity: 95.65217391304348,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         67, 67, 65, 84, 71, 65, 65, 71, 84, 84, 84, 67, 84, 84, 71, 71, 65, 67, 67,
@@ -767,11 +1812,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '+',
                             start: 1,
                             end: 9,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 32,
-                        identity: 89.65517241379311
+                        identity: 89.65517241379311,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         65, 71, 71, 71, 65, 67, 71, 84, 65, 67, 67, 71, 84, 67, 67, 84, 65, 71, 65,
@@ -789,11 +1841,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '+',
                             start: 85,
                             end: 94,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 32,
-                        identity: 85.29411764705883
+                        identity: 85.29411764705883,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         67, 65, 65, 84, 65, 71, 84, 65, 67, 71, 84, 65, 84, 84, 84, 84, 84, 67, 65,
@@ -811,11 +1870,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '*',
                             start: 0,
                             end: 8,
-                            read_type: ReadType::Random
+                            read_type: ReadType::Random,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 31,
-                        identity: 85.18518518518519
+                        identity: 85.18518518518519,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         71, 71, 84, 84, 71, 67, 65, 84, 65, 65, 71, 84, 65, 67, 71, 84, 71, 67, 67,
@@ -833,11 +1899,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '*',
                             start: 0,
                             end: 4,
-                            read_type: ReadType::Junk
+                            read_type: ReadType::Junk,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 23,
-                        identity: 83.33333333333334
+                        identity: 83.33333333333334,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         71, 67, 67, 71, 67, 84, 65, 84, 65, 67, 71, 84, 84, 84, 84, 84, 84, 71, 84,
@@ -855,11 +1928,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '-',
                             start: 36,
                             end: 45,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 25,
-                        identity: 88.0
+                        identity: 88.0,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         67, 71, 71, 71, 84, 71, 71, 84, 65, 84, 71, 84, 71, 84, 71, 65, 65, 65, 65,
@@ -877,11 +1957,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '+',
                             start: 76,
                             end: 83,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 25,
-                        identity: 84.61538461538461
+                        identity: 84.61538461538461,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         67, 84, 67, 65, 71, 84, 71, 84, 65, 67, 71, 84, 84, 67, 71, 67, 71, 71, 71,
@@ -899,11 +1986,18 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
                             strand: '-',
                             start: 71,
                             end: 76,
-                            read_type: ReadType::Real
+                            read_type: ReadType::Real,
+                            lifted: None
                         },
-                        chimera: None,
+                        chimera: Vec::new(),
                         length: 28,
-                        identity: 70.83333333333333
+                        identity: 70.83333333333333,
+                        tags: Vec::new(),
+                        labels: Vec::new(),
+                        fragment_length: None,
+                        duplex: None,
+                        foldback: None,
+                        pcr_errors: Vec::new()
                     },
                     vec![
                         71, 65, 84, 65, 71, 67, 84, 65, 84, 65, 67, 71, 84, 65, 84, 67, 71, 71, 67,
@@ -970,11 +2064,15 @@ TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCG
             &identity,
             &mut rng,
         )
-        .map(|(ref_idx, ref_idx2, description, seed)| {
+        .map(|(ref_idx, chimera_idx, description, seed)| {
+            let read_refs: Vec<&Reference> = std::iter::once(ref_idx)
+                .chain(chimera_idx)
+                .map(|idx| &refs.sequences[idx])
+                .collect();
             generate_read(
-                (&refs.sequences[ref_idx], &refs.sequences[ref_idx2]),
+                &read_refs,
                 description,
-                &adapter,
+                &[&adapter],
                 &error,
                 &glitches,
                 &qscore,
@@ -0,0 +1,731 @@
+//! Simulate PacBio reads, polymerase go around SMRTbell and produce subreads or a HiFi consensus read
+
+/* standard use */
+
+/* crate use */
+use anyhow::{Context, Result};
+use rand::SeedableRng;
+use rayon::prelude::*;
+
+/* local use */
+use super::description::{Description, ReadType};
+use super::fragments::{Fragments, Quota};
+use super::{error, quality, Models, Simulated, MAX_EMPTY_STEP};
+use crate::bam;
+use crate::model;
+use crate::references::*;
+
+/* constant definition */
+const ADAPTER_BEFORE: u8 = 0x1;
+const ADAPTER_AFTER: u8 = 0x2;
+const FORWARD_PASS: u8 = 0x10;
+const REVERSE_PASS: u8 = 0x20;
+/// Maximal qscore of HiFi base
+const MAX_BASE_QSCORE: u8 = 93;
+/// Maximal qscore of HiFi base with an error
+const MAX_ERROR_QSCORE: u8 = 10;
+
+/// Type of PacBio reads produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Subreads,
+    Hifi,
+}
+
+impl std::str::FromStr for Mode {
+    type Err = crate::error::Cli;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "subreads" => Ok(Mode::Subreads),
+            "hifi" => Ok(Mode::Hifi),
+            _ => Err(crate::error::Cli::CantParsePacbio),
+        }
+    }
+}
+
+/// A subread, interval on polymerase read and adapter context flags are store with read
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subread {
+    pub description: Description,
+    pub seq: Vec<u8>,
+    pub qual: Vec<u8>,
+    pub start: usize,
+    pub end: usize,
+    pub context: u8,
+}
+
+/// A HiFi read, consensus of all passes with his number of passes and predicted accuracy
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ccs {
+    pub description: Description,
+    pub seq: Vec<u8>,
+    pub qual: Vec<u8>,
+    pub passes: usize,
+    pub accuracy: f64,
+}
+
+/// Reads produce by a ZMW, subreads or a HiFi read if it pass filter
+#[derive(Debug, Clone, PartialEq)]
+pub enum Zmw {
+    Subreads(Vec<Subread>),
+    Ccs(Option<Box<Ccs>>),
+}
+
+/// Where subreads are write, fastq and an optional unaligned bam, ZMW are number in write order
+pub struct Output<'a, W>
+where
+    W: std::io::Write,
+{
+    fastq: &'a mut W,
+    bam: Option<bam::Writer<std::io::BufWriter<std::fs::File>>>,
+    mode: Mode,
+    movie: String,
+    read_group: String,
+    zmw: u64,
+}
+
+impl<'a, W> Output<'a, W>
+where
+    W: std::io::Write,
+{
+    /// Create output, if bam_path is set an unaligned bam is write with a read group of movie
+    pub fn new(
+        fastq: &'a mut W,
+        bam_path: Option<&str>,
+        movie: String,
+        mode: Mode,
+    ) -> Result<Self> {
+        let read_type = match mode {
+            Mode::Subreads => "SUBREAD",
+            Mode::Hifi => "CCS",
+        };
+
+        let bam = if let Some(path) = bam_path {
+            Some(
+                bam::Writer::new(
+                    std::io::BufWriter::new(
+                        std::fs::File::create(path).with_context(|| "Open bam output file")?,
+                    ),
+                    &header(&movie, read_type),
+                )
+                .with_context(|| "Write bam header")?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Self {
+            fastq,
+            bam,
+            read_group: bam::read_group_id(&movie, read_type),
+            mode,
+            movie,
+            zmw: 0,
+        })
+    }
+
+    /// Write reads of a ZMW, ZMW without subreads isn't count and filtered HiFi reads just increase ZMW number
+    fn write_zmw(&mut self, zmw: &Zmw, simulated: &mut Simulated) -> Result<()> {
+        match zmw {
+            Zmw::Subreads(subreads) if !subreads.is_empty() => {
+                self.zmw += 1;
+                self.write_subreads(subreads, simulated)
+            }
+            Zmw::Subreads(_) => Ok(()),
+            Zmw::Ccs(ccs) => {
+                self.zmw += 1;
+                match ccs {
+                    Some(ccs) => self.write_ccs(ccs, simulated),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+
+    fn write_ccs(&mut self, ccs: &Ccs, simulated: &mut Simulated) -> Result<()> {
+        let name = format!("{}/{}/ccs", self.movie, self.zmw);
+        simulated.add(&ccs.description, ccs.seq.len() as u64);
+
+        writeln!(
+            self.fastq,
+            "@{} {}\n{}\n+ {}\n{}",
+            name,
+            ccs.description,
+            std::str::from_utf8(&ccs.seq).with_context(|| "Write read in output file")?,
+            ccs.description,
+            std::str::from_utf8(&ccs.qual).with_context(|| "Write read in output file")?
+        )
+        .with_context(|| "Write read in output file")?;
+
+        if let Some(writer) = self.bam.as_mut() {
+            writer
+                .write(
+                    &name,
+                    &ccs.seq,
+                    &ccs.qual,
+                    &[
+                        ("zm", bam::Value::Int(self.zmw as i32)),
+                        ("np", bam::Value::Int(ccs.passes as i32)),
+                        ("rq", bam::Value::Float(ccs.accuracy as f32)),
+                        ("RG", bam::Value::String(self.read_group.clone())),
+                    ],
+                )
+                .with_context(|| "Write read in bam file")?;
+        }
+
+        Ok(())
+    }
+
+    fn write_subreads(&mut self, subreads: &[Subread], simulated: &mut Simulated) -> Result<()> {
+        for subread in subreads {
+            let name = format!(
+                "{}/{}/{}_{}",
+                self.movie, self.zmw, subread.start, subread.end
+            );
+            simulated.add(&subread.description, subread.seq.len() as u64);
+
+            writeln!(
+                self.fastq,
+                "@{} {}\n{}\n+ {}\n{}",
+                name,
+                subread.description,
+                std::str::from_utf8(&subread.seq).with_context(|| "Write read in output file")?,
+                subread.description,
+                std::str::from_utf8(&subread.qual).with_context(|| "Write read in output file")?
+            )
+            .with_context(|| "Write read in output file")?;
+
+            if let Some(writer) = self.bam.as_mut() {
+                writer
+                    .write(
+                        &name,
+                        &subread.seq,
+                        &subread.qual,
+                        &[
+                            ("zm", bam::Value::Int(self.zmw as i32)),
+                            ("qs", bam::Value::Int(subread.start as i32)),
+                            ("qe", bam::Value::Int(subread.end as i32)),
+                            ("cx", bam::Value::Int(subread.context as i32)),
+                            ("np", bam::Value::Int(1)),
+                            ("RG", bam::Value::String(self.read_group.clone())),
+                        ],
+                    )
+                    .with_context(|| "Write read in bam file")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write end of bam file
+    pub fn finish(self) -> Result<()> {
+        if let Some(writer) = self.bam {
+            writer.finish().with_context(|| "Write end of bam file")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Header of an unaligned PacBio bam
+pub fn header(movie: &str, read_type: &str) -> String {
+    format!(
+        "@HD\tVN:1.5\tSO:unknown\tpb:3.0.7\n@RG\tID:{}\tPL:PACBIO\tDS:READTYPE={};BINDINGKIT=101-894-200;SEQUENCINGKIT=101-826-100;BASECALLERVERSION=5.0.0;FRAMERATEHZ=100.000000\tPU:{}\tPM:SEQUELII\n@PG\tID:rustyread\tPN:rustyread\tVN:{}\n",
+        bam::read_group_id(movie, read_type),
+        read_type,
+        movie,
+        env!("CARGO_PKG_VERSION"),
+    )
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Generate SMRTbells from references and write their subreads or HiFi read in output
+///
+/// Fragments are SMRTbell insert, so quantity is count in insert bases, ZMW without subreads and filtered HiFi reads aren't count
+pub(super) fn write_reads<W>(
+    references: &References,
+    models: &Models,
+    rates: (f64, f64, f64),
+    bases: (u64, u64),
+    output: &mut Output<W>,
+    rng: &mut rand::rngs::StdRng,
+) -> Result<Simulated>
+where
+    W: std::io::Write,
+{
+    let (total_base, base_limit) = bases;
+    let smrtbell = models
+        .smrtbell
+        .with_context(|| "PacBio mode require a SMRTbell model")?;
+
+    let mut base_produce = 0;
+    let mut simulated = Simulated::default();
+    let targets = models
+        .scheme
+        .map(|x| x.targets(references))
+        .transpose()
+        .with_context(|| "Find amplicons in references")?;
+
+    // quota count only written ZMWs, each step generate remaining quota
+    let mut quota = Quota::new(models.reads, models.reference_targets);
+    let mut empty_step = 0;
+    while base_produce < total_base {
+        if quota.is_reached(&references.weights) {
+            break;
+        }
+
+        let base_loop = if base_limit > total_base - base_produce {
+            total_base - base_produce
+        } else {
+            base_limit
+        };
+
+        log::info!("Start generate {} bases", base_loop);
+        let mut fragments = Fragments::new(
+            base_loop,
+            rates,
+            references,
+            models.length,
+            models.identity,
+            rng,
+        )
+        .chimera_model(models.chimera);
+        if let Some(targets) = targets.as_ref() {
+            fragments = fragments.amplicons(targets);
+        }
+        if let Some(pcr) = models.pcr {
+            fragments = fragments.pcr(pcr);
+        }
+        if !quota.is_unlimited() {
+            fragments = fragments.quota(quota.clone());
+        }
+        if let Some(shearing) = models.shearing {
+            fragments = fragments.shearing(shearing);
+        }
+        if let Some(transcript) = models.transcript {
+            fragments = fragments.transcript_model(transcript);
+        }
+
+        let mode = output.mode;
+        let zmws: Vec<(usize, ReadType, u64, Zmw)> = fragments
+            .by_ref()
+            .par_bridge()
+            .map(|(ref_idx, chimera_idx, description, seed)| {
+                let read_type = description.origin.read_type.clone();
+                let insert_length = description.length as u64;
+                let read_refs: Vec<&Reference> = std::iter::once(ref_idx)
+                    .chain(chimera_idx)
+                    .map(|idx| &references.sequences[idx])
+                    .collect();
+                let rng = rand::rngs::StdRng::seed_from_u64(seed);
+
+                let zmw = match (mode, models.pass_accuracy) {
+                    (Mode::Hifi, Some(pass_accuracy)) => Zmw::Ccs(
+                        generate_ccs(
+                            &read_refs,
+                            description,
+                            smrtbell,
+                            pass_accuracy,
+                            models.error,
+                            models.glitches,
+                            rng,
+                        )
+                        .map(Box::new),
+                    ),
+                    _ => Zmw::Subreads(
+                        generate_subreads(
+                            &read_refs,
+                            description,
+                            smrtbell,
+                            models.error,
+                            models.glitches,
+                            models.qscore,
+                            rng,
+                        )
+                        .unwrap(),
+                    ),
+                };
+
+                (ref_idx, read_type, insert_length, zmw)
+            })
+            .collect();
+        if fragments.selection_failed() {
+            anyhow::bail!(crate::error::Model::SizeSelectionNoFragment(
+                super::fragments::MAX_SELECTION_STEP
+            ));
+        }
+        log::info!("End generate sequences");
+
+        log::info!("Start write {} bases", base_loop);
+        let written = simulated.total.0;
+        for (ref_idx, read_type, insert_length, zmw) in zmws {
+            if quota.available(ref_idx, &read_type) == 0 {
+                continue;
+            }
+            if matches!(&zmw, Zmw::Ccs(Some(_)))
+                || matches!(&zmw, Zmw::Subreads(x) if !x.is_empty())
+            {
+                base_produce += insert_length;
+                quota.consume(ref_idx, &read_type, insert_length);
+            }
+            output.write_zmw(&zmw, &mut simulated)?;
+        }
+        log::info!("End write sequences");
+
+        if simulated.total.0 == written {
+            empty_step += 1;
+            if empty_step == MAX_EMPTY_STEP {
+                anyhow::bail!(crate::error::Model::NoReadWritten(MAX_EMPTY_STEP));
+            }
+        } else {
+            empty_step = 0;
+        }
+    }
+
+    Ok(simulated)
+}
+
+/// Build a SMRTbell insert from description and generate a subread for each pass of polymerase
+///
+/// Each pass get his own errors, start and end of subreads are polymerase read coordinate, adapters are count between passes
+pub fn generate_subreads<R>(
+    references: &[&Reference],
+    mut description: Description,
+    smrtbell: &model::Smrtbell,
+    error_model: &model::Error,
+    glitch_model: &model::Glitch,
+    qscore_model: &model::Quality,
+    mut rng: R,
+) -> Result<Vec<Subread>>
+where
+    R: rand::Rng,
+{
+    let k = error_model.k();
+
+    let insert = build_insert(references, &mut description, &mut rng);
+    let revcomp = bio::alphabets::dna::revcomp(&insert);
+
+    let passes = smrtbell.get_passes(insert.len(), &mut rng);
+    let mut subreads = Vec::with_capacity(passes.len());
+    let mut position = 0;
+    for (i, pass) in passes.iter().enumerate() {
+        let template = if pass.strand == '+' {
+            &insert
+        } else {
+            &revcomp
+        };
+
+        let mut raw_fragment = Vec::with_capacity(2 * k + pass.end - pass.start);
+        raw_fragment.extend(crate::random_seq(k, &mut rng));
+        raw_fragment.extend(&template[pass.start..pass.end]);
+        raw_fragment.extend(crate::random_seq(k, &mut rng));
+
+        let (err_fragment, cigar, real_id) = error::sequence(
+            description.identity,
+            &raw_fragment,
+            error_model,
+            glitch_model,
+            &mut rng,
+        );
+
+        let mut qual = quality::generate_quality(&cigar, qscore_model, &mut rng)?;
+        if qual.len() != err_fragment.len() {
+            log::warn!("read and quality string have different length, if you use seed please send all run information to author.");
+            qual.resize(err_fragment.len(), b'!');
+        }
+
+        if err_fragment.len() <= 2 * k {
+            position += smrtbell.adapter().len();
+            continue;
+        }
+
+        // begin and end of fragment is just random base
+        let seq = err_fragment[k..err_fragment.len() - k].to_vec();
+        let qual = qual[k..qual.len() - k].to_vec();
+
+        let mut context = if pass.strand == '+' {
+            FORWARD_PASS
+        } else {
+            REVERSE_PASS
+        };
+        if i != 0 {
+            context |= ADAPTER_BEFORE;
+        }
+        if i != passes.len() - 1 {
+            context |= ADAPTER_AFTER;
+        }
+
+        let mut pass_description = description.clone();
+        pass_description.identity = real_id * 100.0;
+        pass_description.length = seq.len();
+        pass_description.tags.extend([
+            ("pass", format!("{}/{}", i + 1, passes.len())),
+            ("pass_strand", pass.strand.to_string()),
+            ("pass_interval", format!("{}-{}", pass.start, pass.end)),
+        ]);
+
+        let start = position;
+        position += seq.len();
+        subreads.push(Subread {
+            description: pass_description,
+            seq,
+            qual,
+            start,
+            end: position,
+            context,
+        });
+        position += smrtbell.adapter().len();
+    }
+
+    Ok(subreads)
+}
+
+/// Build a SMRTbell insert and generate a HiFi read, number of passes give read qscore and identity
+///
+/// Return None if read qscore is lower than minimal qscore, quality of bases is compute from cigar and read qscore
+pub fn generate_ccs<R>(
+    references: &[&Reference],
+    mut description: Description,
+    smrtbell: &model::Smrtbell,
+    pass_accuracy: &model::PassAccuracy,
+    error_model: &model::Error,
+    glitch_model: &model::Glitch,
+    mut rng: R,
+) -> Option<Ccs>
+where
+    R: rand::Rng,
+{
+    let k = error_model.k();
+
+    let insert = build_insert(references, &mut description, &mut rng);
+    let passes = smrtbell.get_pass_number(insert.len(), &mut rng);
+    let qscore = pass_accuracy.get_qscore(passes, &mut rng)?;
+    let accuracy = model::pass_accuracy::identity(qscore);
+
+    let mut raw_fragment = Vec::with_capacity(2 * k + insert.len());
+    raw_fragment.extend(crate::random_seq(k, &mut rng));
+    raw_fragment.extend(&insert);
+    raw_fragment.extend(crate::random_seq(k, &mut rng));
+
+    let (err_fragment, cigar, real_id) =
+        error::sequence(accuracy, &raw_fragment, error_model, glitch_model, &mut rng);
+    let qual = hifi_quality(&cigar, qscore, &mut rng);
+
+    if err_fragment.len() <= 2 * k {
+        return None;
+    }
+
+    // begin and end of fragment is just random base
+    let seq = err_fragment[k..err_fragment.len() - k].to_vec();
+    let qual = qual[k..qual.len() - k].to_vec();
+
+    description.identity = real_id * 100.0;
+    description.length = seq.len();
+    description.tags.extend([
+        ("np", passes.to_string()),
+        ("rq", format!("{:.6}", accuracy)),
+    ]);
+
+    Some(Ccs {
+        description,
+        seq,
+        qual,
+        passes,
+        accuracy,
+    })
+}
+
+/// Generate quality of a HiFi read, correct bases get read qscore and errors a low qscore
+pub fn hifi_quality<RNG>(cigar: &[u8], qscore: f64, rng: &mut RNG) -> Vec<u8>
+where
+    RNG: rand::Rng,
+{
+    let read_qscore = (qscore.round() as u8).min(MAX_BASE_QSCORE);
+
+    cigar
+        .iter()
+        .filter(|x| **x != b'D')
+        .map(|x| {
+            if *x == b'=' {
+                read_qscore + 33
+            } else {
+                rng.gen_range(1..=MAX_ERROR_QSCORE.min(read_qscore.max(1))) + 33
+            }
+        })
+        .collect()
+}
+
+/// Build SMRTbell insert, fragment of origin follow by chimeric parts
+fn build_insert<R>(references: &[&Reference], description: &mut Description, rng: &mut R) -> Vec<u8>
+where
+    R: rand::Rng,
+{
+    let mut insert = Vec::with_capacity(description.length);
+    let mut tags = super::add_fragment(&mut insert, &description.origin, references[0], rng);
+    super::add_pcr_errors(&mut insert, description);
+    super::set_fragment_length(description, insert.len());
+    for (chimera, reference) in description.chimera.iter().zip(&references[1..]) {
+        tags.extend(super::add_fragment(&mut insert, chimera, reference, rng));
+    }
+    description.tags.extend(tags);
+
+    insert
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use crate::simulate::description::Origin;
+    use std::str::FromStr;
+
+    #[test]
+    fn mode() {
+        assert_eq!(Mode::from_str("subreads").unwrap(), Mode::Subreads);
+        assert_eq!(Mode::from_str("hifi").unwrap(), Mode::Hifi);
+        assert!(Mode::from_str("bépo").is_err());
+    }
+
+    #[test]
+    fn subreads() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let seq = crate::random_seq(200, &mut rng);
+        let reference = Reference::new("chr1".to_string(), seq.into_boxed_slice(), false);
+
+        let smrtbell = model::Smrtbell::new(
+            b"ATCTCTCTCAACAACAACAACGGAGGAGGAGGAAAAGAGAGAGAT".to_vec(),
+            model::Length::new(1000.0, 0.0).unwrap(),
+        );
+        let error = model::Error::random(7);
+        let qscore = model::Quality::random();
+        let glitches = model::Glitch::new(0.0, 0.0, 0.0).unwrap();
+
+        let description = Description::new(
+            Origin::reference("chr1".to_string(), '+', 0, 100),
+            Vec::new(),
+            100,
+            1.0,
+        );
+
+        let subreads = generate_subreads(
+            &[&reference],
+            description,
+            &smrtbell,
+            &error,
+            &glitches,
+            &qscore,
+            rand::rngs::StdRng::seed_from_u64(42),
+        )
+        .unwrap();
+
+        assert!(subreads.len() >= 6);
+        assert_eq!(subreads[0].context & ADAPTER_BEFORE, 0);
+        assert_eq!(subreads.last().unwrap().context & ADAPTER_AFTER, 0);
+        for (i, subread) in subreads.iter().enumerate() {
+            assert_eq!(subread.seq.len(), subread.end - subread.start);
+            assert_eq!(subread.seq.len(), subread.qual.len());
+            if i != 0 {
+                assert_eq!(
+                    subreads[i - 1].end + smrtbell.adapter().len(),
+                    subread.start
+                );
+            }
+        }
+
+        // full passes at 100% identity are alternatively insert and his reverse complement
+        let full: Vec<&Subread> = subreads[1..subreads.len() - 1].iter().collect();
+        for subread in full.iter() {
+            let expected = if subread.context & FORWARD_PASS != 0 {
+                reference.seq[0..100].to_vec()
+            } else {
+                bio::alphabets::dna::revcomp(&reference.seq[0..100])
+            };
+            assert_eq!(subread.seq, expected);
+        }
+        assert!(full
+            .windows(2)
+            .all(|w| w[0].context & FORWARD_PASS != w[1].context & FORWARD_PASS));
+        assert_eq!(
+            subreads[1]
+                .description
+                .tags
+                .iter()
+                .find(|(k, _)| *k == "pass_interval")
+                .unwrap()
+                .1,
+            "0-100"
+        );
+    }
+
+    #[test]
+    fn ccs() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let seq = crate::random_seq(1000, &mut rng);
+        let reference = Reference::new("chr1".to_string(), seq.into_boxed_slice(), false);
+
+        let smrtbell = model::Smrtbell::new(
+            b"ATCTCTCTCAACAACAACAACGGAGGAGGAGGAAAAGAGAGAGAT".to_vec(),
+            model::Length::new(16000.0, 0.0).unwrap(),
+        );
+        let pass_accuracy = model::PassAccuracy::new(10.0, 5.0, 40.0, 20.0).unwrap();
+        let error = model::Error::homopolymer(7);
+        let glitches = model::Glitch::new(0.0, 0.0, 0.0).unwrap();
+
+        let description = Description::new(
+            Origin::reference("chr1".to_string(), '+', 0, 1000),
+            Vec::new(),
+            1000,
+            0.85,
+        );
+
+        let ccs = generate_ccs(
+            &[&reference],
+            description.clone(),
+            &smrtbell,
+            &pass_accuracy,
+            &error,
+            &glitches,
+            rand::rngs::StdRng::seed_from_u64(42),
+        )
+        .unwrap();
+
+        assert_eq!(ccs.passes, 16);
+        assert!(ccs.accuracy > 0.99);
+        assert!(ccs.description.identity > 99.0);
+        assert_eq!(ccs.seq.len(), ccs.qual.len());
+        assert!(ccs.description.tags.contains(&("np", "16".to_string())));
+
+        // one pass is lower than Q20
+        let smrtbell = model::Smrtbell::new(
+            b"ATCTCTCTCAACAACAACAACGGAGGAGGAGGAAAAGAGAGAGAT".to_vec(),
+            model::Length::new(1500.0, 0.0).unwrap(),
+        );
+        assert!(generate_ccs(
+            &[&reference],
+            description,
+            &smrtbell,
+            &pass_accuracy,
+            &error,
+            &glitches,
+            rand::rngs::StdRng::seed_from_u64(42),
+        )
+        .is_none());
+
+        let qual = hifi_quality(b"==X=I=D=", 30.0, &mut rng);
+        assert_eq!(qual.len(), 7);
+        assert_eq!(qual[0], 63);
+        assert!(qual[2] <= 43);
+        assert!(qual[4] <= 43);
+    }
+
+    #[test]
+    fn bam_header() {
+        let header = header("m64000", "SUBREAD");
+        assert!(header.starts_with("@HD\tVN:1.5"));
+        assert!(header.contains(&format!(
+            "@RG\tID:{}\tPL:PACBIO\tDS:READTYPE=SUBREAD;",
+            bam::read_group_id("m64000", "SUBREAD")
+        )));
+        assert!(header.contains("PU:m64000"));
+    }
+}
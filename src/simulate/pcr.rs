@@ -0,0 +1,250 @@
+//! Simulate PCR amplification, copies inherit polymerase errors of their template and could switch template
+
+/* standard use */
+
+/* crate use */
+use anyhow::Result;
+use rand::distributions::Distribution;
+
+/* local use */
+use super::description::PcrError;
+use crate::error::Model;
+
+/// A PCR copy of a molecule, polymerase errors (position and shift of base) and position of template switch
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Molecule {
+    pub errors: Vec<(usize, u8)>,
+    pub switch: Option<usize>,
+}
+
+/// Struct to store PCR parameter
+pub struct Pcr {
+    cycles: usize,
+    efficiency: f64,
+    error_rate: f64,
+    switch_rate: f64,
+    duplicates: Option<rand_distr::Poisson<f64>>,
+}
+
+impl Pcr {
+    /// Create PCR parameter, efficiency, error rate by base and duplication and template switching rate by duplication are percentage, duplicates is mean number of reads by duplicate group
+    pub fn new(
+        cycles: usize,
+        efficiency: f64,
+        error_rate: f64,
+        switch_rate: f64,
+        duplicates: f64,
+    ) -> Result<Self> {
+        if !(0.0..=100.0).contains(&efficiency)
+            || !(0.0..=100.0).contains(&error_rate)
+            || !(0.0..=100.0).contains(&switch_rate)
+            || duplicates < 1.0
+        {
+            anyhow::bail!(Model::PcrParamOutOfRange);
+        }
+
+        Ok(Self {
+            cycles,
+            efficiency: efficiency / 100.0,
+            error_rate: error_rate / 100.0,
+            switch_rate: switch_rate / 100.0,
+            duplicates: if duplicates != 1.0 {
+                Some(rand_distr::Poisson::new(duplicates - 1.0)?)
+            } else {
+                None
+            },
+        })
+    }
+
+    /// Amplify a molecule and return copies sequenced, they are a duplicate group
+    ///
+    /// Only genealogy of sequenced copies is simulate: population size of each cycle is draw, then ancestors of copies are trace back, copies with a common ancestor share its errors
+    pub fn amplify<RNG>(&self, length: usize, rng: &mut RNG) -> Vec<Molecule>
+    where
+        RNG: rand::Rng,
+    {
+        // population size after each cycle, new copies are at end of population
+        let mut sizes = vec![1_u64];
+        for _ in 0..self.cycles {
+            let previous = *sizes.last().unwrap_or(&1);
+            let copied = rand_distr::Binomial::new(previous, self.efficiency)
+                .map(|x| x.sample(rng))
+                .unwrap_or(0);
+            sizes.push(previous.saturating_add(copied));
+        }
+
+        let final_size = *sizes.last().unwrap_or(&1);
+        let number =
+            (1 + self.duplicates.map(|x| x.sample(rng) as u64).unwrap_or(0)).min(final_size);
+
+        let mut indexs = Vec::with_capacity(number as usize);
+        while (indexs.len() as u64) < number {
+            let index = rng.gen_range(0..final_size);
+            if !indexs.contains(&index) {
+                indexs.push(index);
+            }
+        }
+
+        // copy events of each sequenced molecule, from first to last cycle
+        let mut events: rustc_hash::FxHashMap<(usize, u64), Molecule> =
+            rustc_hash::FxHashMap::default();
+        indexs
+            .into_iter()
+            .map(|mut index| {
+                let mut path = Vec::new();
+                for cycle in (1..=self.cycles).rev() {
+                    let previous = sizes[cycle - 1];
+                    if index >= previous {
+                        path.push((cycle, index));
+                        index -= previous;
+                    }
+                }
+
+                let mut molecule = Molecule::default();
+                for event in path.into_iter().rev() {
+                    let copy = events
+                        .entry(event)
+                        .or_insert_with(|| self.copy(length, rng));
+                    molecule.errors.extend(copy.errors.iter().cloned());
+                    if molecule.switch.is_none() {
+                        molecule.switch = copy.switch;
+                    }
+                }
+
+                molecule
+            })
+            .collect()
+    }
+
+    /// Change made by polymerase during a copy, substitutions and template switch
+    fn copy<RNG>(&self, length: usize, rng: &mut RNG) -> Molecule
+    where
+        RNG: rand::Rng,
+    {
+        let mut molecule = Molecule::default();
+        if length == 0 {
+            return molecule;
+        }
+        if self.error_rate != 0.0 {
+            let number = rand_distr::Binomial::new(length as u64, self.error_rate)
+                .map(|x| x.sample(rng))
+                .unwrap_or(0);
+            for _ in 0..number {
+                molecule
+                    .errors
+                    .push((rng.gen_range(0..length), rng.gen_range(1..=3)));
+            }
+        }
+
+        if length > 1 && self.switch_rate != 0.0 && rng.gen_bool(self.switch_rate) {
+            molecule.switch = Some(rng.gen_range(1..length));
+        }
+
+        molecule
+    }
+}
+
+/// Polymerase errors of a copy, sorted by position
+pub fn errors(molecule: &Molecule) -> Vec<PcrError> {
+    let mut errors: Vec<PcrError> = molecule
+        .errors
+        .iter()
+        .map(|(position, shift)| PcrError {
+            position: *position,
+            shift: *shift,
+            base: None,
+        })
+        .collect();
+    errors.sort_unstable_by_key(|x| (x.position, x.shift));
+
+    errors
+}
+
+/// Add polymerase errors in sequence, new base is set for each apply error
+pub fn add_errors(seq: &mut [u8], errors: &mut [PcrError]) {
+    for error in errors.iter_mut() {
+        if let Some(base) = seq.get_mut(error.position) {
+            if let Some(index) = crate::NUCS
+                .iter()
+                .position(|x| *x == base.to_ascii_uppercase())
+            {
+                *base = crate::NUCS[(index + error.shift as usize) % 4];
+                error.base = Some(*base);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn param() {
+        assert!(Pcr::new(10, 101.0, 0.0, 0.0, 2.0).is_err());
+        assert!(Pcr::new(10, 90.0, -1.0, 0.0, 2.0).is_err());
+        assert!(Pcr::new(10, 90.0, 0.0, 101.0, 2.0).is_err());
+        assert!(Pcr::new(10, 90.0, 0.0, 0.0, 0.5).is_err());
+    }
+
+    #[test]
+    fn amplify() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let pcr = Pcr::new(0, 90.0, 1.0, 1.0, 1.0).unwrap();
+        assert_eq!(pcr.amplify(100, &mut rng), vec![Molecule::default()]);
+
+        let pcr = Pcr::new(20, 90.0, 0.1, 0.0, 3.0).unwrap();
+        let groups: Vec<Vec<Molecule>> = (0..200).map(|_| pcr.amplify(1_000, &mut rng)).collect();
+        let mean = groups.iter().map(|x| x.len()).sum::<usize>() as f64 / groups.len() as f64;
+        assert!((2.7..3.3).contains(&mean));
+
+        let errors: Vec<&(usize, u8)> = groups
+            .iter()
+            .flatten()
+            .flat_map(|x| x.errors.iter())
+            .collect();
+        assert!(!errors.is_empty());
+        assert!(errors
+            .iter()
+            .all(|(pos, shift)| *pos < 1_000 && (1..=3).contains(shift)));
+        assert!(groups.iter().flatten().all(|x| x.switch.is_none()));
+
+        // copies of a group share errors of their common ancestors
+        assert!(groups.iter().any(|group| group.len() > 1
+            && group[0]
+                .errors
+                .iter()
+                .any(|e| group[1..].iter().any(|x| x.errors.contains(e)))));
+
+        let pcr = Pcr::new(10, 100.0, 0.0, 100.0, 1.0).unwrap();
+        let molecules = pcr.amplify(50, &mut rng);
+        assert!(molecules
+            .iter()
+            .all(|x| x.switch.map(|p| (1..50).contains(&p)).unwrap_or(true)));
+    }
+
+    #[test]
+    fn errors() {
+        let mut errors = super::errors(&Molecule {
+            errors: vec![(10, 1), (3, 2), (1, 1)],
+            switch: None,
+        });
+        assert_eq!(
+            errors.iter().map(|x| x.position).collect::<Vec<usize>>(),
+            vec![1, 3, 10]
+        );
+
+        let mut seq = b"ACGT".to_vec();
+        add_errors(&mut seq, &mut errors);
+        assert_eq!(seq, b"ATGA".to_vec());
+        assert_eq!(
+            errors.iter().map(|x| x.base).collect::<Vec<Option<u8>>>(),
+            vec![Some(b'T'), Some(b'A'), None]
+        );
+
+        add_errors(&mut seq, &mut []);
+        assert_eq!(seq, b"ATGA".to_vec());
+    }
+}
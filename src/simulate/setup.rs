@@ -0,0 +1,813 @@
+//! Init references and models of each simulation mode from simulate parameters
+
+/* standard use */
+
+/* crate use */
+use anyhow::{Context, Result};
+
+/* local use */
+use super::description::Label;
+use super::{duplex, pacbio, pcr};
+use crate::cli::simulate::Command;
+use crate::community;
+use crate::model;
+use crate::references::*;
+use crate::strain;
+use crate::variant;
+
+/// Models of library preparation
+pub(super) struct Library {
+    pub chimera: model::Chimera,
+    pub transposase: Option<model::Transposase>,
+    pub shearing: Option<model::Shearing>,
+    pub scheme: Option<crate::amplicon::Scheme>,
+    pub pcr: Option<pcr::Pcr>,
+    pub pore_c: Option<crate::pore_c::PoreC>,
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Fragment length model with size selection, and polymerase length model in PacBio mode
+pub(super) fn length_models(
+    params: &Command,
+    rng: &mut rand::rngs::StdRng,
+) -> Result<(model::Length, Option<model::Length>)> {
+    log::info!("Start init lenght model");
+    // in PacBio mode fragment is SMRTbell insert and length is polymerase read length
+    let (length, polymerase) = if params.pacbio.is_some() {
+        (
+            model::Length::new(params.insert_length.0 as f64, params.insert_length.1 as f64)
+                .with_context(|| "Init insert length model")?,
+            Some(
+                model::Length::new(params.length.0 as f64, params.length.1 as f64)
+                    .with_context(|| "Init polymerase length model")?,
+            ),
+        )
+    } else {
+        (
+            model::Length::new(params.length.0 as f64, params.length.1 as f64)
+                .with_context(|| "Init length model")?,
+            None,
+        )
+    };
+    // size selection is apply on fragments, in PacBio mode on SMRTbell inserts
+    let length = if let Some(selection) = params.size_selection {
+        let length = length.size_selection(selection);
+        if (0..10_000).all(|_| {
+            let value = length.get_length(rng);
+            length.selection_probability(value) == 0.0
+        }) {
+            anyhow::bail!(crate::error::Model::SizeSelectionTooStrict);
+        }
+
+        length
+    } else {
+        length
+    };
+    log::info!("End init lenght model");
+
+    Ok((length, polymerase))
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Read community file and draw abundances
+pub(super) fn read_community(
+    params: &Command,
+    rng: &mut rand::rngs::StdRng,
+) -> Result<Option<community::Community>> {
+    if let Some(community_path) = &params.community_path {
+        let mut community = community::Community::from_stream(
+            std::fs::File::open(community_path).with_context(|| "Read community file")?,
+            params.abundance_unit,
+        )
+        .with_context(|| "Parse community file")?;
+
+        if let Some(distribution) = params.abundance_distribution {
+            community
+                .draw_abundances(distribution, rng)
+                .with_context(|| "Draw community abundances")?;
+        }
+
+        Ok(Some(community))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Read strains file
+pub(super) fn read_population(params: &Command) -> Result<Option<strain::Population>> {
+    if let Some(strains_path) = &params.strains_path {
+        Ok(Some(
+            strain::Population::from_stream(
+                std::fs::File::open(strains_path).with_context(|| "Read strains file")?,
+            )
+            .with_context(|| "Parse strains file")?,
+        ))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Read transcriptome annotation and expression
+pub(super) fn read_annotation(
+    params: &Command,
+) -> Result<Option<crate::transcriptome::Annotation>> {
+    if let Some(annotation_path) = &params.annotation {
+        let mut annotation = crate::transcriptome::Annotation::from_gtf(
+            niffler::get_reader(Box::new(std::io::BufReader::new(
+                std::fs::File::open(annotation_path).with_context(|| "Read annotation file")?,
+            )))
+            .with_context(|| "Read annotation file niffler")?
+            .0,
+        )
+        .with_context(|| "Parse annotation file")?;
+
+        if let Some(expression_path) = &params.expression {
+            annotation
+                .read_expression(
+                    std::fs::File::open(expression_path).with_context(|| "Read expression file")?,
+                )
+                .with_context(|| "Parse expression file")?;
+        }
+
+        Ok(Some(annotation))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Read sample sheet and barcode kit
+pub(super) fn read_samples(
+    params: &Command,
+) -> Result<Option<(Vec<crate::barcode::Sample>, crate::barcode::Kit)>> {
+    if let Some(samples_path) = &params.samples_path {
+        log::info!("Start read sample sheet");
+        let kit = match params.barcode_kit.as_str() {
+            "NB" => crate::barcode::Kit::native(),
+            "RB" => crate::barcode::Kit::rapid(),
+            path => crate::barcode::Kit::from_stream(
+                std::fs::File::open(path).with_context(|| "Read barcode file")?,
+            )
+            .with_context(|| "Parse barcode file")?,
+        };
+
+        let samples = crate::barcode::read_samples(
+            std::fs::File::open(samples_path).with_context(|| "Read sample sheet")?,
+            &kit,
+        )
+        .with_context(|| "Parse sample sheet")?;
+        log::info!("End read sample sheet");
+
+        Ok(Some((samples, kit)))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Read references of samples, transcriptome, community, population or reference file, number of base of samples is return
+pub(super) fn read_references(
+    params: &Command,
+    community: Option<&mut community::Community>,
+    population: Option<&mut strain::Population>,
+    annotation: Option<&crate::transcriptome::Annotation>,
+    samples: Option<&[crate::barcode::Sample]>,
+    length: &model::Length,
+    rng: &mut rand::rngs::StdRng,
+) -> Result<(References, Option<u64>)> {
+    let reference_reader = if let Some(reference_path) = &params.reference_path {
+        Some(
+            niffler::get_reader(Box::new(std::io::BufReader::new(
+                std::fs::File::open(reference_path).with_context(|| "Read reference file")?,
+            )))
+            .with_context(|| "Read reference file niffler")?
+            .0,
+        )
+    } else {
+        None
+    };
+
+    if let Some(samples) = samples {
+        let mut groups = Vec::with_capacity(samples.len());
+        for sample in samples {
+            let mut sample_refs = References::from_stream_adjusted_weight(
+                niffler::get_reader(Box::new(std::io::BufReader::new(
+                    std::fs::File::open(&sample.reference_path)
+                        .with_context(|| "Read sample reference file")?,
+                )))
+                .with_context(|| "Read sample reference file niffler")?
+                .0,
+                params.small_plasmid_bias,
+                length,
+                rng,
+            )?;
+            for reference in sample_refs.sequences.iter_mut() {
+                reference
+                    .labels
+                    .push(Label::Barcode(sample.barcode.clone()));
+            }
+
+            if sample.quantity.number_of_reads().is_some() {
+                anyhow::bail!(crate::error::Cli::QuantityInReads(format!(
+                    "sample {}",
+                    sample.barcode
+                )));
+            }
+            let bases = sample.quantity.number_of_base(sample_refs.genome_length);
+            groups.push((sample_refs, bases));
+        }
+        let samples_base = groups.iter().map(|x| x.1).sum();
+
+        Ok((
+            References::from_samples(groups, length, rng)
+                .with_context(|| "Merge samples references")?,
+            Some(samples_base),
+        ))
+    } else if let (Some(annotation), Some(transcriptome_path)) =
+        (annotation, &params.transcriptome_path)
+    {
+        Ok((
+            References::from_transcriptome(
+                annotation,
+                niffler::get_reader(Box::new(std::io::BufReader::new(
+                    std::fs::File::open(transcriptome_path)
+                        .with_context(|| "Read transcriptome genome file")?,
+                )))
+                .with_context(|| "Read transcriptome genome file niffler")?
+                .0,
+            )
+            .with_context(|| "Build transcripts")?,
+            None,
+        ))
+    } else {
+        let references = match (community, population, reference_reader) {
+            (Some(community), _, reader) => References::from_community(
+                community,
+                reader,
+                params.small_plasmid_bias,
+                length,
+                rng,
+            )?,
+            (None, Some(population), Some(reader)) => References::from_population(
+                population,
+                reader,
+                params.small_plasmid_bias,
+                length,
+                rng,
+            )?,
+            (None, None, Some(reader)) => References::from_stream_adjusted_weight(
+                reader,
+                params.small_plasmid_bias,
+                length,
+                rng,
+            )?,
+            (None, _, None) => unreachable!(), // clap require reference, community, transcriptome or samples
+        };
+
+        Ok((references, None))
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Build haplotypes with variants, structural variants, repeats and divergence, and subclones of tumour
+///
+/// Haplotypes of matched normal are return with subclones
+pub(super) fn build_haplotypes(
+    params: &Command,
+    references: References,
+    rng: &mut rand::rngs::StdRng,
+) -> Result<(References, Option<References>)> {
+    if !(params.vcf_path.is_some()
+        || params.sv_vcf_path.is_some()
+        || params.sv_random != 0
+        || params.repeat_catalog.is_some()
+        || params.clones_path.is_some()
+        || params.divergence.is_some())
+    {
+        return Ok((references, None));
+    }
+
+    log::info!("Start build haplotypes");
+    let mut edits = variant::Edits::new(params.ploidy);
+
+    if let Some(vcf_path) = &params.vcf_path {
+        let variants = variant::vcf::from_stream(
+            niffler::get_reader(Box::new(std::io::BufReader::new(
+                std::fs::File::open(vcf_path).with_context(|| "Read vcf file")?,
+            )))
+            .with_context(|| "Read vcf file niffler")?
+            .0,
+        )
+        .with_context(|| "Parse vcf file")?;
+
+        edits.add_variants(&variants);
+    }
+
+    if let Some(divergence) = params.divergence {
+        let variants = variant::divergence::random(
+            divergence,
+            params.divergence_snv_indel,
+            params.divergence_ts_tv,
+            &references,
+            params.ploidy,
+            rng,
+        );
+
+        edits.add_variants(&variants);
+
+        log::info!("Start write divergence truth");
+        variant::divergence::write_vcf(
+            &mut std::io::BufWriter::new(
+                std::fs::File::create(format!("{}_divergence.vcf", params.truth_prefix))
+                    .with_context(|| "Create divergence truth vcf")?,
+            ),
+            &references,
+            &variants,
+        )
+        .with_context(|| "Write divergence truth vcf")?;
+        log::info!("End write divergence truth");
+    }
+
+    if let Some(repeat_catalog) = &params.repeat_catalog {
+        let repeats = variant::repeat::from_stream(
+            niffler::get_reader(Box::new(std::io::BufReader::new(
+                std::fs::File::open(repeat_catalog).with_context(|| "Read repeat catalog")?,
+            )))
+            .with_context(|| "Read repeat catalog niffler")?
+            .0,
+        )
+        .with_context(|| "Parse repeat catalog")?;
+
+        edits.add_repeats(&repeats);
+    }
+
+    let mut svs = if let Some(sv_vcf_path) = &params.sv_vcf_path {
+        variant::vcf::sv_from_stream(
+            niffler::get_reader(Box::new(std::io::BufReader::new(
+                std::fs::File::open(sv_vcf_path)
+                    .with_context(|| "Read structural variant vcf file")?,
+            )))
+            .with_context(|| "Read structural variant vcf file niffler")?
+            .0,
+            rng,
+        )
+        .with_context(|| "Parse structural variant vcf file")?
+    } else {
+        Vec::new()
+    };
+
+    if params.sv_random != 0 {
+        svs.extend(
+            variant::sv::random(params.sv_random, &references, params.ploidy, rng)
+                .with_context(|| "Generate random structural variants")?,
+        );
+    }
+
+    if !svs.is_empty() {
+        edits.add_structural(&svs, &references);
+
+        log::info!("Start write structural variants truth");
+        variant::sv::write_vcf(
+            &mut std::io::BufWriter::new(
+                std::fs::File::create(format!("{}_sv.vcf", params.truth_prefix))
+                    .with_context(|| "Create structural variant truth vcf")?,
+            ),
+            &references,
+            &svs,
+        )
+        .with_context(|| "Write structural variant truth vcf")?;
+        variant::sv::write_bed(
+            &mut std::io::BufWriter::new(
+                std::fs::File::create(format!("{}_sv.bed", params.truth_prefix))
+                    .with_context(|| "Create structural variant truth bed")?,
+            ),
+            &svs,
+        )
+        .with_context(|| "Write structural variant truth bed")?;
+        log::info!("End write structural variants truth");
+    }
+
+    let haplotypes = references
+        .with_haplotypes(&edits)
+        .with_context(|| "Build haplotypes")?;
+    log::info!("End build haplotypes");
+
+    if let Some(clones_path) = &params.clones_path {
+        log::info!("Start build subclones");
+        let tumour = variant::Tumour::from_stream(
+            std::fs::File::open(clones_path).with_context(|| "Read clones file")?,
+            params.purity,
+            rng,
+        )
+        .with_context(|| "Parse clones file")?;
+
+        let clones = references
+            .with_clones(&tumour.populations(&edits, &references))
+            .with_context(|| "Build subclones")?;
+
+        log::info!("Start write somatic truth");
+        tumour
+            .write_truth(
+                &mut std::io::BufWriter::new(
+                    std::fs::File::create(format!("{}_somatic.vcf", params.truth_prefix))
+                        .with_context(|| "Create somatic truth vcf")?,
+                ),
+                &references,
+                params.ploidy,
+            )
+            .with_context(|| "Write somatic truth vcf")?;
+        log::info!("End write somatic truth");
+        log::info!("End build subclones");
+
+        Ok((clones, Some(haplotypes)))
+    } else {
+        Ok((haplotypes, None))
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Build cells of single-cell mode, weights of references are set by cells expression
+pub(super) fn build_cells(
+    params: &Command,
+    references: &mut References,
+    rng: &mut rand::rngs::StdRng,
+) -> Result<Option<crate::single_cell::Cells>> {
+    if !params.single_cell {
+        return Ok(None);
+    }
+
+    log::info!("Start build cells");
+    let barcodes = if let Some(whitelist) = &params.whitelist {
+        crate::single_cell::read_whitelist(
+            niffler::get_reader(Box::new(std::io::BufReader::new(
+                std::fs::File::open(whitelist).with_context(|| "Read whitelist file")?,
+            )))
+            .with_context(|| "Read whitelist file niffler")?
+            .0,
+            params.cells,
+            rng,
+        )
+        .with_context(|| "Parse whitelist file")?
+    } else {
+        crate::single_cell::random_barcodes(params.cells, rng)
+    };
+
+    let mut cells =
+        crate::single_cell::Cells::new(barcodes, params.cell_variability, params.umi_duplicates)
+            .with_context(|| "Init cells")?;
+    if let Some(cell_expression) = &params.cell_expression {
+        cells
+            .read_profiles(
+                std::fs::File::open(cell_expression)
+                    .with_context(|| "Read cell expression file")?,
+            )
+            .with_context(|| "Parse cell expression file")?;
+    }
+
+    let weights = cells
+        .expression(references, rng)
+        .with_context(|| "Compute cells expression")?;
+    references
+        .set_weights(weights)
+        .with_context(|| "Set transcripts weight")?;
+    log::info!("End build cells");
+
+    Ok(Some(cells))
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Adapter model and for cDNA reads adapter model of reverse strand reads
+pub(super) fn adapter_models(
+    params: &Command,
+    transcriptome: bool,
+) -> Result<(model::Adapter, Option<model::Adapter>)> {
+    log::info!("Start init adapter model");
+    // single-cell cDNA have template switching oligo at 5' end and read-1 adapter before cell barcode at 3' end
+    let (ssp_seq, vnp_seq) = if params.single_cell {
+        (&params.tso_seq, &params.read1_seq)
+    } else {
+        (&params.ssp_seq, &params.vnp_seq)
+    };
+    // direct RNA read are reverse after generation, sequencing adapter is ligate to poly-A tail so it's add reversed at end
+    let (start_adapter_seq, end_adapter_seq) = if params.direct_rna {
+        (Vec::new(), params.start_adapter_seq.bytes().rev().collect())
+    } else if transcriptome {
+        (
+            ssp_seq.as_bytes().to_vec(),
+            bio::alphabets::dna::revcomp(vnp_seq.as_bytes()),
+        )
+    } else if params.library == model::transposase::Library::Rapid {
+        (model::transposase::RAPID_ADAPTER.to_vec(), Vec::new())
+    } else {
+        (
+            params.start_adapter_seq.as_bytes().to_vec(),
+            params.end_adapter_seq.as_bytes().to_vec(),
+        )
+    };
+    // Pore-C concatemer is ligate before library preparation, junctions don't contains adapter
+    let chimera_adapter = if params.pore_c.is_some() {
+        (0, 0)
+    } else if params.library == model::transposase::Library::Rapid {
+        (0, params.chimera_adapter.1)
+    } else {
+        (params.chimera_adapter.0, params.chimera_adapter.1)
+    };
+    let adapter = if params.direct_rna {
+        model::Adapter::new(
+            start_adapter_seq,
+            end_adapter_seq,
+            0.0,
+            0.0,
+            params.start_adapter.0 as f64,
+            params.start_adapter.1 as f64,
+        )
+    } else if params.library == model::transposase::Library::Rapid {
+        // transposase attach rapid adapter only at 5' end of molecule
+        model::Adapter::new(
+            start_adapter_seq,
+            end_adapter_seq,
+            params.start_adapter.0 as f64,
+            params.start_adapter.1 as f64,
+            0.0,
+            0.0,
+        )
+    } else {
+        model::Adapter::new(
+            start_adapter_seq,
+            end_adapter_seq,
+            params.start_adapter.0 as f64,
+            params.start_adapter.1 as f64,
+            params.end_adapter.0 as f64,
+            params.end_adapter.1 as f64,
+        )
+    }
+    .and_then(|x| x.chimera_adapters(chimera_adapter.0 as f64, chimera_adapter.1 as f64))
+    .with_context(|| "Init adapter model")?;
+
+    // cDNA read on reverse strand start with VN primer and end with strand switching primer
+    let reverse_adapter = if transcriptome && !params.direct_rna {
+        Some(
+            model::Adapter::new(
+                vnp_seq.as_bytes().to_vec(),
+                bio::alphabets::dna::revcomp(ssp_seq.as_bytes()),
+                params.start_adapter.0 as f64,
+                params.start_adapter.1 as f64,
+                params.end_adapter.0 as f64,
+                params.end_adapter.1 as f64,
+            )
+            .and_then(|x| x.chimera_adapters(chimera_adapter.0 as f64, chimera_adapter.1 as f64))
+            .with_context(|| "Init reverse adapter model")?,
+        )
+    } else {
+        None
+    };
+    log::info!("End init adapter model");
+
+    Ok((adapter, reverse_adapter))
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Models of library preparation, chimera, transposase, shearing, amplicons, PCR and Pore-C
+pub(super) fn library_models(params: &Command, rng: &mut rand::rngs::StdRng) -> Result<Library> {
+    let transposase = if params.library == model::transposase::Library::Rapid {
+        Some(
+            model::Transposase::new(
+                params.insertion_motif.as_bytes(),
+                params.insertion_bias,
+                params.length.0 as f64,
+            )
+            .with_context(|| "Init transposase model")?,
+        )
+    } else {
+        None
+    };
+
+    let shearing = if let Some(device) = params.shearing {
+        Some(
+            model::Shearing::new(
+                params.parent_length.0 as f64,
+                params.parent_length.1 as f64,
+                device.size(),
+            )
+            .with_context(|| "Init shearing model")?,
+        )
+    } else {
+        None
+    };
+
+    let chimera = model::Chimera::new(params.chimera_parts, params.chimera_junction)
+        .with_context(|| "Init chimera model")?;
+
+    let scheme = if let Some(scheme_path) = &params.primer_scheme {
+        let mut scheme = crate::amplicon::Scheme::from_stream(
+            std::fs::File::open(scheme_path).with_context(|| "Open primer scheme")?,
+        )
+        .with_context(|| "Read primer scheme")?;
+        scheme
+            .set_efficiency(
+                params.amplicon_variability,
+                params.amplicon_dropout,
+                (params.pool_balance.0 as f64, params.pool_balance.1 as f64),
+                rng,
+            )
+            .with_context(|| "Init amplicon efficiency")?;
+
+        Some(scheme)
+    } else {
+        None
+    };
+
+    let pcr = if params.pcr_cycles != 0 {
+        Some(
+            pcr::Pcr::new(
+                params.pcr_cycles,
+                params.pcr_efficiency,
+                params.pcr_error_rate,
+                params.template_switch,
+                params.pcr_duplicates,
+            )
+            .with_context(|| "Init PCR model")?,
+        )
+    } else {
+        None
+    };
+
+    let pore_c = if let Some(enzyme) = &params.pore_c {
+        let mut pore_c = crate::pore_c::PoreC::new(
+            crate::pore_c::restriction_site(enzyme).with_context(|| "Parse Pore-C enzyme")?,
+            params.contacts,
+            params.contact_decay,
+            params.trans_contacts,
+            params.domain_insulation,
+        )
+        .with_context(|| "Init Pore-C model")?;
+        if let Some(domains_path) = &params.domains_path {
+            pore_c
+                .read_domains(
+                    std::fs::File::open(domains_path).with_context(|| "Open domains file")?,
+                )
+                .with_context(|| "Read domains")?;
+        }
+
+        Some(pore_c)
+    } else {
+        None
+    };
+
+    Ok(Library {
+        chimera,
+        transposase,
+        shearing,
+        scheme,
+        pcr,
+        pore_c,
+    })
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Transcript model of cDNA or direct RNA molecules
+pub(super) fn transcript_model(
+    params: &Command,
+    transcriptome: bool,
+) -> Result<Option<model::Transcript>> {
+    if !transcriptome {
+        return Ok(None);
+    }
+
+    log::info!("Start init transcript model");
+    let mut transcript = model::Transcript::new(
+        params.poly_a.0 as f64,
+        params.poly_a.1 as f64,
+        params.truncation_rate,
+        params.intra_priming_rate,
+    )
+    .with_context(|| "Init transcript model")?;
+    if params.direct_rna {
+        transcript = transcript
+            .direct_rna(
+                (
+                    params.five_prime_loss.0 as usize,
+                    params.five_prime_loss.1 as usize,
+                ),
+                params.uracil,
+            )
+            .with_context(|| "Init direct RNA model")?;
+    }
+    log::info!("End init transcript model");
+
+    Ok(Some(transcript))
+}
+
+#[cfg(not(tarpaulin_include))]
+/// SMRTbell model and in HiFi mode pass accuracy model
+pub(super) fn pacbio_models(
+    params: &Command,
+    polymerase: Option<model::Length>,
+) -> Result<(Option<model::Smrtbell>, Option<model::PassAccuracy>)> {
+    let smrtbell = polymerase.map(|polymerase| {
+        model::Smrtbell::new(params.smrtbell_adapter.as_bytes().to_vec(), polymerase)
+    });
+
+    let pass_accuracy = if params.pacbio == Some(pacbio::Mode::Hifi) {
+        Some(
+            model::PassAccuracy::new(
+                params.pass_accuracy.0 as f64,
+                params.pass_accuracy.1 as f64,
+                params.pass_accuracy.2 as f64,
+                params.min_qscore,
+            )
+            .with_context(|| "Init pass accuracy model")?,
+        )
+    } else {
+        None
+    };
+
+    Ok((smrtbell, pass_accuracy))
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Duplex parameter, a RNA molecule have only one strand and PacBio SMRTbell already read both strands
+pub(super) fn duplex_model(params: &Command) -> Result<Option<duplex::Duplex>> {
+    if params.duplex == 0.0 || params.direct_rna || params.pacbio.is_some() {
+        return Ok(None);
+    }
+
+    let consensus_identity = if params.duplex_output.is_some() {
+        Some(
+            model::Identity::new(
+                params.duplex_identity.0 as f64,
+                params.duplex_identity.1 as f64,
+                params.duplex_identity.2 as f64,
+            )
+            .with_context(|| "Init duplex identity model")?,
+        )
+    } else {
+        None
+    };
+
+    Ok(Some(duplex::Duplex::new(params.duplex, consensus_identity)))
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Read error model, random and hifi are build in models, PacBio HiFi mode use hifi by default
+pub(super) fn error_model(params: &Command, rng: &mut rand::rngs::StdRng) -> Result<model::Error> {
+    log::info!("Start read error model");
+    let error_model = match (&params.error_model, params.pacbio) {
+        (Some(error_model), _) => error_model.clone(),
+        (None, Some(pacbio::Mode::Hifi)) => "hifi".to_string(),
+        (None, _) => "nanopore2020".to_string(),
+    };
+    let error = if error_model == *"random" {
+        log::info!("Use random error model");
+        model::Error::random(7)
+    } else if error_model == *"hifi" {
+        log::info!("Use homopolymer error model");
+        model::Error::homopolymer(7)
+    } else {
+        log::info!("Use file error model");
+        let error_path = crate::cli::simulate::found_model(error_model, "error".to_string())
+            .with_context(|| "Get path of error model")?;
+        model::Error::from_stream(
+            niffler::get_reader(Box::new(std::io::BufReader::new(
+                std::fs::File::open(error_path).with_context(|| "Open error model")?,
+            )))
+            .with_context(|| "Open error model")?
+            .0,
+            rng,
+        )
+        .with_context(|| "Init error model")?
+    };
+    log::info!("End read error model");
+
+    Ok(error)
+}
+
+#[cfg(not(tarpaulin_include))]
+/// Read quality score model, ideal and random are build in models
+pub(super) fn qscore_model(params: &Command) -> Result<model::Quality> {
+    log::info!("Start read quality score model");
+    let qscore = if params.qscore_model == *"ideal" {
+        log::info!("Use ideal quality score model");
+        model::Quality::ideal()
+    } else if params.qscore_model == *"random" {
+        log::info!("Use random quality score model");
+        model::Quality::random()
+    } else {
+        log::info!("Use file error model");
+        let qscore_path =
+            crate::cli::simulate::found_model(params.qscore_model.clone(), "qscore".to_string())
+                .with_context(|| "Get path of qscore model")?;
+        model::Quality::from_stream(
+            niffler::get_reader(Box::new(std::io::BufReader::new(
+                std::fs::File::open(qscore_path).with_context(|| "Open qscore model")?,
+            )))
+            .with_context(|| "Open qscore model")?
+            .0,
+        )?
+    };
+    log::info!("End read quality score model");
+
+    Ok(qscore)
+}
@@ -0,0 +1,395 @@
+//! Assign transcript molecules to cells, each molecule get cell barcode and an UMI
+
+/* standard use */
+use std::io::BufRead;
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::{Context, Result};
+use rand::distributions::Distribution;
+
+/* local use */
+use crate::error::SingleCell as Error;
+use crate::references::References;
+use crate::simulate::description::{Description, Label};
+
+/* constant definition */
+/// Length of cell barcode
+pub const BARCODE_LENGTH: usize = 16;
+/// Length of unique molecular identifier
+pub const UMI_LENGTH: usize = 12;
+
+/// Cells and expression of each reference in each cell
+pub struct Cells {
+    barcodes: Vec<String>,
+    profiles: rustc_hash::FxHashMap<(String, String), f64>,
+    variability: Option<rand_distr::LogNormal<f64>>,
+    duplicates: Option<rand_distr::Poisson<f64>>,
+    dists: Vec<Option<rand::distributions::WeightedIndex<f64>>>,
+}
+
+impl Cells {
+    /// Create cells from their barcode
+    ///
+    /// Variability is standard deviation of log of cell size and transcript expression, duplicates is mean number of reads by UMI
+    pub fn new(barcodes: Vec<String>, variability: f64, duplicates: f64) -> Result<Self> {
+        if variability < 0.0 || duplicates < 1.0 {
+            anyhow::bail!(Error::ParamOutOfRange);
+        }
+
+        Ok(Self {
+            barcodes,
+            profiles: rustc_hash::FxHashMap::default(),
+            variability: if variability != 0.0 {
+                Some(rand_distr::LogNormal::new(0.0, variability)?)
+            } else {
+                None
+            },
+            duplicates: if duplicates != 1.0 {
+                Some(rand_distr::Poisson::new(duplicates - 1.0)?)
+            } else {
+                None
+            },
+            dists: Vec::new(),
+        })
+    }
+
+    /// Read expression profile of cells, columns are barcode, transcript id and expression
+    ///
+    /// Cells are replace by cells of profile and a transcript absent of a cell profile isn't express
+    pub fn read_profiles<R>(&mut self, input: R) -> Result<()>
+    where
+        R: std::io::Read,
+    {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .quoting(false)
+            .comment(Some(b'#'))
+            .from_reader(input);
+
+        self.barcodes.clear();
+        for record in reader.records() {
+            let record = record?;
+            if record.len() < 3 || !is_barcode(&record[0]) {
+                anyhow::bail!(Error::ProfileParsing);
+            }
+
+            if !self.barcodes.iter().any(|x| x == &record[0]) {
+                self.barcodes.push(record[0].to_string());
+            }
+            self.profiles.insert(
+                (record[0].to_string(), record[1].to_string()),
+                f64::from_str(&record[2]).with_context(|| Error::ProfileParsing)?,
+            );
+        }
+
+        if self.barcodes.is_empty() {
+            anyhow::bail!(Error::ProfileParsing);
+        }
+
+        Ok(())
+    }
+
+    /// Compute expression of each transcript in each cell and return weight of references
+    ///
+    /// Without profile, expression of a transcript in a cell is reference weight multiply by a cell size and a noise, weight of a transcript is the sum of his expression in all cells
+    pub fn expression<RNG>(&mut self, references: &References, rng: &mut RNG) -> Result<Vec<f64>>
+    where
+        RNG: rand::Rng,
+    {
+        let sizes: Vec<f64> = self
+            .barcodes
+            .iter()
+            .map(|_| self.variability.map(|x| x.sample(rng)).unwrap_or(1.0))
+            .collect();
+
+        let mut weights = Vec::with_capacity(references.sequences.len());
+        self.dists = Vec::with_capacity(references.sequences.len());
+        for (reference, weight) in references.sequences.iter().zip(references.weights.iter()) {
+            let transcript = match reference.transcript() {
+                Some(transcript) => transcript,
+                None => {
+                    weights.push(*weight);
+                    self.dists.push(None);
+                    continue;
+                }
+            };
+
+            let expression: Vec<f64> = if self.profiles.is_empty() {
+                sizes
+                    .iter()
+                    .map(|size| {
+                        weight * size * self.variability.map(|x| x.sample(rng)).unwrap_or(1.0)
+                    })
+                    .collect()
+            } else {
+                self.barcodes
+                    .iter()
+                    .map(|barcode| {
+                        self.profiles
+                            .get(&(barcode.clone(), transcript.clone()))
+                            .cloned()
+                            .unwrap_or(0.0)
+                    })
+                    .collect()
+            };
+
+            weights.push(expression.iter().sum());
+            self.dists
+                .push(rand::distributions::WeightedIndex::new(&expression).ok());
+        }
+
+        Ok(weights)
+    }
+
+    /// Barcode of a cell
+    pub fn barcode(&self, cell: usize) -> &str {
+        &self.barcodes[cell]
+    }
+
+    /// Choose a cell according to expression of reference, None if reference isn't a transcript
+    pub fn choose_cell<RNG>(&self, reference: usize, rng: &mut RNG) -> Option<usize>
+    where
+        RNG: rand::Rng,
+    {
+        self.dists
+            .get(reference)
+            .and_then(|x| x.as_ref())
+            .map(|dist| dist.sample(rng))
+    }
+
+    /// Number of reads produce from a molecule
+    pub fn get_duplicates<RNG>(&self, rng: &mut RNG) -> usize
+    where
+        RNG: rand::Rng,
+    {
+        1 + self.duplicates.map(|x| x.sample(rng) as usize).unwrap_or(0)
+    }
+
+    /// Write number of reads and bases produce by each cell
+    pub fn write_truth<W>(
+        &self,
+        output: &mut W,
+        simulated: &rustc_hash::FxHashMap<String, (u64, u64)>,
+    ) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        writeln!(output, "#cell\treads\tbases")?;
+        for barcode in self.barcodes.iter() {
+            let (reads, bases) = simulated.get(barcode).cloned().unwrap_or((0, 0));
+            writeln!(output, "{}\t{}\t{}", barcode, reads, bases)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Read a whitelist, one barcode by line, and randomly choose number barcodes
+pub fn read_whitelist<R, RNG>(input: R, number: usize, rng: &mut RNG) -> Result<Vec<String>>
+where
+    R: std::io::Read,
+    RNG: rand::Rng,
+{
+    let mut whitelist = Vec::new();
+    for line in std::io::BufReader::new(input).lines() {
+        let line = line?;
+        let barcode = line.trim();
+        if barcode.is_empty() {
+            continue;
+        }
+        if !is_barcode(barcode) {
+            anyhow::bail!(Error::BarcodeParsing);
+        }
+
+        whitelist.push(barcode.to_string());
+    }
+
+    if whitelist.len() < number {
+        anyhow::bail!(Error::NotEnoughBarcode);
+    }
+
+    Ok(rand::seq::index::sample(rng, whitelist.len(), number)
+        .into_iter()
+        .map(|i| whitelist[i].clone())
+        .collect())
+}
+
+/// Generate number random barcodes
+pub fn random_barcodes<RNG>(number: usize, rng: &mut RNG) -> Vec<String>
+where
+    RNG: rand::Rng,
+{
+    (0..number)
+        .map(|_| String::from_utf8(crate::random_seq(BARCODE_LENGTH, rng)).unwrap())
+        .collect()
+}
+
+/// Generate a random UMI
+pub fn get_umi<RNG>(rng: &mut RNG) -> String
+where
+    RNG: rand::Rng,
+{
+    String::from_utf8(crate::random_seq(UMI_LENGTH, rng)).unwrap()
+}
+
+/// Cell barcode and UMI of a read, add after read-1 adapter at start of read on reverse strand or before reverse complement of read-1 adapter at end of read on forward strand
+pub fn barcodes(description: &Description) -> Option<(Vec<u8>, Vec<u8>)> {
+    let cell = description.labels.iter().find_map(|x| match x {
+        Label::Cell(cell) => Some(cell),
+        _ => None,
+    })?;
+    let umi = description.labels.iter().find_map(|x| match x {
+        Label::Umi(umi) => Some(umi),
+        _ => None,
+    })?;
+
+    let mut barcode = cell.as_bytes().to_vec();
+    barcode.extend(umi.as_bytes());
+
+    if description.origin.strand == '-' {
+        Some((barcode, Vec::new()))
+    } else {
+        Some((Vec::new(), bio::alphabets::dna::revcomp(barcode)))
+    }
+}
+
+fn is_barcode(barcode: &str) -> bool {
+    barcode.len() == BARCODE_LENGTH && barcode.bytes().all(|x| crate::NUCS.contains(&x))
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    use crate::simulate::description::Origin;
+
+    static FASTA: &[u8] = b">T1
+ACTGACTGAC
+>T2
+ACTGACTGAC
+>C1
+ACTGACTGAC
+";
+
+    fn references() -> References {
+        let mut references = References::from_stream(FASTA).unwrap();
+        references.sequences[0]
+            .labels
+            .push(Label::Transcript("T1".to_string()));
+        references.sequences[1]
+            .labels
+            .push(Label::Transcript("T2".to_string()));
+        references
+    }
+
+    #[test]
+    fn whitelist() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let input = b"AAACCCAAGAAACACT\nAAACCCAAGAAACCAT\n\nAAACCCAAGAAACCCA\n";
+        let barcodes = read_whitelist(&input[..], 2, &mut rng).unwrap();
+        assert_eq!(barcodes.len(), 2);
+        assert!(barcodes.iter().all(|x| x.starts_with("AAACCCAAGAAAC")));
+
+        assert!(read_whitelist(&input[..], 4, &mut rng).is_err());
+        assert!(read_whitelist(&b"AAACCCAAGAAACAC\n"[..], 1, &mut rng).is_err());
+
+        let barcodes = random_barcodes(3, &mut rng);
+        assert!(barcodes.iter().all(|x| is_barcode(x)));
+        assert_eq!(get_umi(&mut rng).len(), UMI_LENGTH);
+    }
+
+    #[test]
+    fn profiles() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let references = references();
+
+        assert!(Cells::new(Vec::new(), -1.0, 1.0).is_err());
+        assert!(Cells::new(Vec::new(), 0.0, 0.5).is_err());
+
+        let mut cells = Cells::new(Vec::new(), 0.0, 1.0).unwrap();
+        cells
+            .read_profiles(&b"AAACCCAAGAAACACT\tT1\t10\nAAACCCAAGAAACCAT\tT2\t5\nAAACCCAAGAAACCAT\tT1\t10\n"[..])
+            .unwrap();
+        assert_eq!(cells.barcode(1), "AAACCCAAGAAACCAT");
+
+        assert_eq!(
+            cells.expression(&references, &mut rng).unwrap(),
+            vec![20.0, 5.0, 10.0]
+        );
+        assert_eq!(cells.choose_cell(1, &mut rng), Some(1));
+        assert_eq!(cells.choose_cell(2, &mut rng), None);
+        assert_eq!(cells.get_duplicates(&mut rng), 1);
+
+        assert!(cells.read_profiles(&b"AAAC\tT1\t10\n"[..]).is_err());
+        assert!(cells
+            .read_profiles(&b"AAACCCAAGAAACACT\tT1\tauie\n"[..])
+            .is_err());
+    }
+
+    #[test]
+    fn expression() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let references = references();
+
+        let mut cells = Cells::new(random_barcodes(100, &mut rng), 0.5, 3.0).unwrap();
+        let weights = cells.expression(&references, &mut rng).unwrap();
+        assert!((800.0..2000.0).contains(&weights[0]));
+        assert_eq!(weights[2], 10.0);
+
+        let mut counts = [0; 100];
+        for _ in 0..10_000 {
+            counts[cells.choose_cell(0, &mut rng).unwrap()] += 1;
+        }
+        assert!(counts.iter().max() > Some(&200));
+
+        let mean = (0..1000)
+            .map(|_| cells.get_duplicates(&mut rng))
+            .sum::<usize>() as f64
+            / 1000.0;
+        assert!((2.8..3.2).contains(&mean));
+
+        let mut output = Vec::new();
+        let mut simulated = rustc_hash::FxHashMap::default();
+        simulated.insert(cells.barcode(0).to_string(), (2, 20));
+        cells.write_truth(&mut output, &simulated).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 101);
+        assert_eq!(
+            output.lines().nth(1).unwrap(),
+            format!("{}\t2\t20", cells.barcode(0))
+        );
+    }
+
+    #[test]
+    fn read_barcodes() {
+        let mut description = Description::new(
+            Origin::reference("T1".to_string(), '+', 0, 10),
+            Vec::new(),
+            10,
+            90.0,
+        );
+        assert_eq!(barcodes(&description), None);
+
+        description
+            .labels
+            .push(Label::Cell("AAACCCAAGAAACACT".to_string()));
+        description
+            .labels
+            .push(Label::Umi("ACGTACGTACGT".to_string()));
+        assert_eq!(
+            barcodes(&description),
+            Some((Vec::new(), b"ACGTACGTACGTAGTGTTTCTTGGGTTT".to_vec()))
+        );
+
+        description.origin.strand = '-';
+        assert_eq!(
+            barcodes(&description),
+            Some((b"AAACCCAAGAAACACTACGTACGTACGT".to_vec(), Vec::new()))
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! Manage a population of strains build from one reference
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::{Context, Result};
+
+/* local use */
+use crate::error::Variant as Error;
+use crate::references::{Reference, References};
+use crate::variant;
+
+/// Reference id, position, reference and alternative sequence of a variant
+type VariantKey = (String, usize, Vec<u8>, Vec<u8>);
+
+/// A strain, path is a vcf apply on reference or a fasta of strain sequences
+#[derive(Debug, Clone, PartialEq)]
+pub struct Strain {
+    pub name: String,
+    pub path: String,
+    pub proportion: f64,
+}
+
+impl Strain {
+    /// Return true if strain is define by a vcf
+    pub fn is_vcf(&self) -> bool {
+        self.path.contains(".vcf")
+    }
+}
+
+/// A variant carry by a set of strains
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrainVariant {
+    pub ref_id: String,
+    pub pos: usize,
+    pub reference: Vec<u8>,
+    pub alternative: Vec<u8>,
+    pub strains: Vec<usize>,
+}
+
+/// A population of strains
+#[derive(Debug, Clone, PartialEq)]
+pub struct Population {
+    pub strains: Vec<Strain>,
+    pub variants: Vec<StrainVariant>,
+}
+
+impl Population {
+    /// Read strains from a tsv, columns are name, path to a vcf or a fasta and proportion
+    pub fn from_stream<R>(input: R) -> Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let mut strains = Vec::new();
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .quoting(false)
+            .comment(Some(b'#'))
+            .from_reader(input);
+
+        for record in reader.records() {
+            let record = record?;
+            if record.len() < 3 || record[0].is_empty() || record[1].is_empty() {
+                anyhow::bail!(Error::StrainParsing);
+            }
+
+            strains.push(Strain {
+                name: record[0].to_string(),
+                path: record[1].to_string(),
+                proportion: f64::from_str(&record[2]).with_context(|| Error::StrainParsing)?,
+            });
+        }
+
+        Ok(Self {
+            strains,
+            variants: Vec::new(),
+        })
+    }
+
+    /// Get sequences of each strain and their weight, weight of a sequence is proportional to strain proportion and sequence length
+    ///
+    /// Vcf strains are build by apply first haplotype of genotype on reference sequences, fasta strains sequences are read in file.
+    /// Variants of fasta strains are found only for sequences with same id and length than a reference sequence.
+    pub fn sequences(
+        &mut self,
+        references: &[Reference],
+        weights: &[f64],
+    ) -> Result<(Vec<Reference>, Vec<f64>)> {
+        let mut sequences = Vec::new();
+        let mut seqs_weight = Vec::new();
+        let mut key2variant: rustc_hash::FxHashMap<VariantKey, Vec<usize>> =
+            rustc_hash::FxHashMap::default();
+
+        for (index, strain) in self.strains.iter().enumerate() {
+            let reader = niffler::get_reader(Box::new(std::io::BufReader::new(
+                std::fs::File::open(&strain.path).with_context(|| "Read strain file")?,
+            )))
+            .with_context(|| "Read strain file niffler")?
+            .0;
+
+            if strain.is_vcf() {
+                let variants = variant::vcf::from_stream(reader)?;
+
+                let mut edits = variant::Edits::new(1);
+                edits.add_variants(&variants);
+
+                for variant in variants.iter() {
+                    if let Some(edit) = variant.edit(0) {
+                        key2variant
+                            .entry((
+                                variant.ref_id.clone(),
+                                variant.pos,
+                                variant.reference.clone(),
+                                edit.seq,
+                            ))
+                            .or_default()
+                            .push(index);
+                    }
+                }
+
+                for (reference, weight) in references.iter().zip(weights) {
+                    let mut haplotype =
+                        reference.haplotype(strain.name.clone(), &edits.get(&reference.id, 0));
+                    haplotype.tags.push(("strain", strain.name.clone()));
+
+                    seqs_weight.push(
+                        strain.proportion * weight * haplotype.seq.len() as f64
+                            / reference.seq.len() as f64,
+                    );
+                    sequences.push(haplotype);
+                }
+            } else {
+                let (seqs, prob) = References::read_reference(reader)?;
+
+                for (mut seq, weight) in seqs.into_iter().zip(prob) {
+                    if let Some(reference) = references
+                        .iter()
+                        .find(|x| x.id == seq.id && x.seq.len() == seq.seq.len())
+                    {
+                        for (pos, (r, a)) in reference.seq.iter().zip(seq.seq.iter()).enumerate() {
+                            let (r, a) = (r.to_ascii_uppercase(), a.to_ascii_uppercase());
+                            if r != a && r != b'N' && a != b'N' {
+                                key2variant
+                                    .entry((seq.id.clone(), pos, vec![r], vec![a]))
+                                    .or_default()
+                                    .push(index);
+                            }
+                        }
+                    } else {
+                        log::warn!(
+                            "Strain {} sequence {} didn't match a reference sequence with same length, its variants aren't report in truth",
+                            strain.name,
+                            seq.id
+                        );
+                    }
+
+                    seq.tags.push(("strain", strain.name.clone()));
+
+                    seqs_weight.push(strain.proportion * weight);
+                    sequences.push(seq);
+                }
+            }
+        }
+
+        self.variants = key2variant
+            .into_iter()
+            .map(
+                |((ref_id, pos, reference, alternative), strains)| StrainVariant {
+                    ref_id,
+                    pos,
+                    reference,
+                    alternative,
+                    strains,
+                },
+            )
+            .collect();
+        self.variants.sort_by(|a, b| {
+            (&a.ref_id, a.pos, &a.alternative).cmp(&(&b.ref_id, b.pos, &b.alternative))
+        });
+
+        Ok((sequences, seqs_weight))
+    }
+
+    /// Allele frequency of a variant, sum of normalized proportion of strains carry variant
+    pub fn frequency(&self, variant: &StrainVariant) -> f64 {
+        let total: f64 = self.strains.iter().map(|x| x.proportion).sum();
+
+        if total > 0.0 {
+            variant
+                .strains
+                .iter()
+                .map(|x| self.strains[*x].proportion)
+                .sum::<f64>()
+                / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Write truth table of variants allele frequency, position is 1-based
+    pub fn write_truth<W>(&self, output: &mut W) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        writeln!(output, "#ref_id\tpos\tref\talt\tallele_frequency\tstrains")?;
+
+        for variant in self.variants.iter() {
+            writeln!(
+                output,
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                variant.ref_id,
+                variant.pos + 1,
+                String::from_utf8_lossy(&variant.reference),
+                String::from_utf8_lossy(&variant.alternative),
+                self.frequency(variant),
+                variant
+                    .strains
+                    .iter()
+                    .map(|x| self.strains[*x].name.clone())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    static FASTA: &[u8] = b">chr1
+ACTGACTGACTGACTGACTG
+";
+
+    #[test]
+    fn population() {
+        let vcf = crate::write_temp_file(
+            "strain_a.vcf",
+            b"##fileformat=VCFv4.2
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample
+chr1\t3\t.\tT\tA\t.\tPASS\t.\tGT\t1
+chr1\t6\t.\tC\tCGG\t.\tPASS\t.\tGT\t1
+",
+        );
+        let fasta = crate::write_temp_file(
+            "strain_b.fasta",
+            b">chr1
+ACAGACTGACTGACTGACTC
+",
+        );
+
+        let tsv = format!("#name\tpath\tproportion\nA\t{}\t3\nB\t{}\t1\n", vcf, fasta);
+        let mut population = Population::from_stream(tsv.as_bytes()).unwrap();
+        assert_eq!(population.strains[0].name, "A");
+        assert!(population.strains[0].is_vcf());
+        assert!(!population.strains[1].is_vcf());
+
+        let (references, weights) = References::read_reference(FASTA).unwrap();
+        let (seqs, seqs_weight) = population.sequences(&references, &weights).unwrap();
+
+        assert_eq!(&seqs[0].seq[..], b"ACAGACGGTGACTGACTGACTG");
+        assert_eq!(seqs[0].tags, vec![("strain", "A".to_string())]);
+        assert_eq!(seqs[0].haplotype.as_ref().unwrap().name, "A");
+        assert_eq!(&seqs[1].seq[..], b"ACAGACTGACTGACTGACTC");
+        assert_eq!(seqs[1].tags, vec![("strain", "B".to_string())]);
+        assert_eq!(seqs_weight, vec![66.0, 20.0]);
+
+        let mut output = Vec::new();
+        population.write_truth(&mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "#ref_id\tpos\tref\talt\tallele_frequency\tstrains
+chr1\t3\tT\tA\t1\tA,B
+chr1\t6\tC\tCGG\t0.75\tA
+chr1\t20\tG\tC\t0.25\tB
+"
+        );
+
+        assert!(Population::from_stream(&b"A\tpath\n"[..]).is_err());
+        assert!(Population::from_stream(&b"A\tpath\tauie\n"[..]).is_err());
+    }
+}
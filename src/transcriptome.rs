@@ -0,0 +1,425 @@
+//! Build transcripts from a genome and an annotation
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::{Context, Result};
+
+/* local use */
+use crate::error::Transcriptome as Error;
+use crate::model::transcript::POLY_A_MAX_LENGTH;
+use crate::references::Reference;
+use crate::simulate::description::{Label, Origin};
+
+/// A transcript, exons are sorted 0-based half-open interval on genome
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    pub id: String,
+    pub gene: String,
+    pub ref_id: String,
+    pub strand: char,
+    pub exons: Vec<(usize, usize)>,
+}
+
+impl Transcript {
+    /// Length of spliced transcript
+    pub fn length(&self) -> usize {
+        self.exons.iter().map(|(s, e)| e - s).sum()
+    }
+
+    /// Build spliced sequence of transcript in transcript orientation, None if an exon is outside of genome sequence
+    pub fn sequence(&self, genome: &[u8]) -> Option<Vec<u8>> {
+        let mut seq = Vec::with_capacity(self.length());
+
+        for (start, end) in self.exons.iter() {
+            seq.extend(genome.get(*start..*end)?);
+        }
+
+        if self.strand == '-' {
+            Some(bio::alphabets::dna::revcomp(&seq))
+        } else {
+            Some(seq)
+        }
+    }
+}
+
+/// A collection of transcripts
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub transcripts: Vec<Transcript>,
+    pub expression: rustc_hash::FxHashMap<String, f64>,
+}
+
+impl Annotation {
+    /// Read transcripts from exon records of a gtf, transcript order is order of first exon
+    pub fn from_gtf<R>(input: R) -> Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let mut transcripts: Vec<Transcript> = Vec::new();
+        let mut id2index: rustc_hash::FxHashMap<String, usize> = rustc_hash::FxHashMap::default();
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .quoting(false)
+            .comment(Some(b'#'))
+            .from_reader(input);
+
+        for record in reader.records() {
+            let record = record?;
+            if record.len() < 9 {
+                anyhow::bail!(Error::GtfParsing);
+            }
+
+            if &record[2] != "exon" {
+                continue;
+            }
+
+            let start = usize::from_str(&record[3]).with_context(|| Error::GtfParsing)?;
+            let end = usize::from_str(&record[4]).with_context(|| Error::GtfParsing)?;
+            let strand = match &record[6] {
+                "+" => '+',
+                "-" => '-',
+                _ => anyhow::bail!(Error::GtfParsing),
+            };
+            if start == 0 || end < start {
+                anyhow::bail!(Error::GtfParsing);
+            }
+
+            let id = attribute(&record[8], "transcript_id").ok_or(Error::GtfParsing)?;
+            let index = *id2index.entry(id.clone()).or_insert_with(|| {
+                transcripts.push(Transcript {
+                    gene: attribute(&record[8], "gene_id").unwrap_or_else(|| id.clone()),
+                    id,
+                    ref_id: record[0].to_string(),
+                    strand,
+                    exons: Vec::new(),
+                });
+                transcripts.len() - 1
+            });
+
+            transcripts[index].exons.push((start - 1, end));
+        }
+
+        for transcript in transcripts.iter_mut() {
+            transcript.exons.sort_unstable();
+        }
+
+        Ok(Self {
+            transcripts,
+            expression: rustc_hash::FxHashMap::default(),
+        })
+    }
+
+    /// Read expression of transcripts from a tsv, columns are transcript id and expression (e.g. TPM)
+    pub fn read_expression<R>(&mut self, input: R) -> Result<()>
+    where
+        R: std::io::Read,
+    {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .quoting(false)
+            .comment(Some(b'#'))
+            .from_reader(input);
+
+        for record in reader.records() {
+            let record = record?;
+            if record.len() < 2 || record[0].is_empty() {
+                anyhow::bail!(Error::ExpressionParsing);
+            }
+
+            self.expression.insert(
+                record[0].to_string(),
+                f64::from_str(&record[1]).with_context(|| Error::ExpressionParsing)?,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Expression of a transcript, if no expression is set all transcripts have same expression
+    pub fn expression(&self, id: &str) -> f64 {
+        if self.expression.is_empty() {
+            1.0
+        } else {
+            self.expression.get(id).cloned().unwrap_or(0.0)
+        }
+    }
+
+    /// Get sequence of each transcript follow by a poly-A tail and their weight, weight is transcript expression
+    ///
+    /// Sequences are tagged with transcript and gene id, transcript on a sequence absent of genome are ignored
+    pub fn sequences(&self, genome: &[Reference]) -> Result<(Vec<Reference>, Vec<f64>)> {
+        let id2genome: rustc_hash::FxHashMap<&str, &Reference> =
+            genome.iter().map(|x| (x.id.as_str(), x)).collect();
+
+        let mut sequences = Vec::new();
+        let mut weights = Vec::new();
+        for transcript in self.transcripts.iter() {
+            let mut seq = match id2genome
+                .get(transcript.ref_id.as_str())
+                .and_then(|x| transcript.sequence(&x.seq))
+            {
+                Some(seq) => seq,
+                None => {
+                    log::warn!(
+                        "Transcript {} isn't in genome sequences, it's ignored",
+                        transcript.id
+                    );
+                    continue;
+                }
+            };
+            seq.extend([b'A'; POLY_A_MAX_LENGTH]);
+
+            let mut reference =
+                Reference::new(transcript.id.clone(), seq.into_boxed_slice(), false);
+            reference
+                .labels
+                .push(Label::Transcript(transcript.id.clone()));
+            reference.labels.push(Label::Gene(transcript.gene.clone()));
+
+            sequences.push(reference);
+            weights.push(self.expression(&transcript.id));
+        }
+
+        if sequences.is_empty() || weights.iter().all(|x| *x <= 0.0) {
+            anyhow::bail!(Error::NoTranscript);
+        }
+
+        Ok((sequences, weights))
+    }
+
+    /// Write truth table of transcripts, simulated associate transcript to number of reads and bases produce
+    pub fn write_truth<W>(
+        &self,
+        output: &mut W,
+        simulated: &rustc_hash::FxHashMap<String, (u64, u64)>,
+    ) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        writeln!(
+            output,
+            "#transcript\tgene\tlength\texpression\treads\tbases"
+        )?;
+
+        for transcript in self.transcripts.iter() {
+            let (reads, bases) = simulated.get(&transcript.id).cloned().unwrap_or((0, 0));
+
+            writeln!(
+                output,
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                transcript.id,
+                transcript.gene,
+                transcript.length(),
+                self.expression(&transcript.id),
+                reads,
+                bases
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Tags of a molecule, type of molecule (full-length, truncated or intra-priming) and poly-A tail length
+///
+/// Molecule with less than five_prime_loss base missing at 5' end isn't consider as truncated
+pub fn molecule_tags(
+    origin: &Origin,
+    reference: &Reference,
+    five_prime_loss: usize,
+) -> Vec<(&'static str, String)> {
+    let length = reference.seq.len().saturating_sub(POLY_A_MAX_LENGTH);
+
+    let (begin, end) = if origin.strand == '-' {
+        (
+            reference.seq.len() - origin.end,
+            reference.seq.len() - origin.start,
+        )
+    } else {
+        (origin.start, origin.end)
+    };
+
+    let molecule = if end < length {
+        "intra-priming"
+    } else if begin > five_prime_loss {
+        "truncated"
+    } else {
+        "full-length"
+    };
+
+    vec![
+        ("molecule", molecule.to_string()),
+        ("poly_a", end.saturating_sub(length).to_string()),
+    ]
+}
+
+fn attribute(attributes: &str, key: &str) -> Option<String> {
+    attributes
+        .split(';')
+        .map(|x| x.trim())
+        .filter_map(|x| x.split_once(' '))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.trim_matches('"').to_string())
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    static GTF: &[u8] = b"#gtf
+chr1\ttest\tgene\t1\t40\t.\t+\t.\tgene_id \"G1\";
+chr1\ttest\texon\t1\t4\t.\t+\t.\tgene_id \"G1\"; transcript_id \"T1\";
+chr1\ttest\texon\t11\t14\t.\t+\t.\tgene_id \"G1\"; transcript_id \"T1\";
+chr1\ttest\texon\t11\t20\t.\t+\t.\tgene_id \"G1\"; transcript_id \"T2\";
+chr1\ttest\texon\t31\t34\t.\t-\t.\tgene_id \"G2\"; transcript_id \"T3\";
+chr1\ttest\texon\t21\t24\t.\t-\t.\tgene_id \"G2\"; transcript_id \"T3\";
+chr2\ttest\texon\t1\t4\t.\t-\t.\tgene_id \"G3\"; transcript_id \"T4\";
+";
+
+    static FASTA: &[u8] = b">chr1
+ACTGTTTTTTGGGGCCCCCCAAACTTTTTTTTGGCCTTTT
+";
+
+    #[test]
+    fn gtf() {
+        let annotation = Annotation::from_gtf(GTF).unwrap();
+
+        assert_eq!(annotation.transcripts.len(), 4);
+        assert_eq!(
+            annotation.transcripts[2],
+            Transcript {
+                id: "T3".to_string(),
+                gene: "G2".to_string(),
+                ref_id: "chr1".to_string(),
+                strand: '-',
+                exons: vec![(20, 24), (30, 34)],
+            }
+        );
+        assert_eq!(annotation.transcripts[0].length(), 8);
+
+        assert!(
+            Annotation::from_gtf(&b"chr1\ttest\texon\t1\t4\t.\t+\t.\tgene_id \"G1\";\n"[..])
+                .is_err()
+        );
+        assert!(Annotation::from_gtf(
+            &b"chr1\ttest\texon\t1\t4\t.\t.\t.\ttranscript_id \"T1\";\n"[..]
+        )
+        .is_err());
+        assert!(Annotation::from_gtf(
+            &b"chr1\ttest\texon\t4\t1\t.\t+\t.\ttranscript_id \"T1\";\n"[..]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn sequences() {
+        let genome = crate::references::References::read_reference(FASTA)
+            .unwrap()
+            .0;
+        let mut annotation = Annotation::from_gtf(GTF).unwrap();
+
+        let (seqs, weights) = annotation.sequences(&genome).unwrap();
+        assert_eq!(seqs.len(), 3);
+        assert_eq!(&seqs[0].seq[..8], b"ACTGGGGG");
+        assert_eq!(&seqs[2].seq[..8], b"CCAAGTTT");
+        assert_eq!(seqs[0].seq.len(), 8 + POLY_A_MAX_LENGTH);
+        assert_eq!(
+            seqs[1].labels,
+            vec![
+                Label::Transcript("T2".to_string()),
+                Label::Gene("G1".to_string())
+            ]
+        );
+        assert_eq!(weights, vec![1.0, 1.0, 1.0]);
+
+        annotation
+            .read_expression(&b"#transcript\ttpm\nT1\t10\nT3\t2.5\n"[..])
+            .unwrap();
+        let (_, weights) = annotation.sequences(&genome).unwrap();
+        assert_eq!(weights, vec![10.0, 0.0, 2.5]);
+
+        assert!(annotation.read_expression(&b"T1\tauie\n"[..]).is_err());
+        assert!(Annotation::from_gtf(GTF)
+            .unwrap()
+            .sequences(&genome[..0])
+            .is_err());
+
+        let mut output = Vec::new();
+        let mut simulated = rustc_hash::FxHashMap::default();
+        simulated.insert("T1".to_string(), (2, 20));
+        annotation.write_truth(&mut output, &simulated).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "#transcript\tgene\tlength\texpression\treads\tbases
+T1\tG1\t8\t10\t2\t20
+T2\tG1\t10\t0\t0\t0
+T3\tG2\t8\t2.5\t0\t0
+T4\tG3\t4\t0\t0\t0
+"
+        );
+    }
+
+    #[test]
+    fn tags() {
+        let mut seq = b"ACTGACTGAC".to_vec();
+        seq.extend([b'A'; POLY_A_MAX_LENGTH]);
+        let reference = Reference::new("T1".to_string(), seq.into_boxed_slice(), false);
+
+        assert_eq!(
+            molecule_tags(
+                &Origin::reference("T1".to_string(), '+', 0, 40),
+                &reference,
+                0
+            ),
+            vec![
+                ("molecule", "full-length".to_string()),
+                ("poly_a", "30".to_string())
+            ]
+        );
+        assert_eq!(
+            molecule_tags(
+                &Origin::reference("T1".to_string(), '-', POLY_A_MAX_LENGTH - 30, 308),
+                &reference,
+                0
+            ),
+            vec![
+                ("molecule", "truncated".to_string()),
+                ("poly_a", "30".to_string())
+            ]
+        );
+        assert_eq!(
+            molecule_tags(
+                &Origin::reference("T1".to_string(), '+', 0, 5),
+                &reference,
+                0
+            )[0]
+            .1,
+            "intra-priming"
+        );
+        assert_eq!(
+            molecule_tags(
+                &Origin::reference("T1".to_string(), '+', 5, 40),
+                &reference,
+                8
+            )[0]
+            .1,
+            "full-length"
+        );
+        assert_eq!(
+            molecule_tags(
+                &Origin::reference("T1".to_string(), '+', 5, 40),
+                &reference,
+                0
+            )[0]
+            .1,
+            "truncated"
+        );
+    }
+}
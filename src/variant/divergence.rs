@@ -0,0 +1,217 @@
+//! Mutate reference to simulate a related strain
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::Result;
+
+/* local use */
+use crate::error::Cli as Error;
+use crate::references::References;
+use crate::variant::{sv, vcf, Variant};
+
+/* constant definition */
+const INDEL_EXTEND_CHANCE: f64 = 0.5;
+const INDEL_MAX_LENGTH: usize = 50;
+
+/// Fraction of reference base mutate, can be parse from a percentage (e.g. 0.5%)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Divergence(pub f64);
+
+impl FromStr for Divergence {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rate = match s.strip_suffix('%') {
+            Some(percent) => {
+                f64::from_str(percent).map_err(|_| Error::CantParseDivergence)? / 100.0
+            }
+            None => f64::from_str(s).map_err(|_| Error::CantParseDivergence)?,
+        };
+
+        if !(0.0..1.0).contains(&rate) {
+            return Err(Error::CantParseDivergence);
+        }
+
+        Ok(Divergence(rate))
+    }
+}
+
+/// Generate random variants on each reference, number of variant is reference length times divergence
+///
+/// A variant is a snv with probability `snv_indel / (snv_indel + 1)`, a snv is a transition with probability `ts_tv / (ts_tv + 1)`,
+/// indels are insertion or deletion with same probability and variants are carry by all haplotypes
+pub fn random<RNG>(
+    divergence: Divergence,
+    snv_indel: f64,
+    ts_tv: f64,
+    references: &References,
+    ploidy: usize,
+    rng: &mut RNG,
+) -> Vec<Variant>
+where
+    RNG: rand::Rng,
+{
+    let snv_chance = (snv_indel / (snv_indel + 1.0)).clamp(0.0, 1.0);
+    let transition_chance = (ts_tv / (ts_tv + 1.0)).clamp(0.0, 1.0);
+
+    let mut variants = Vec::new();
+    for reference in references.sequences.iter() {
+        let length = reference.seq.len();
+        let number = ((length as f64 * divergence.0).round() as usize).min(length);
+
+        let mut positions = rand::seq::index::sample(rng, length, number).into_vec();
+        positions.sort_unstable();
+
+        let mut next_free = 0;
+        for pos in positions {
+            if pos < next_free {
+                continue;
+            }
+
+            let base = reference.seq[pos].to_ascii_uppercase();
+            if !crate::NUCS.contains(&base) {
+                continue;
+            }
+
+            let (ref_allele, alt_allele) = if rng.gen_bool(snv_chance) {
+                (vec![base], vec![substitute(base, transition_chance, rng)])
+            } else {
+                let mut indel_length = 1;
+                while indel_length < INDEL_MAX_LENGTH && rng.gen_bool(INDEL_EXTEND_CHANCE) {
+                    indel_length += 1;
+                }
+
+                if rng.gen_bool(0.5) {
+                    let mut alt = vec![base];
+                    alt.extend(crate::random_seq(indel_length, rng));
+                    (vec![base], alt)
+                } else {
+                    let end = (pos + 1 + indel_length).min(length);
+                    (reference.seq[pos..end].to_ascii_uppercase(), vec![base])
+                }
+            };
+
+            next_free = pos + ref_allele.len() + 1;
+            variants.push(Variant {
+                ref_id: reference.id.clone(),
+                pos,
+                reference: ref_allele,
+                alternatives: vec![alt_allele],
+                genotype: vec![1; ploidy],
+            });
+        }
+    }
+
+    variants
+}
+
+/// Get a new base, a transition with probability transition_chance else a transversion
+fn substitute<RNG>(base: u8, transition_chance: f64, rng: &mut RNG) -> u8
+where
+    RNG: rand::Rng,
+{
+    if rng.gen_bool(transition_chance) {
+        match base {
+            b'A' => b'G',
+            b'G' => b'A',
+            b'C' => b'T',
+            _ => b'C',
+        }
+    } else {
+        let transversions = match base {
+            b'A' | b'G' => [b'C', b'T'],
+            _ => [b'A', b'G'],
+        };
+
+        transversions[rng.gen_range(0..2)]
+    }
+}
+
+/// Write variants in vcf format
+pub fn write_vcf<W>(output: &mut W, references: &References, variants: &[Variant]) -> Result<()>
+where
+    W: std::io::Write,
+{
+    vcf::write_header(output, references, &[])?;
+
+    for (i, variant) in variants.iter().enumerate() {
+        writeln!(
+            output,
+            "{}\t{}\trustyread_divergence_{}\t{}\t{}\t.\tPASS\t.\tGT\t{}",
+            variant.ref_id,
+            variant.pos + 1,
+            i,
+            String::from_utf8_lossy(&variant.reference),
+            variant
+                .alternatives
+                .iter()
+                .map(|x| String::from_utf8_lossy(x).to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+            sv::genotype2str(&variant.genotype)
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Divergence::from_str("0.5%").unwrap(), Divergence(0.005));
+        assert_eq!(Divergence::from_str("0.01").unwrap(), Divergence(0.01));
+
+        assert!(Divergence::from_str("auie%").is_err());
+        assert!(Divergence::from_str("100%").is_err());
+    }
+
+    #[test]
+    fn mutate() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let mut fasta = b">random_seq_0\n".to_vec();
+        fasta.extend(crate::random_seq(100_000, &mut rng));
+        fasta.push(b'\n');
+        let references = References::from_stream(&fasta[..]).unwrap();
+
+        let variants = random(Divergence(0.01), 4.0, 2.0, &references, 2, &mut rng);
+
+        assert!((950..=1000).contains(&variants.len()));
+        assert!(variants
+            .windows(2)
+            .all(|w| w[0].pos + w[0].reference.len() < w[1].pos));
+        assert!(variants.iter().all(|v| v.genotype == vec![1, 1]
+            && references.sequences[0].seq[v.pos..v.pos + v.reference.len()] == v.reference[..]));
+
+        let snvs: Vec<&Variant> = variants
+            .iter()
+            .filter(|v| v.reference.len() == 1 && v.alternatives[0].len() == 1)
+            .collect();
+        let ratio = snvs.len() as f64 / (variants.len() - snvs.len()) as f64;
+        assert!((3.0..5.0).contains(&ratio));
+
+        let transitions = snvs
+            .iter()
+            .filter(|v| {
+                matches!(
+                    (v.reference[0], v.alternatives[0][0]),
+                    (b'A', b'G') | (b'G', b'A') | (b'C', b'T') | (b'T', b'C')
+                )
+            })
+            .count();
+        let ts_tv = transitions as f64 / (snvs.len() - transitions) as f64;
+        assert!((1.6..2.4).contains(&ts_tv));
+
+        let mut output = Vec::new();
+        write_vcf(&mut output, &references, &variants[..1]).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("##contig=<ID=random_seq_0,length=100000>"));
+        assert!(output.lines().last().unwrap().starts_with("random_seq_0\t"));
+    }
+}
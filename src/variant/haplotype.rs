@@ -0,0 +1,291 @@
+//! Build haplotype sequence from a reference and a set of edit
+
+/* standard use */
+
+/* crate use */
+
+/* local use */
+use crate::simulate::description::Lifted;
+use crate::variant::repeat::RepeatAllele;
+
+/// Replace `length` base of reference at position `pos` by `seq`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub pos: usize,
+    pub length: usize,
+    pub seq: Vec<u8>,
+    pub inverted: bool,
+}
+
+impl Edit {
+    /// Build a new edit
+    pub fn new(pos: usize, length: usize, seq: Vec<u8>) -> Self {
+        Self {
+            pos,
+            length,
+            seq,
+            inverted: false,
+        }
+    }
+
+    /// Build an edit where `seq` is reverse complement of replaced reference
+    pub fn inversion(pos: usize, seq: Vec<u8>) -> Self {
+        Self {
+            pos,
+            length: seq.len(),
+            seq,
+            inverted: true,
+        }
+    }
+
+    /// Position after last reference base replaced by edit
+    pub fn end(&self) -> usize {
+        self.pos + self.length
+    }
+}
+
+/// A region where haplotype and reference are colinear, or reverse colinear
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Segment {
+    hap_start: usize,
+    ref_start: usize,
+    length: usize,
+    reverse: bool,
+}
+
+/// Convert haplotype coordinate in reference coordinate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Liftover {
+    segments: Vec<Segment>,
+}
+
+impl Liftover {
+    /// Get reference position of a haplotype position, position inside an insertion are lifted to insertion point
+    pub fn lift(&self, pos: usize) -> usize {
+        let index = self.segments.partition_point(|s| s.hap_start <= pos);
+
+        if index == 0 {
+            return 0;
+        }
+
+        let segment = &self.segments[index - 1];
+        if pos < segment.hap_start + segment.length {
+            if segment.reverse {
+                segment.ref_start + segment.length - 1 - (pos - segment.hap_start)
+            } else {
+                segment.ref_start + pos - segment.hap_start
+            }
+        } else {
+            segment.ref_start + segment.length
+        }
+    }
+
+    /// Get haplotype position of a reference position, None if position isn't colinear in haplotype
+    pub fn unlift(&self, pos: usize) -> Option<usize> {
+        self.segments
+            .iter()
+            .filter(|s| !s.reverse && s.ref_start <= pos && pos <= s.ref_start + s.length)
+            .map(|s| s.hap_start + pos - s.ref_start)
+            .next()
+    }
+
+    fn push(&mut self, hap_start: usize, ref_start: usize, length: usize, reverse: bool) {
+        if length == 0 {
+            return;
+        }
+
+        if let Some(last) = self.segments.last_mut() {
+            if !last.reverse
+                && !reverse
+                && last.hap_start + last.length == hap_start
+                && last.ref_start + last.length == ref_start
+            {
+                last.length += length;
+                return;
+            }
+        }
+
+        self.segments.push(Segment {
+            hap_start,
+            ref_start,
+            length,
+            reverse,
+        });
+    }
+}
+
+/// Information to link a haplotype to his reference
+#[derive(Debug, Clone, PartialEq)]
+pub struct Haplotype {
+    pub name: String,
+    pub ref_length: usize,
+    pub liftover: Liftover,
+    pub repeats: Vec<RepeatAllele>,
+}
+
+impl Haplotype {
+    /// Lift a fragment of haplotype on reference, fragment coordinate are on strand
+    pub fn lift(&self, strand: char, start: usize, end: usize, hap_length: usize) -> Lifted {
+        let (start, end) = if strand == '-' {
+            (
+                self.ref_length - self.liftover.lift(hap_length - start),
+                self.ref_length - self.liftover.lift(hap_length - end),
+            )
+        } else {
+            (self.liftover.lift(start), self.liftover.lift(end))
+        };
+
+        Lifted {
+            haplotype: self.name.clone(),
+            start,
+            end,
+        }
+    }
+}
+
+/// Apply edits on reference sequence, edits must be sorted by position, overlapping edits are ignored
+pub fn build(name: String, reference: &[u8], edits: &[Edit]) -> (Vec<u8>, Haplotype) {
+    let mut seq = Vec::with_capacity(reference.len());
+    let mut liftover = Liftover {
+        segments: Vec::new(),
+    };
+    let mut ref_pos = 0;
+
+    for edit in edits {
+        if edit.pos < ref_pos || edit.end() > reference.len() {
+            log::warn!(
+                "Edit at position {} of haplotype {} overlap a previous edit or reference end, it's ignored",
+                edit.pos,
+                name
+            );
+            continue;
+        }
+
+        liftover.push(seq.len(), ref_pos, edit.pos - ref_pos, false);
+        seq.extend(&reference[ref_pos..edit.pos]);
+
+        liftover.push(
+            seq.len(),
+            edit.pos,
+            edit.length.min(edit.seq.len()),
+            edit.inverted,
+        );
+        seq.extend(&edit.seq);
+
+        ref_pos = edit.end();
+    }
+
+    liftover.push(seq.len(), ref_pos, reference.len() - ref_pos, false);
+    seq.extend(&reference[ref_pos..]);
+
+    (
+        seq,
+        Haplotype {
+            name,
+            ref_length: reference.len(),
+            liftover,
+            repeats: Vec::new(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    fn init() {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Trace)
+            .is_test(true)
+            .try_init();
+    }
+
+    #[test]
+    fn apply_edit() {
+        init();
+
+        let reference = b"ACTGACTGACTGACTGACTG";
+
+        let (seq, _) = build(
+            "1".to_string(),
+            reference,
+            &[
+                Edit::new(2, 1, b"A".to_vec()),
+                Edit::new(5, 1, b"CGGG".to_vec()),
+                Edit::new(10, 4, b"T".to_vec()),
+                Edit::new(11, 1, b"G".to_vec()), // overlap previous edit
+            ],
+        );
+
+        assert_eq!(b"ACAGACGGGTGACTTGACTG".to_vec(), seq);
+    }
+
+    #[test]
+    fn liftover() {
+        init();
+
+        let reference = b"ACTGACTGACTGACTGACTG";
+
+        let (seq, haplotype) = build(
+            "1".to_string(),
+            reference,
+            &[
+                Edit::new(5, 1, b"CGGG".to_vec()),
+                Edit::new(10, 4, b"T".to_vec()),
+            ],
+        );
+
+        assert_eq!(
+            (0..=seq.len())
+                .map(|x| haplotype.liftover.lift(x))
+                .collect::<Vec<usize>>(),
+            vec![0, 1, 2, 3, 4, 5, 6, 6, 6, 6, 7, 8, 9, 10, 14, 15, 16, 17, 18, 19, 20]
+        );
+
+        assert_eq!(haplotype.liftover.unlift(3), Some(3));
+        assert_eq!(haplotype.liftover.unlift(10), Some(13));
+        assert_eq!(haplotype.liftover.unlift(12), None);
+        assert_eq!(haplotype.liftover.unlift(15), Some(15));
+
+        assert_eq!(
+            haplotype.lift('+', 2, 12, seq.len()),
+            Lifted {
+                haplotype: "1".to_string(),
+                start: 2,
+                end: 9
+            }
+        );
+        assert_eq!(
+            haplotype.lift('-', 2, 12, seq.len()),
+            Lifted {
+                haplotype: "1".to_string(),
+                start: 2,
+                end: 14
+            }
+        );
+    }
+
+    #[test]
+    fn liftover_inversion() {
+        init();
+
+        let reference = b"ACTGACTGACTGACTGACTG";
+
+        let (seq, haplotype) = build(
+            "1".to_string(),
+            reference,
+            &[Edit::inversion(
+                4,
+                bio::alphabets::dna::revcomp(&reference[4..8]),
+            )],
+        );
+
+        assert_eq!(b"ACTGCAGTACTGACTGACTG".to_vec(), seq);
+        assert_eq!(
+            (0..=seq.len())
+                .map(|x| haplotype.liftover.lift(x))
+                .collect::<Vec<usize>>(),
+            vec![0, 1, 2, 3, 7, 6, 5, 4, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]
+        );
+    }
+}
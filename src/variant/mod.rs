@@ -0,0 +1,321 @@
+//! Manage variant and haplotype build from them
+
+/* local use */
+use crate::references::References;
+
+/* module declaration */
+pub mod divergence;
+pub mod haplotype;
+pub mod repeat;
+pub mod somatic;
+pub mod sv;
+pub mod vcf;
+
+/* reexport for easiest use */
+pub use haplotype::{Edit, Haplotype, Liftover};
+pub use repeat::{Repeat, RepeatAllele};
+pub use somatic::{CopyNumber, Subclone, Tumour};
+pub use sv::{StructuralVariant, SvType};
+
+/// Store a small variant and his phased genotype
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub ref_id: String,
+    pub pos: usize,
+    pub reference: Vec<u8>,
+    pub alternatives: Vec<Vec<u8>>,
+    pub genotype: Vec<usize>,
+}
+
+impl Variant {
+    /// Get edit apply by this variant on haplotype, None if haplotype carry reference allele
+    pub fn edit(&self, haplotype: usize) -> Option<Edit> {
+        match self.genotype.get(haplotype) {
+            Some(0) | None => None,
+            Some(allele) => self
+                .alternatives
+                .get(allele - 1)
+                .map(|alt| Edit::new(self.pos, self.reference.len(), alt.clone())),
+        }
+    }
+}
+
+/// Store edits apply on each haplotype of each reference
+#[derive(Debug, Clone)]
+pub struct Edits {
+    ploidy: usize,
+    ref2edits: rustc_hash::FxHashMap<String, Vec<Vec<Edit>>>,
+    repeats: Vec<Repeat>,
+}
+
+impl Edits {
+    /// Build an empty set of edits
+    pub fn new(ploidy: usize) -> Self {
+        Self {
+            ploidy,
+            ref2edits: rustc_hash::FxHashMap::default(),
+            repeats: Vec::new(),
+        }
+    }
+
+    /// Number of haplotype
+    pub fn ploidy(&self) -> usize {
+        self.ploidy
+    }
+
+    /// Add an edit on a haplotype of a reference, edit on haplotype upper than ploidy are ignored
+    pub fn add(&mut self, ref_id: &str, haplotype: usize, edit: Edit) {
+        if haplotype >= self.ploidy {
+            return;
+        }
+
+        let ploidy = self.ploidy;
+        self.ref2edits
+            .entry(ref_id.to_string())
+            .or_insert_with(|| vec![Vec::new(); ploidy])[haplotype]
+            .push(edit);
+    }
+
+    /// Add edits of small variants
+    pub fn add_variants(&mut self, variants: &[Variant]) {
+        for variant in variants {
+            for haplotype in 0..self.ploidy {
+                if let Some(edit) = variant.edit(haplotype) {
+                    self.add(&variant.ref_id, haplotype, edit);
+                }
+            }
+        }
+    }
+
+    /// Add edits of structural variants
+    pub fn add_structural(&mut self, svs: &[StructuralVariant], references: &References) {
+        for sv in svs {
+            let edits = sv.edits(references);
+
+            for (haplotype, allele) in sv.genotype.iter().enumerate() {
+                if *allele == 0 {
+                    continue;
+                }
+
+                for (ref_id, edit) in edits.iter() {
+                    self.add(ref_id, haplotype, edit.clone());
+                }
+            }
+        }
+    }
+
+    /// Add edits of tandem repeats
+    pub fn add_repeats(&mut self, repeats: &[Repeat]) {
+        for repeat in repeats {
+            for haplotype in 0..self.ploidy {
+                if let Some(edit) = repeat.edit(haplotype) {
+                    self.add(&repeat.ref_id, haplotype, edit);
+                }
+            }
+        }
+
+        self.repeats.extend_from_slice(repeats);
+    }
+
+    /// Add edits of copy number changes, they must be add after other edits
+    ///
+    /// Edits include in region of a copy number change are apply on each copy of region, copy number changes overlapping an other edit are ignored
+    pub fn add_copy_numbers(&mut self, copy_numbers: &[CopyNumber], references: &References) {
+        for cn in copy_numbers {
+            let reference = match references.sequences.iter().find(|x| x.id == cn.ref_id) {
+                Some(r) if cn.end <= r.seq.len() => r,
+                _ => {
+                    log::warn!(
+                        "Copy number change {}:{}-{} isn't in a reference, it's ignored",
+                        cn.ref_id,
+                        cn.start,
+                        cn.end
+                    );
+                    continue;
+                }
+            };
+
+            for haplotype in 0..self.ploidy {
+                let copies = match cn.copies(haplotype) {
+                    Some(1) | None => continue,
+                    Some(copies) => copies,
+                };
+
+                let mut inside = Vec::new();
+                if let Some(edits) = self
+                    .ref2edits
+                    .get_mut(&cn.ref_id)
+                    .and_then(|x| x.get_mut(haplotype))
+                {
+                    if edits.iter().any(|e| {
+                        e.pos < cn.end
+                            && cn.start < e.end()
+                            && (e.pos < cn.start || cn.end < e.end())
+                    }) {
+                        log::warn!(
+                            "Copy number change {}:{}-{} overlap an other edit, it's ignored",
+                            cn.ref_id,
+                            cn.start,
+                            cn.end
+                        );
+                        continue;
+                    }
+
+                    let (region, outside): (Vec<Edit>, Vec<Edit>) = edits
+                        .drain(..)
+                        .partition(|e| cn.start <= e.pos && e.end() <= cn.end);
+                    *edits = outside;
+                    inside = region;
+                }
+
+                inside.sort_by_key(|x| x.pos);
+                for edit in inside.iter_mut() {
+                    edit.pos -= cn.start;
+                }
+                let (region, _) = haplotype::build(
+                    (haplotype + 1).to_string(),
+                    &reference.seq[cn.start..cn.end],
+                    &inside,
+                );
+
+                self.add(
+                    &cn.ref_id,
+                    haplotype,
+                    Edit::new(cn.start, cn.end - cn.start, region.repeat(copies)),
+                );
+            }
+        }
+    }
+
+    /// Get repeat alleles of a haplotype of a reference, liftover is used to found allele position in haplotype
+    pub fn repeat_alleles(
+        &self,
+        ref_id: &str,
+        haplotype: usize,
+        liftover: &Liftover,
+    ) -> Vec<RepeatAllele> {
+        let mut alleles: Vec<RepeatAllele> = self
+            .repeats
+            .iter()
+            .filter(|r| r.ref_id == ref_id)
+            .filter_map(|r| {
+                Some(RepeatAllele {
+                    locus: r.locus(),
+                    start: liftover.unlift(r.start)?,
+                    motif: r.motif.clone(),
+                    copies: r.copies(haplotype)?,
+                    mosaicism: r.mosaicism,
+                })
+            })
+            .collect();
+
+        alleles.sort_by_key(|x| x.start);
+
+        alleles
+    }
+
+    /// Get edits of a haplotype of a reference sorted by position
+    pub fn get(&self, ref_id: &str, haplotype: usize) -> Vec<Edit> {
+        let mut edits = self
+            .ref2edits
+            .get(ref_id)
+            .and_then(|x| x.get(haplotype))
+            .cloned()
+            .unwrap_or_default();
+
+        edits.sort_by_key(|x| x.pos);
+
+        edits
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn edit() {
+        let variant = Variant {
+            ref_id: "chr1".to_string(),
+            pos: 10,
+            reference: b"A".to_vec(),
+            alternatives: vec![b"C".to_vec(), b"AT".to_vec()],
+            genotype: vec![0, 2],
+        };
+
+        assert_eq!(variant.edit(0), None);
+        assert_eq!(variant.edit(1), Some(Edit::new(10, 1, b"AT".to_vec())));
+        assert_eq!(variant.edit(2), None);
+    }
+
+    #[test]
+    fn edits() {
+        let mut edits = Edits::new(2);
+
+        edits.add_variants(&[
+            Variant {
+                ref_id: "chr1".to_string(),
+                pos: 10,
+                reference: b"A".to_vec(),
+                alternatives: vec![b"C".to_vec()],
+                genotype: vec![1, 1, 1],
+            },
+            Variant {
+                ref_id: "chr1".to_string(),
+                pos: 5,
+                reference: b"A".to_vec(),
+                alternatives: vec![b"T".to_vec()],
+                genotype: vec![0, 1],
+            },
+        ]);
+
+        assert_eq!(edits.ploidy(), 2);
+        assert_eq!(edits.get("chr1", 0), vec![Edit::new(10, 1, b"C".to_vec())]);
+        assert_eq!(
+            edits.get("chr1", 1),
+            vec![
+                Edit::new(5, 1, b"T".to_vec()),
+                Edit::new(10, 1, b"C".to_vec())
+            ]
+        );
+        assert_eq!(edits.get("chr1", 2), vec![]);
+        assert_eq!(edits.get("chr2", 0), vec![]);
+    }
+
+    #[test]
+    fn repeats() {
+        let mut edits = Edits::new(2);
+
+        edits.add_repeats(&[Repeat {
+            ref_id: "chr1".to_string(),
+            start: 4,
+            end: 10,
+            motif: b"CA".to_vec(),
+            copies: vec![1, 5],
+            mosaicism: 0.0,
+        }]);
+
+        assert_eq!(edits.get("chr1", 0), vec![Edit::new(4, 6, b"CA".to_vec())]);
+        assert_eq!(
+            edits.get("chr1", 1),
+            vec![Edit::new(4, 6, b"CACACACACA".to_vec())]
+        );
+
+        let reference = b"TTTTCACACATTTT";
+        let (seq, haplotype) = haplotype::build("2".to_string(), reference, &edits.get("chr1", 1));
+        let alleles = edits.repeat_alleles("chr1", 1, &haplotype.liftover);
+
+        assert_eq!(
+            alleles,
+            vec![RepeatAllele {
+                locus: "chr1:4-10".to_string(),
+                start: 4,
+                motif: b"CA".to_vec(),
+                copies: 5,
+                mosaicism: 0.0,
+            }]
+        );
+        assert_eq!(&seq[alleles[0].start..alleles[0].end()], b"CACACACACA");
+        assert_eq!(edits.repeat_alleles("chr2", 1, &haplotype.liftover), vec![]);
+    }
+}
@@ -0,0 +1,277 @@
+//! Tandem repeat expansion and contraction
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::Result;
+use rand::distributions::Distribution;
+
+/* local use */
+use crate::error::Variant as Error;
+use crate::variant::Edit;
+
+/* constant definition */
+/// Max motif length of a short tandem repeat
+pub const STR_MAX_MOTIF: usize = 6;
+
+/// Probability of stutter by repeat unit of a short tandem repeat
+pub const STR_STUTTER_RATE: f64 = 0.005;
+
+/// Max probability of stutter of a short tandem repeat
+pub const STR_STUTTER_MAX: f64 = 0.5;
+
+/// Probability a stutter is a contraction
+pub const STR_STUTTER_CONTRACTION: f64 = 0.7;
+
+/// Store a tandem repeat locus and his number of copy on each haplotype
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repeat {
+    pub ref_id: String,
+    pub start: usize,
+    pub end: usize,
+    pub motif: Vec<u8>,
+    pub copies: Vec<usize>,
+    pub mosaicism: f64,
+}
+
+impl Repeat {
+    /// Name of locus
+    pub fn locus(&self) -> String {
+        format!("{}:{}-{}", self.ref_id, self.start, self.end)
+    }
+
+    /// Number of copy on a haplotype, if only one number of copy is set it's used for all haplotypes
+    pub fn copies(&self, haplotype: usize) -> Option<usize> {
+        if self.copies.len() == 1 {
+            Some(self.copies[0])
+        } else {
+            self.copies.get(haplotype).copied()
+        }
+    }
+
+    /// Get edit apply by repeat on haplotype
+    pub fn edit(&self, haplotype: usize) -> Option<Edit> {
+        self.copies(haplotype)
+            .map(|copies| Edit::new(self.start, self.end - self.start, self.motif.repeat(copies)))
+    }
+}
+
+/// Repeat allele carry by a haplotype, start is in haplotype coordinate
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepeatAllele {
+    pub locus: String,
+    pub start: usize,
+    pub motif: Vec<u8>,
+    pub copies: usize,
+    pub mosaicism: f64,
+}
+
+impl RepeatAllele {
+    /// End of repeat allele in haplotype coordinate
+    pub fn end(&self) -> usize {
+        self.start + self.length()
+    }
+
+    /// Length of repeat allele
+    pub fn length(&self) -> usize {
+        self.motif.len() * self.copies
+    }
+
+    /// Get number of copy of repeat in a read, if mosaicism is set number of copy follow a normal distribution
+    pub fn somatic_copies<RNG>(&self, rng: &mut RNG) -> usize
+    where
+        RNG: rand::Rng,
+    {
+        if self.mosaicism > 0.0 {
+            match rand_distr::Normal::new(self.copies as f64, self.mosaicism) {
+                Ok(dist) => dist.sample(rng).round().max(0.0) as usize,
+                Err(_) => self.copies,
+            }
+        } else {
+            self.copies
+        }
+    }
+
+    /// Get number of copy read by sequencer, a short tandem repeat lost or gain one unit with a probability proportional to number of copy
+    pub fn stutter<RNG>(&self, copies: usize, rng: &mut RNG) -> usize
+    where
+        RNG: rand::Rng,
+    {
+        if copies == 0 || self.motif.len() > STR_MAX_MOTIF {
+            return copies;
+        }
+
+        if rng.gen_bool((STR_STUTTER_RATE * copies as f64).min(STR_STUTTER_MAX)) {
+            if rng.gen_bool(STR_STUTTER_CONTRACTION) {
+                copies - 1
+            } else {
+                copies + 1
+            }
+        } else {
+            copies
+        }
+    }
+
+    /// Description of allele use in read description
+    pub fn description(&self, copies: usize) -> String {
+        format!(
+            "{},{},{},{}",
+            self.locus,
+            std::str::from_utf8(&self.motif).unwrap_or_default(),
+            copies,
+            copies * self.motif.len()
+        )
+    }
+}
+
+/// Read a repeat catalog in bed format
+///
+/// Columns are reference, start, end, motif, number of copy on each haplotype separate by ',' and an optional
+/// standard deviation of number of copy in each read to simulate somatic mosaicism
+pub fn from_stream<R>(input: R) -> Result<Vec<Repeat>>
+where
+    R: std::io::Read,
+{
+    let mut repeats = Vec::new();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(false)
+        .flexible(true)
+        .quoting(false)
+        .comment(Some(b'#'))
+        .from_reader(input);
+
+    for record in reader.records() {
+        let record = record?;
+        if record.len() < 5 || record[3].is_empty() {
+            anyhow::bail!(Error::RepeatParsing);
+        }
+
+        let start = usize::from_str(&record[1])?;
+        let end = usize::from_str(&record[2])?;
+        if end < start {
+            anyhow::bail!(Error::RepeatParsing);
+        }
+
+        repeats.push(Repeat {
+            ref_id: record[0].to_string(),
+            start,
+            end,
+            motif: record[3].as_bytes().to_ascii_uppercase(),
+            copies: record[4]
+                .split(|c| c == ',' || c == '|')
+                .map(usize::from_str)
+                .collect::<Result<Vec<usize>, std::num::ParseIntError>>()?,
+            mosaicism: if let Some(m) = record.get(5) {
+                f64::from_str(m)?
+            } else {
+                0.0
+            },
+        });
+    }
+
+    Ok(repeats)
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    static BED: &[u8] = b"# catalog
+chr1\t10\t22\tCAG\t2,8
+chr2\t100\t130\tgaa\t10\t1.5
+";
+
+    #[test]
+    fn read() {
+        let repeats = from_stream(BED).unwrap();
+
+        assert_eq!(
+            repeats,
+            vec![
+                Repeat {
+                    ref_id: "chr1".to_string(),
+                    start: 10,
+                    end: 22,
+                    motif: b"CAG".to_vec(),
+                    copies: vec![2, 8],
+                    mosaicism: 0.0,
+                },
+                Repeat {
+                    ref_id: "chr2".to_string(),
+                    start: 100,
+                    end: 130,
+                    motif: b"GAA".to_vec(),
+                    copies: vec![10],
+                    mosaicism: 1.5,
+                }
+            ]
+        );
+
+        assert_eq!(repeats[0].locus(), "chr1:10-22");
+        assert_eq!(repeats[0].copies(1), Some(8));
+        assert_eq!(repeats[0].copies(2), None);
+        assert_eq!(repeats[1].copies(1), Some(10));
+        assert_eq!(
+            repeats[0].edit(0),
+            Some(Edit::new(10, 12, b"CAGCAG".to_vec()))
+        );
+
+        assert!(from_stream(&b"chr1\t10\t22\t\t2,8\n"[..]).is_err());
+        assert!(from_stream(&b"chr1\t10\t2\tCAG\t2,8\n"[..]).is_err());
+        assert!(from_stream(&b"chr1\t10\t22\tCAG\n"[..]).is_err());
+    }
+
+    #[test]
+    fn somatic() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let mut allele = RepeatAllele {
+            locus: "chr1:10-22".to_string(),
+            start: 10,
+            motif: b"CAG".to_vec(),
+            copies: 20,
+            mosaicism: 0.0,
+        };
+
+        assert_eq!(allele.end(), 70);
+        assert_eq!(allele.description(20), "chr1:10-22,CAG,20,60");
+        assert_eq!(allele.somatic_copies(&mut rng), 20);
+
+        allele.mosaicism = 2.0;
+        let copies: Vec<usize> = (0..1000).map(|_| allele.somatic_copies(&mut rng)).collect();
+        let mean = copies.iter().sum::<usize>() as f64 / copies.len() as f64;
+
+        assert!((mean - 20.0).abs() < 0.5);
+        assert!(copies.iter().any(|x| *x != 20));
+    }
+
+    #[test]
+    fn stutter() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let mut allele = RepeatAllele {
+            locus: "chr1:10-22".to_string(),
+            start: 10,
+            motif: b"CAG".to_vec(),
+            copies: 40,
+            mosaicism: 0.0,
+        };
+
+        assert_eq!(allele.stutter(0, &mut rng), 0);
+
+        let copies: Vec<usize> = (0..10_000).map(|_| allele.stutter(40, &mut rng)).collect();
+        assert!(copies.iter().all(|x| (39..=41).contains(x)));
+
+        let stutter = copies.iter().filter(|x| **x != 40).count();
+        assert!((1_800..2_200).contains(&stutter));
+        let contraction = copies.iter().filter(|x| **x == 39).count();
+        assert!(contraction > stutter / 2);
+
+        allele.motif = b"CAGCAGCAG".to_vec();
+        assert!((0..1_000).all(|_| allele.stutter(40, &mut rng) == 40));
+    }
+}
@@ -0,0 +1,552 @@
+//! Tumour build from subclones carrying somatic variants and copy number changes
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::{Context, Result};
+
+/* local use */
+use crate::error::Variant as Error;
+use crate::references::References;
+use crate::variant::{sv, vcf, Edits, StructuralVariant, Variant};
+
+/// Number of copy of a reference region on each haplotype, one copy is the reference state and zero copy a deletion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyNumber {
+    pub ref_id: String,
+    pub start: usize,
+    pub end: usize,
+    pub copies: Vec<usize>,
+}
+
+impl CopyNumber {
+    /// Number of copy on a haplotype, if only one number of copy is set it's used for all haplotypes
+    pub fn copies(&self, haplotype: usize) -> Option<usize> {
+        if self.copies.len() == 1 {
+            Some(self.copies[0])
+        } else {
+            self.copies.get(haplotype).copied()
+        }
+    }
+
+    /// Return true if position is in region
+    pub fn contains(&self, ref_id: &str, pos: usize) -> bool {
+        self.ref_id == ref_id && self.start <= pos && pos < self.end
+    }
+}
+
+/// Read copy number changes in bed format
+///
+/// Columns are reference, start, end and number of copy on each haplotype separate by ','
+pub fn copy_numbers_from_stream<R>(input: R) -> Result<Vec<CopyNumber>>
+where
+    R: std::io::Read,
+{
+    let mut copy_numbers = Vec::new();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(false)
+        .flexible(true)
+        .quoting(false)
+        .comment(Some(b'#'))
+        .from_reader(input);
+
+    for record in reader.records() {
+        let record = record?;
+        if record.len() < 4 {
+            anyhow::bail!(Error::CopyNumberParsing);
+        }
+
+        let start = usize::from_str(&record[1]).with_context(|| Error::CopyNumberParsing)?;
+        let end = usize::from_str(&record[2]).with_context(|| Error::CopyNumberParsing)?;
+        if end <= start {
+            anyhow::bail!(Error::CopyNumberParsing);
+        }
+
+        copy_numbers.push(CopyNumber {
+            ref_id: record[0].to_string(),
+            start,
+            end,
+            copies: record[3]
+                .split(|c| c == ',' || c == '|')
+                .map(usize::from_str)
+                .collect::<Result<Vec<usize>, std::num::ParseIntError>>()
+                .with_context(|| Error::CopyNumberParsing)?,
+        });
+    }
+
+    Ok(copy_numbers)
+}
+
+/// A subclone, fraction is the fraction of tumour cells in this subclone and not in one of his descendants
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subclone {
+    pub name: String,
+    pub parent: Option<usize>,
+    pub fraction: f64,
+    pub variants: Vec<Variant>,
+    pub svs: Vec<StructuralVariant>,
+    pub copy_numbers: Vec<CopyNumber>,
+}
+
+/// A tumour, purity is the fraction of tumour cells in sample
+///
+/// Tumour cells not assign to a subclone carry only germline variants
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tumour {
+    pub purity: f64,
+    pub subclones: Vec<Subclone>,
+}
+
+impl Tumour {
+    /// Read subclones from a tsv, columns are name, parent, fraction, small variant vcf, structural variant vcf and copy number bed
+    ///
+    /// Parent must be define before his children, missing parent and files are set with '.'
+    pub fn from_stream<R, RNG>(input: R, purity: f64, rng: &mut RNG) -> Result<Self>
+    where
+        R: std::io::Read,
+        RNG: rand::Rng,
+    {
+        let mut subclones: Vec<Subclone> = Vec::new();
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .quoting(false)
+            .comment(Some(b'#'))
+            .from_reader(input);
+
+        for record in reader.records() {
+            let record = record?;
+            if record.len() < 3 || record[0].is_empty() {
+                anyhow::bail!(Error::SubcloneParsing);
+            }
+
+            let parent = match &record[1] {
+                "." | "" => None,
+                name => Some(
+                    subclones
+                        .iter()
+                        .position(|x| x.name == name)
+                        .ok_or_else(|| Error::UnknownParent(name.to_string()))?,
+                ),
+            };
+
+            let path = |index: usize| match record.get(index) {
+                Some(".") | Some("") | None => None,
+                Some(path) => Some(path.to_string()),
+            };
+
+            subclones.push(Subclone {
+                name: record[0].to_string(),
+                parent,
+                fraction: f64::from_str(&record[2]).with_context(|| Error::SubcloneParsing)?,
+                variants: if let Some(path) = path(3) {
+                    vcf::from_stream(open(&path)?)
+                        .with_context(|| format!("Parse subclone vcf {}", path))?
+                } else {
+                    Vec::new()
+                },
+                svs: if let Some(path) = path(4) {
+                    vcf::sv_from_stream(open(&path)?, rng).with_context(|| {
+                        format!("Parse subclone structural variant vcf {}", path)
+                    })?
+                } else {
+                    Vec::new()
+                },
+                copy_numbers: if let Some(path) = path(5) {
+                    copy_numbers_from_stream(open(&path)?)
+                        .with_context(|| format!("Parse subclone copy number bed {}", path))?
+                } else {
+                    Vec::new()
+                },
+            });
+        }
+
+        if !(0.0..=1.0).contains(&purity)
+            || subclones.iter().any(|x| x.fraction < 0.0)
+            || subclones.iter().map(|x| x.fraction).sum::<f64>() > 1.0 + f64::EPSILON
+        {
+            anyhow::bail!(Error::CellularFractionTooHigh);
+        }
+
+        Ok(Self { purity, subclones })
+    }
+
+    /// Index of subclone ancestors and subclone, from root to subclone
+    pub fn lineage(&self, index: usize) -> Vec<usize> {
+        let mut lineage = vec![index];
+
+        while let Some(parent) = self.subclones[*lineage.last().unwrap()].parent {
+            lineage.push(parent);
+        }
+
+        lineage.reverse();
+        lineage
+    }
+
+    /// Fraction of sample cells without somatic event
+    pub fn normal_fraction(&self) -> f64 {
+        1.0 - self.purity * self.subclones.iter().map(|x| x.fraction).sum::<f64>()
+    }
+
+    /// Cancer cell fraction of a subclone, fraction of tumour cells carrying events of this subclone
+    pub fn ccf(&self, index: usize) -> f64 {
+        (0..self.subclones.len())
+            .filter(|x| self.lineage(*x).contains(&index))
+            .map(|x| self.subclones[x].fraction)
+            .sum()
+    }
+
+    /// Get name, fraction of cells and edits of each cell population, first population is normal cells
+    ///
+    /// Edits of a subclone are germline edits, small and structural variants of its lineage and, apply last, copy number changes of its lineage
+    pub fn populations(
+        &self,
+        germline: &Edits,
+        references: &References,
+    ) -> Vec<(String, f64, Edits)> {
+        let mut populations = vec![(
+            "normal".to_string(),
+            self.normal_fraction(),
+            germline.clone(),
+        )];
+
+        for (index, subclone) in self.subclones.iter().enumerate() {
+            if subclone.fraction <= 0.0 {
+                continue;
+            }
+
+            let lineage = self.lineage(index);
+            let mut edits = germline.clone();
+
+            for ancestor in lineage.iter() {
+                edits.add_variants(&self.subclones[*ancestor].variants);
+                edits.add_structural(&self.subclones[*ancestor].svs, references);
+            }
+            for ancestor in lineage.iter() {
+                edits.add_copy_numbers(&self.subclones[*ancestor].copy_numbers, references);
+            }
+
+            populations.push((
+                subclone.name.clone(),
+                self.purity * subclone.fraction,
+                edits,
+            ));
+        }
+
+        populations
+    }
+
+    /// Number of copy of a position on each haplotype of a subclone
+    fn copies(&self, index: usize, ploidy: usize, ref_id: &str, pos: usize) -> Vec<usize> {
+        let mut copies = vec![1; ploidy];
+
+        for ancestor in self.lineage(index) {
+            for cn in self.subclones[ancestor]
+                .copy_numbers
+                .iter()
+                .filter(|x| x.contains(ref_id, pos))
+            {
+                for (haplotype, copy) in copies.iter_mut().enumerate() {
+                    *copy *= cn.copies(haplotype).unwrap_or(1);
+                }
+            }
+        }
+
+        copies
+    }
+
+    /// Expected variant allele frequency of a variant of a subclone, copy number changes of each population are take in account
+    pub fn vaf(
+        &self,
+        index: usize,
+        genotype: &[usize],
+        ploidy: usize,
+        ref_id: &str,
+        pos: usize,
+    ) -> f64 {
+        let mut alt = 0.0;
+        let mut total = self.normal_fraction() * ploidy as f64;
+
+        for (subclone, fraction) in self.subclones.iter().map(|x| x.fraction).enumerate() {
+            let weight = self.purity * fraction;
+            let copies = self.copies(subclone, ploidy, ref_id, pos);
+
+            total += weight * copies.iter().sum::<usize>() as f64;
+            if self.lineage(subclone).contains(&index) {
+                alt += weight
+                    * copies
+                        .iter()
+                        .enumerate()
+                        .filter(|(haplotype, _)| genotype.get(*haplotype).unwrap_or(&0) != &0)
+                        .map(|(_, copy)| *copy)
+                        .sum::<usize>() as f64;
+            }
+        }
+
+        if total > 0.0 {
+            alt / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Write truth vcf of somatic events, each record have subclone name, cancer cell fraction and expected variant allele frequency
+    pub fn write_truth<W>(
+        &self,
+        output: &mut W,
+        references: &References,
+        ploidy: usize,
+    ) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        vcf::write_header(
+            output,
+            references,
+            &[
+                "##INFO=<ID=CLONE,Number=1,Type=String,Description=\"Subclone where event appear\">",
+                "##INFO=<ID=CCF,Number=1,Type=Float,Description=\"Cancer cell fraction\">",
+                "##INFO=<ID=VAF,Number=1,Type=Float,Description=\"Expected variant allele frequency\">",
+                "##INFO=<ID=CN,Number=.,Type=Integer,Description=\"Number of copy on each haplotype\">",
+                "##INFO=<ID=SVTYPE,Number=1,Type=String,Description=\"Type of structural variant\">",
+                "##INFO=<ID=SVLEN,Number=1,Type=Integer,Description=\"Length of structural variant\">",
+                "##INFO=<ID=END,Number=1,Type=Integer,Description=\"End position of structural variant\">",
+                "##INFO=<ID=CHR2,Number=1,Type=String,Description=\"Source chromosome of translocation\">",
+                "##INFO=<ID=POS2,Number=1,Type=Integer,Description=\"Source position of translocation\">",
+                "##ALT=<ID=DEL,Description=\"Deletion\">",
+                "##ALT=<ID=INV,Description=\"Inversion\">",
+                "##ALT=<ID=DUP,Description=\"Duplication\">",
+                "##ALT=<ID=TRA,Description=\"Translocation\">",
+                "##ALT=<ID=CNV,Description=\"Copy number variation\">",
+            ],
+        )?;
+
+        let mut records: Vec<(String, usize, String)> = Vec::new();
+        for (index, subclone) in self.subclones.iter().enumerate() {
+            let clone = format!("CLONE={};CCF={:.4}", subclone.name, self.ccf(index));
+
+            for variant in subclone.variants.iter() {
+                records.push((
+                    variant.ref_id.clone(),
+                    variant.pos + 1,
+                    format!(
+                        "{}\t{}\t.\tPASS\t{};VAF={:.4}\tGT\t{}",
+                        String::from_utf8_lossy(&variant.reference),
+                        variant
+                            .alternatives
+                            .iter()
+                            .map(|x| String::from_utf8_lossy(x).to_string())
+                            .collect::<Vec<String>>()
+                            .join(","),
+                        clone,
+                        self.vaf(
+                            index,
+                            &variant.genotype,
+                            ploidy,
+                            &variant.ref_id,
+                            variant.pos
+                        ),
+                        sv::genotype2str(&variant.genotype)
+                    ),
+                ));
+            }
+
+            for structural in subclone.svs.iter() {
+                let (ref_base, alt, info) = structural.vcf_fields(references);
+
+                records.push((
+                    structural.ref_id.clone(),
+                    structural.pos,
+                    format!(
+                        "{}\t{}\t.\tPASS\t{};{};VAF={:.4}\tGT\t{}",
+                        ref_base,
+                        alt,
+                        info,
+                        clone,
+                        self.vaf(
+                            index,
+                            &structural.genotype,
+                            ploidy,
+                            &structural.ref_id,
+                            structural.pos
+                        ),
+                        sv::genotype2str(&structural.genotype)
+                    ),
+                ));
+            }
+
+            for cn in subclone.copy_numbers.iter() {
+                let ref_base = references
+                    .sequences
+                    .iter()
+                    .find(|x| x.id == cn.ref_id)
+                    .and_then(|x| x.seq.get(cn.start.max(1) - 1))
+                    .copied()
+                    .unwrap_or(b'N') as char;
+                let copies: Vec<usize> = (0..ploidy).map(|x| cn.copies(x).unwrap_or(1)).collect();
+
+                records.push((
+                    cn.ref_id.clone(),
+                    cn.start,
+                    format!(
+                        "{}\t<CNV>\t.\tPASS\tSVTYPE=CNV;END={};CN={};{}\tGT\t{}",
+                        ref_base,
+                        cn.end,
+                        copies
+                            .iter()
+                            .map(|x| x.to_string())
+                            .collect::<Vec<String>>()
+                            .join(","),
+                        clone,
+                        sv::genotype2str(
+                            &copies
+                                .iter()
+                                .map(|x| (*x != 1) as usize)
+                                .collect::<Vec<usize>>()
+                        )
+                    ),
+                ));
+            }
+        }
+
+        records.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+
+        for (i, (ref_id, pos, fields)) in records.iter().enumerate() {
+            writeln!(
+                output,
+                "{}\t{}\trustyread_somatic_{}\t{}",
+                ref_id, pos, i, fields
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn open(path: &str) -> Result<Box<dyn std::io::Read>> {
+    Ok(niffler::get_reader(Box::new(std::io::BufReader::new(
+        std::fs::File::open(path).with_context(|| format!("Read subclone file {}", path))?,
+    )))
+    .with_context(|| "Read subclone file niffler")?
+    .0)
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    static FASTA: &[u8] = b">chr1
+ACTGACTGACTGACTGACTGACTGACTGACTGACTGACTG
+";
+
+    fn tumour() -> Tumour {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let vcf = crate::write_temp_file(
+            "somatic_a.vcf",
+            b"##fileformat=VCFv4.2
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample
+chr1\t3\t.\tT\tA\t.\tPASS\t.\tGT\t0|1
+",
+        );
+        let bed = crate::write_temp_file("somatic_b.bed", b"chr1\t0\t8\t1,3\n");
+
+        let tsv = format!(
+            "#name\tparent\tfraction\tvcf\tsv\tcn\nA\t.\t0.5\t{}\t.\t.\nB\tA\t0.5\t.\t.\t{}\n",
+            vcf, bed
+        );
+
+        Tumour::from_stream(tsv.as_bytes(), 0.8, &mut rng).unwrap()
+    }
+
+    #[test]
+    fn copy_number() {
+        let copy_numbers =
+            copy_numbers_from_stream(&b"chr1\t10\t20\t2\nchr2\t0\t5\t0,1\n"[..]).unwrap();
+
+        assert_eq!(
+            copy_numbers[1],
+            CopyNumber {
+                ref_id: "chr2".to_string(),
+                start: 0,
+                end: 5,
+                copies: vec![0, 1],
+            }
+        );
+        assert_eq!(copy_numbers[0].copies(1), Some(2));
+        assert_eq!(copy_numbers[1].copies(2), None);
+        assert!(copy_numbers[0].contains("chr1", 10));
+        assert!(!copy_numbers[0].contains("chr1", 20));
+
+        assert!(copy_numbers_from_stream(&b"chr1\t10\t20\n"[..]).is_err());
+        assert!(copy_numbers_from_stream(&b"chr1\t10\t5\t2\n"[..]).is_err());
+        assert!(copy_numbers_from_stream(&b"chr1\t10\t20\tauie\n"[..]).is_err());
+    }
+
+    #[test]
+    fn subclones() {
+        let tumour = tumour();
+
+        assert_eq!(tumour.subclones[1].parent, Some(0));
+        assert_eq!(tumour.lineage(1), vec![0, 1]);
+        assert_eq!(tumour.ccf(0), 1.0);
+        assert_eq!(tumour.ccf(1), 0.5);
+        assert!((tumour.normal_fraction() - 0.2).abs() < 1e-12);
+
+        // normal: 0.2 * 2 copies, A: 0.4 * 2 copies, B: 0.4 * 4 copies with 3 alt copies
+        assert!((tumour.vaf(0, &[0, 1], 2, "chr1", 2) - 1.6 / 2.8).abs() < 1e-12);
+        assert!((tumour.vaf(0, &[0, 1], 2, "chr1", 10) - 0.8 / 2.0).abs() < 1e-12);
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        assert!(Tumour::from_stream(&b"A\tB\t0.5\n"[..], 1.0, &mut rng).is_err());
+        assert!(Tumour::from_stream(&b"A\t.\tauie\n"[..], 1.0, &mut rng).is_err());
+        assert!(Tumour::from_stream(&b"A\t.\t0.6\nB\tA\t0.6\n"[..], 1.0, &mut rng).is_err());
+    }
+
+    #[test]
+    fn populations() {
+        let tumour = tumour();
+        let references = References::from_stream(FASTA).unwrap();
+
+        let populations = tumour.populations(&Edits::new(2), &references);
+        assert_eq!(
+            populations
+                .iter()
+                .map(|x| x.0.clone())
+                .collect::<Vec<String>>(),
+            vec!["normal", "A", "B"]
+        );
+
+        let clones = references.with_clones(&populations).unwrap();
+        assert_eq!(clones.sequences.len(), 6);
+        assert_eq!(clones.sequences[3].tags, vec![("clone", "A".to_string())]);
+        assert_eq!(&clones.sequences[3].seq[..8], b"ACAGACTG");
+        assert_eq!(&clones.sequences[4].seq[..8], b"ACTGACTG");
+        assert_eq!(
+            &clones.sequences[5].seq[..26],
+            b"ACAGACTGACAGACTGACAGACTGAC"
+        );
+        assert_eq!(clones.genome_length, references.genome_length);
+    }
+
+    #[test]
+    fn truth() {
+        let tumour = tumour();
+        let references = References::from_stream(FASTA).unwrap();
+
+        let mut output = Vec::new();
+        tumour.write_truth(&mut output, &references, 2).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("chr1\t0\trustyread_somatic_0\tA\t<CNV>\t.\tPASS\tSVTYPE=CNV;END=8;CN=1,3;CLONE=B;CCF=0.5000\tGT\t0|1\n"));
+        assert!(output.contains(
+            "chr1\t3\trustyread_somatic_1\tT\tA\t.\tPASS\tCLONE=A;CCF=1.0000;VAF=0.5714\tGT\t0|1\n"
+        ));
+    }
+}
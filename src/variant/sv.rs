@@ -0,0 +1,523 @@
+//! Generate and apply structural variant
+
+/* standard use */
+
+/* crate use */
+use anyhow::Result;
+use rand::distributions::Distribution;
+
+/* local use */
+use crate::references::References;
+use crate::variant::Edit;
+
+/* constant definition */
+const SV_MIN_LENGTH: f64 = 50.0;
+const SV_MAX_LENGTH: f64 = 10_000.0;
+const SV_HOMOZYGOUS_CHANCE: f64 = 0.33;
+
+/// An enum to represent type of structural variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SvType {
+    Deletion,
+    Insertion,
+    Inversion,
+    Duplication,
+    Translocation,
+}
+
+impl std::fmt::Display for SvType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SvType::Deletion => write!(f, "DEL"),
+            SvType::Insertion => write!(f, "INS"),
+            SvType::Inversion => write!(f, "INV"),
+            SvType::Duplication => write!(f, "DUP"),
+            SvType::Translocation => write!(f, "TRA"),
+        }
+    }
+}
+
+impl std::str::FromStr for SvType {
+    type Err = crate::error::Variant;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "DEL" => Ok(SvType::Deletion),
+            "INS" => Ok(SvType::Insertion),
+            "INV" => Ok(SvType::Inversion),
+            "DUP" | "DUP:TANDEM" => Ok(SvType::Duplication),
+            "TRA" => Ok(SvType::Translocation),
+            _ => Err(crate::error::Variant::UnsupportedSvType(s.to_string())),
+        }
+    }
+}
+
+/// Store a structural variant and his genotype
+///
+/// Structural variant affect `length` base after `pos`, an insertion is made before `pos` with `seq`,
+/// a translocation move `length` base after partner position before `pos`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructuralVariant {
+    pub ref_id: String,
+    pub pos: usize,
+    pub length: usize,
+    pub kind: SvType,
+    pub seq: Vec<u8>,
+    pub partner: Option<(String, usize)>,
+    pub genotype: Vec<usize>,
+}
+
+impl StructuralVariant {
+    /// Get edits apply by this variant on a haplotype carrying it, each edit is associate to a reference id
+    pub fn edits(&self, references: &References) -> Vec<(String, Edit)> {
+        let reference = match references.sequences.iter().find(|x| x.id == self.ref_id) {
+            Some(r) => r,
+            None => {
+                log::warn!("Reference {} of structural variant not found", self.ref_id);
+                return Vec::new();
+            }
+        };
+
+        if self.pos + self.length > reference.seq.len() {
+            log::warn!(
+                "Structural variant {}:{} is after end of reference, it's ignored",
+                self.ref_id,
+                self.pos
+            );
+            return Vec::new();
+        }
+
+        let region = &reference.seq[self.pos..self.pos + self.length];
+        match self.kind {
+            SvType::Deletion => vec![(
+                self.ref_id.clone(),
+                Edit::new(self.pos, self.length, Vec::new()),
+            )],
+            SvType::Insertion => vec![(
+                self.ref_id.clone(),
+                Edit::new(self.pos, 0, self.seq.clone()),
+            )],
+            SvType::Inversion => vec![(
+                self.ref_id.clone(),
+                Edit::inversion(self.pos, bio::alphabets::dna::revcomp(region)),
+            )],
+            SvType::Duplication => vec![(
+                self.ref_id.clone(),
+                Edit::new(self.pos, self.length, region.repeat(2)),
+            )],
+            SvType::Translocation => {
+                let (partner_id, partner_pos) = match &self.partner {
+                    Some(p) => p,
+                    None => return Vec::new(),
+                };
+
+                match references.sequences.iter().find(|x| &x.id == partner_id) {
+                    Some(partner) if partner_pos + self.length <= partner.seq.len() => vec![
+                        (
+                            self.ref_id.clone(),
+                            Edit::new(
+                                self.pos,
+                                0,
+                                partner.seq[*partner_pos..partner_pos + self.length].to_vec(),
+                            ),
+                        ),
+                        (
+                            partner_id.clone(),
+                            Edit::new(*partner_pos, self.length, Vec::new()),
+                        ),
+                    ],
+                    _ => {
+                        log::warn!(
+                            "Partner of translocation {}:{} isn't valid, it's ignored",
+                            self.ref_id,
+                            self.pos
+                        );
+                        Vec::new()
+                    }
+                }
+            }
+        }
+    }
+
+    /// End of region affected by structural variant on reference
+    pub fn end(&self) -> usize {
+        match self.kind {
+            SvType::Insertion | SvType::Translocation => self.pos,
+            _ => self.pos + self.length,
+        }
+    }
+
+    /// Get reference base, alternative allele and info field of structural variant vcf record
+    pub fn vcf_fields(&self, references: &References) -> (char, String, String) {
+        let ref_base = references
+            .sequences
+            .iter()
+            .find(|x| x.id == self.ref_id)
+            .and_then(|x| x.seq.get(self.pos.max(1) - 1))
+            .copied()
+            .unwrap_or(b'N') as char;
+
+        let alt = if self.kind == SvType::Insertion {
+            format!(
+                "{}{}",
+                ref_base,
+                std::str::from_utf8(&self.seq).unwrap_or_default()
+            )
+        } else {
+            format!("<{}>", self.kind)
+        };
+
+        let svlen = if self.kind == SvType::Deletion {
+            -(self.length as i64)
+        } else {
+            self.length as i64
+        };
+
+        let mut info = format!("SVTYPE={};SVLEN={};END={}", self.kind, svlen, self.end());
+        if let Some((partner_id, partner_pos)) = &self.partner {
+            info.push_str(&format!(";CHR2={};POS2={}", partner_id, partner_pos + 1));
+        }
+
+        (ref_base, alt, info)
+    }
+}
+
+/// Generate random structural variant, positions are choose to avoid overlap between variants
+pub fn random<RNG>(
+    number: usize,
+    references: &References,
+    ploidy: usize,
+    rng: &mut RNG,
+) -> Result<Vec<StructuralVariant>>
+where
+    RNG: rand::Rng,
+{
+    let mut svs: Vec<StructuralVariant> = Vec::with_capacity(number);
+
+    let ref_dist = rand::distributions::WeightedIndex::new(
+        references
+            .sequences
+            .iter()
+            .map(|x| x.seq.len() as f64)
+            .collect::<Vec<f64>>(),
+    )?;
+    let kinds = if references.sequences.len() > 1 {
+        &[
+            SvType::Deletion,
+            SvType::Insertion,
+            SvType::Inversion,
+            SvType::Duplication,
+            SvType::Translocation,
+        ][..]
+    } else {
+        &[
+            SvType::Deletion,
+            SvType::Insertion,
+            SvType::Inversion,
+            SvType::Duplication,
+        ][..]
+    };
+
+    let mut occupied: Vec<(usize, usize, usize)> = Vec::new();
+    let mut attempt = 0;
+    while svs.len() < number && attempt < number * 100 {
+        attempt += 1;
+
+        let kind = kinds[rng.gen_range(0..kinds.len())];
+        let length = rng
+            .gen_range(SV_MIN_LENGTH.ln()..SV_MAX_LENGTH.ln())
+            .exp()
+            .round() as usize;
+
+        let ref_index = ref_dist.sample(rng);
+        let ref_len = references.sequences[ref_index].seq.len();
+        if ref_len <= length + 2 {
+            continue;
+        }
+        let pos = rng.gen_range(1..(ref_len - length));
+
+        let partner = if kind == SvType::Translocation {
+            let partner_index = ref_dist.sample(rng);
+            let partner_len = references.sequences[partner_index].seq.len();
+            if partner_index == ref_index || partner_len <= length + 2 {
+                continue;
+            }
+            let partner_pos = rng.gen_range(1..(partner_len - length));
+
+            if overlap(&occupied, partner_index, partner_pos, partner_pos + length) {
+                continue;
+            }
+
+            Some((partner_index, partner_pos))
+        } else {
+            None
+        };
+
+        if overlap(&occupied, ref_index, pos, pos + length) {
+            continue;
+        }
+
+        occupied.push((ref_index, pos, pos + length));
+        if let Some((partner_index, partner_pos)) = partner {
+            occupied.push((partner_index, partner_pos, partner_pos + length));
+        }
+
+        let genotype = if ploidy == 1 || rng.gen_bool(SV_HOMOZYGOUS_CHANCE) {
+            vec![1; ploidy]
+        } else {
+            let mut g = vec![0; ploidy];
+            g[rng.gen_range(0..ploidy)] = 1;
+            g
+        };
+
+        svs.push(StructuralVariant {
+            ref_id: references.sequences[ref_index].id.clone(),
+            pos,
+            length,
+            kind,
+            seq: if kind == SvType::Insertion {
+                crate::random_seq(length, rng)
+            } else {
+                Vec::new()
+            },
+            partner: partner.map(|(i, p)| (references.sequences[i].id.clone(), p)),
+            genotype,
+        });
+    }
+
+    if svs.len() < number {
+        log::warn!(
+            "Only {} structural variants are generate, references are probably too small",
+            svs.len()
+        );
+    }
+
+    svs.sort_by(|a, b| (&a.ref_id, a.pos).cmp(&(&b.ref_id, b.pos)));
+
+    Ok(svs)
+}
+
+fn overlap(occupied: &[(usize, usize, usize)], index: usize, start: usize, end: usize) -> bool {
+    occupied
+        .iter()
+        .any(|(i, s, e)| *i == index && start <= *e && *s <= end)
+}
+
+/// Write structural variants in bed format
+pub fn write_bed<W>(output: &mut W, svs: &[StructuralVariant]) -> Result<()>
+where
+    W: std::io::Write,
+{
+    for sv in svs {
+        let name = if let Some((partner_id, partner_pos)) = &sv.partner {
+            format!("{}:{}:{}", sv.kind, partner_id, partner_pos)
+        } else {
+            sv.kind.to_string()
+        };
+
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}\t{}",
+            sv.ref_id,
+            sv.pos,
+            sv.end().max(sv.pos + 1),
+            name,
+            genotype2str(&sv.genotype)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write structural variants in vcf format
+pub fn write_vcf<W>(
+    output: &mut W,
+    references: &References,
+    svs: &[StructuralVariant],
+) -> Result<()>
+where
+    W: std::io::Write,
+{
+    crate::variant::vcf::write_header(
+        output,
+        references,
+        &[
+            "##INFO=<ID=SVTYPE,Number=1,Type=String,Description=\"Type of structural variant\">",
+            "##INFO=<ID=SVLEN,Number=1,Type=Integer,Description=\"Length of structural variant\">",
+            "##INFO=<ID=END,Number=1,Type=Integer,Description=\"End position of structural variant\">",
+            "##INFO=<ID=CHR2,Number=1,Type=String,Description=\"Source chromosome of translocation\">",
+            "##INFO=<ID=POS2,Number=1,Type=Integer,Description=\"Source position of translocation\">",
+            "##ALT=<ID=DEL,Description=\"Deletion\">",
+            "##ALT=<ID=INV,Description=\"Inversion\">",
+            "##ALT=<ID=DUP,Description=\"Duplication\">",
+            "##ALT=<ID=TRA,Description=\"Translocation\">",
+        ],
+    )?;
+
+    for (i, sv) in svs.iter().enumerate() {
+        let (ref_base, alt, info) = sv.vcf_fields(references);
+
+        writeln!(
+            output,
+            "{}\t{}\trustyread_sv_{}\t{}\t{}\t.\tPASS\t{}\tGT\t{}",
+            sv.ref_id,
+            sv.pos,
+            i,
+            ref_base,
+            alt,
+            info,
+            genotype2str(&sv.genotype)
+        )?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn genotype2str(genotype: &[usize]) -> String {
+    genotype
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("|")
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    static FASTA: &[u8] = b">random_seq_0
+TCCTAACGTGTCACGATTACCCTATCCGATTGCAAGATCATAGCCGTGGTCGCTTTGTGACACATGGGCGATCTAATGCGCGGAACTCAGTCCCGCTGTC
+>random_seq_1
+CGGAACTCAGTCCCGCTGTC
+";
+
+    fn sv(kind: SvType, pos: usize, length: usize) -> StructuralVariant {
+        StructuralVariant {
+            ref_id: "random_seq_0".to_string(),
+            pos,
+            length,
+            kind,
+            seq: if kind == SvType::Insertion {
+                b"GGGG".to_vec()
+            } else {
+                Vec::new()
+            },
+            partner: if kind == SvType::Translocation {
+                Some(("random_seq_1".to_string(), 2))
+            } else {
+                None
+            },
+            genotype: vec![0, 1],
+        }
+    }
+
+    #[test]
+    fn edits() {
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+
+        assert_eq!(
+            sv(SvType::Deletion, 10, 5).edits(&refs),
+            vec![("random_seq_0".to_string(), Edit::new(10, 5, b"".to_vec()))]
+        );
+        assert_eq!(
+            sv(SvType::Insertion, 10, 4).edits(&refs),
+            vec![(
+                "random_seq_0".to_string(),
+                Edit::new(10, 0, b"GGGG".to_vec())
+            )]
+        );
+        assert_eq!(
+            sv(SvType::Inversion, 10, 5).edits(&refs),
+            vec![(
+                "random_seq_0".to_string(),
+                Edit::inversion(10, b"CGTGA".to_vec())
+            )]
+        );
+        assert_eq!(
+            sv(SvType::Duplication, 10, 5).edits(&refs),
+            vec![(
+                "random_seq_0".to_string(),
+                Edit::new(10, 5, b"TCACGTCACG".to_vec())
+            )]
+        );
+        assert_eq!(
+            sv(SvType::Translocation, 10, 5).edits(&refs),
+            vec![
+                (
+                    "random_seq_0".to_string(),
+                    Edit::new(10, 0, b"GAACT".to_vec())
+                ),
+                ("random_seq_1".to_string(), Edit::new(2, 5, b"".to_vec()))
+            ]
+        );
+
+        assert_eq!(sv(SvType::Deletion, 98, 5).edits(&refs), vec![]);
+    }
+
+    #[test]
+    fn random_sv() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let mut fasta = Vec::new();
+        for i in 0..3 {
+            fasta.extend(format!(">random_seq_{}\n", i).as_bytes());
+            fasta.extend(crate::random_seq(100_000, &mut rng));
+            fasta.push(b'\n');
+        }
+        let refs = References::from_stream(std::io::Cursor::new(fasta)).unwrap();
+
+        let svs = random(50, &refs, 2, &mut rng).unwrap();
+
+        assert_eq!(svs.len(), 50);
+        for sv in svs.iter() {
+            assert!(sv.length >= SV_MIN_LENGTH as usize && sv.length <= SV_MAX_LENGTH as usize);
+            assert!(sv.pos + sv.length < 100_000);
+            assert_eq!(sv.genotype.iter().filter(|x| **x > 1).count(), 0);
+            assert!(sv.genotype.iter().sum::<usize>() >= 1);
+            assert_eq!(sv.partner.is_some(), sv.kind == SvType::Translocation);
+        }
+
+        for w in svs.windows(2) {
+            if w[0].ref_id == w[1].ref_id {
+                assert!(w[0].pos + w[0].length < w[1].pos);
+            }
+        }
+    }
+
+    #[test]
+    fn write() {
+        let refs = References::from_stream(std::io::Cursor::new(FASTA)).unwrap();
+        let svs = vec![
+            sv(SvType::Deletion, 10, 5),
+            sv(SvType::Insertion, 20, 4),
+            sv(SvType::Translocation, 30, 5),
+        ];
+
+        let mut bed = Vec::new();
+        write_bed(&mut bed, &svs).unwrap();
+        assert_eq!(
+            String::from_utf8(bed).unwrap(),
+            "random_seq_0\t10\t15\tDEL\t0|1
+random_seq_0\t20\t21\tINS\t0|1
+random_seq_0\t30\t31\tTRA:random_seq_1:2\t0|1
+"
+        );
+
+        let mut vcf = Vec::new();
+        write_vcf(&mut vcf, &refs, &svs).unwrap();
+        let vcf = String::from_utf8(vcf).unwrap();
+        let records: Vec<&str> = vcf.lines().filter(|x| !x.starts_with('#')).collect();
+
+        assert!(vcf.starts_with("##fileformat=VCFv4.2\n"));
+        assert!(vcf.contains("##contig=<ID=random_seq_1,length=20>\n"));
+        assert_eq!(
+            records,
+            vec![
+                "random_seq_0\t10\trustyread_sv_0\tG\t<DEL>\t.\tPASS\tSVTYPE=DEL;SVLEN=-5;END=15\tGT\t0|1",
+                "random_seq_0\t20\trustyread_sv_1\tC\tCGGGG\t.\tPASS\tSVTYPE=INS;SVLEN=4;END=20\tGT\t0|1",
+                "random_seq_0\t30\trustyread_sv_2\tT\t<TRA>\t.\tPASS\tSVTYPE=TRA;SVLEN=5;END=30;CHR2=random_seq_1;POS2=3\tGT\t0|1"
+            ]
+        );
+    }
+}
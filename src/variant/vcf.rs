@@ -0,0 +1,404 @@
+//! Read variant from vcf
+
+/* standard use */
+use std::str::FromStr;
+
+/* crate use */
+use anyhow::Result;
+
+/* local use */
+use crate::error::Variant as Error;
+use crate::references::References;
+use crate::variant::{StructuralVariant, SvType, Variant};
+
+/// Read phased small variant from a vcf stream, genotype of first sample is used
+///
+/// Record with a FILTER value different than PASS or '.' and record with symbolic allele are ignored
+pub fn from_stream<R>(input: R) -> Result<Vec<Variant>>
+where
+    R: std::io::Read,
+{
+    let mut variants = Vec::new();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(false)
+        .flexible(true)
+        .quoting(false)
+        .comment(Some(b'#'))
+        .from_reader(input);
+
+    let mut unphased = 0;
+    for record in reader.records() {
+        let record = record?;
+        if record.len() < 10 {
+            anyhow::bail!(Error::VcfParsing);
+        }
+
+        let ref_id = record[0].to_string();
+        let pos = usize::from_str(&record[1])?;
+        if pos == 0 {
+            anyhow::bail!(Error::VcfParsing);
+        }
+
+        if &record[6] != "PASS" && &record[6] != "." {
+            continue;
+        }
+
+        let reference = record[3].as_bytes().to_ascii_uppercase();
+        let alternatives: Vec<Vec<u8>> = record[4]
+            .split(',')
+            .map(|x| x.as_bytes().to_ascii_uppercase())
+            .collect();
+
+        if alternatives.iter().any(|x| !is_sequence(x)) || !is_sequence(&reference) {
+            log::warn!(
+                "Vcf record {}:{} is ignored, allele isn't a sequence",
+                ref_id,
+                pos
+            );
+            continue;
+        }
+
+        let (genotype, phased) = genotype(&record, &ref_id, pos)?;
+        if !phased {
+            unphased += 1;
+        }
+
+        variants.push(Variant {
+            ref_id,
+            pos: pos - 1,
+            reference,
+            alternatives,
+            genotype,
+        });
+    }
+
+    if unphased != 0 {
+        log::warn!(
+            "{} vcf record isn't phased, allele are assign to haplotype in genotype order",
+            unphased
+        );
+    }
+
+    Ok(variants)
+}
+
+/// Read structural variant from a vcf stream, genotype of first sample is used
+///
+/// SVTYPE info field is required, DEL, INV and DUP need END or SVLEN, INS without sequence are fill with random base,
+/// TRA need CHR2, POS2 and SVLEN, region of length SVLEN after POS2 on CHR2 is move after POS
+pub fn sv_from_stream<R, RNG>(input: R, rng: &mut RNG) -> Result<Vec<StructuralVariant>>
+where
+    R: std::io::Read,
+    RNG: rand::Rng,
+{
+    let mut svs = Vec::new();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(false)
+        .flexible(true)
+        .quoting(false)
+        .comment(Some(b'#'))
+        .from_reader(input);
+
+    for record in reader.records() {
+        let record = record?;
+        if record.len() < 10 {
+            anyhow::bail!(Error::VcfParsing);
+        }
+
+        let ref_id = record[0].to_string();
+        let pos = usize::from_str(&record[1])?;
+
+        if &record[6] != "PASS" && &record[6] != "." {
+            continue;
+        }
+
+        let info: rustc_hash::FxHashMap<&str, &str> = record[7]
+            .split(';')
+            .filter_map(|x| {
+                let mut kv = x.splitn(2, '=');
+                Some((kv.next()?, kv.next()?))
+            })
+            .collect();
+        let get_usize = |key: &str| -> Option<usize> {
+            info.get(key)
+                .and_then(|x| i64::from_str(x).ok())
+                .map(|x| x.unsigned_abs() as usize)
+        };
+
+        let kind = match info.get("SVTYPE") {
+            Some(t) => match SvType::from_str(t) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::warn!("Vcf record {}:{} is ignored: {}", ref_id, pos, e);
+                    continue;
+                }
+            },
+            None => {
+                log::warn!(
+                    "Vcf record {}:{} is ignored, SVTYPE is missing",
+                    ref_id,
+                    pos
+                );
+                continue;
+            }
+        };
+
+        let missing = || Error::SvMissingInfo(ref_id.clone(), pos);
+        let alt = record[4].as_bytes().to_ascii_uppercase();
+        let (length, seq, partner) = match kind {
+            SvType::Insertion => {
+                let seq = if is_sequence(&alt) {
+                    alt[1..].to_vec()
+                } else {
+                    crate::random_seq(get_usize("SVLEN").ok_or_else(missing)?, rng)
+                };
+                (seq.len(), seq, None)
+            }
+            SvType::Translocation => (
+                get_usize("SVLEN").ok_or_else(missing)?,
+                Vec::new(),
+                Some((
+                    info.get("CHR2").ok_or_else(missing)?.to_string(),
+                    get_usize("POS2").ok_or_else(missing)?.max(1) - 1,
+                )),
+            ),
+            _ => (
+                get_usize("END")
+                    .map(|end| end.saturating_sub(pos))
+                    .or_else(|| get_usize("SVLEN"))
+                    .ok_or_else(missing)?,
+                Vec::new(),
+                None,
+            ),
+        };
+
+        let (genotype, _) = genotype(&record, &ref_id, pos)?;
+
+        svs.push(StructuralVariant {
+            ref_id,
+            pos,
+            length,
+            kind,
+            seq,
+            partner,
+            genotype,
+        });
+    }
+
+    Ok(svs)
+}
+
+/// Write vcf header, each reference is write as a contig
+pub fn write_header<W>(output: &mut W, references: &References, infos: &[&str]) -> Result<()>
+where
+    W: std::io::Write,
+{
+    writeln!(output, "##fileformat=VCFv4.2")?;
+    writeln!(output, "##source=rustyread")?;
+
+    let mut contigs = rustc_hash::FxHashSet::default();
+    for reference in references.sequences.iter() {
+        if contigs.insert(&reference.id) {
+            writeln!(
+                output,
+                "##contig=<ID={},length={}>",
+                reference.id,
+                reference
+                    .haplotype
+                    .as_ref()
+                    .map(|x| x.ref_length)
+                    .unwrap_or_else(|| reference.seq.len())
+            )?;
+        }
+    }
+
+    for info in infos {
+        writeln!(output, "{}", info)?;
+    }
+
+    writeln!(
+        output,
+        "##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">"
+    )?;
+    writeln!(
+        output,
+        "#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample"
+    )?;
+
+    Ok(())
+}
+
+/// Extract genotype of first sample and if genotype is phased
+fn genotype(record: &csv::StringRecord, ref_id: &str, pos: usize) -> Result<(Vec<usize>, bool)> {
+    let gt_index = record[8]
+        .split(':')
+        .position(|x| x == "GT")
+        .ok_or_else(|| Error::VcfMissingGenotype(ref_id.to_string(), pos))?;
+    let gt = record[9]
+        .split(':')
+        .nth(gt_index)
+        .ok_or_else(|| Error::VcfMissingGenotype(ref_id.to_string(), pos))?;
+
+    let genotype = gt
+        .split(|c| c == '|' || c == '/')
+        .map(|x| if x == "." { Ok(0) } else { usize::from_str(x) })
+        .collect::<Result<Vec<usize>, std::num::ParseIntError>>()?;
+
+    Ok((genotype, !gt.contains('/')))
+}
+
+fn is_sequence(allele: &[u8]) -> bool {
+    !allele.is_empty()
+        && allele
+            .iter()
+            .all(|x| matches!(x, b'A' | b'C' | b'T' | b'G' | b'N'))
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use rand::SeedableRng;
+
+    static VCF: &[u8] = b"##fileformat=VCFv4.2
+##contig=<ID=random_seq_0,length=20>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample
+random_seq_0\t3\t.\tT\tA\t50\tPASS\t.\tGT\t1|0
+random_seq_0\t6\t.\tC\tCGGG,G\t50\tPASS\t.\tGT:DP\t2|1:30
+random_seq_0\t8\t.\tG\tT\t50\tLowQual\t.\tGT\t1|1
+random_seq_0\t11\t.\tACTG\tA\t50\t.\t.\tDP:GT\t20:0/1
+random_seq_0\t15\t.\tA\t<DEL>\t50\tPASS\t.\tGT\t1|1
+random_seq_1\t1\t.\ta\tc\t50\tPASS\t.\tGT\t.|1
+";
+
+    #[test]
+    fn read() {
+        let variants = from_stream(VCF).unwrap();
+
+        assert_eq!(
+            variants,
+            vec![
+                Variant {
+                    ref_id: "random_seq_0".to_string(),
+                    pos: 2,
+                    reference: b"T".to_vec(),
+                    alternatives: vec![b"A".to_vec()],
+                    genotype: vec![1, 0]
+                },
+                Variant {
+                    ref_id: "random_seq_0".to_string(),
+                    pos: 5,
+                    reference: b"C".to_vec(),
+                    alternatives: vec![b"CGGG".to_vec(), b"G".to_vec()],
+                    genotype: vec![2, 1]
+                },
+                Variant {
+                    ref_id: "random_seq_0".to_string(),
+                    pos: 10,
+                    reference: b"ACTG".to_vec(),
+                    alternatives: vec![b"A".to_vec()],
+                    genotype: vec![0, 1]
+                },
+                Variant {
+                    ref_id: "random_seq_1".to_string(),
+                    pos: 0,
+                    reference: b"A".to_vec(),
+                    alternatives: vec![b"C".to_vec()],
+                    genotype: vec![0, 1]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_genotype() {
+        assert!(from_stream(&b"chr1\t3\t.\tT\tA\t50\tPASS\t.\tDP\t30\n"[..]).is_err());
+        assert!(from_stream(&b"chr1\t3\t.\tT\tA\t50\tPASS\t.\n"[..]).is_err());
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(from_stream(&b"chr1\t0\t.\tT\tA\t50\tPASS\t.\tGT\t1|0\n"[..]).is_err());
+        assert!(from_stream(
+            &b"chr1\t3\t.\tT\tA\t50\tPASS\t.\tGT\t1|0\nchr1\t5\t.\t\xff\tA\t50\tPASS\t.\tGT\t1|0\n"
+                [..]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn read_sv() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let vcf = b"##fileformat=VCFv4.2
+random_seq_0\t10\tsv0\tG\t<DEL>\t.\tPASS\tSVTYPE=DEL;SVLEN=-5;END=15\tGT\t0|1
+random_seq_0\t20\tsv1\tC\tCGGGG\t.\tPASS\tSVTYPE=INS;SVLEN=4;END=20\tGT\t1|1
+random_seq_0\t25\tsv2\tC\t<INS>\t.\tPASS\tSVTYPE=INS;SVLEN=3\tGT\t1|0
+random_seq_0\t30\tsv3\tT\t<TRA>\t.\tPASS\tSVTYPE=TRA;SVLEN=5;END=30;CHR2=random_seq_1;POS2=3\tGT\t0|1
+random_seq_0\t40\tsv4\tT\t<INV>\t.\tPASS\tSVTYPE=INV;SVLEN=8\tGT\t0|1
+random_seq_0\t50\tsv5\tT\tN[random_seq_1:10[\t.\tPASS\tSVTYPE=BND\tGT\t0|1
+random_seq_0\t60\tsv6\tT\t<DUP>\t.\tLowQual\tSVTYPE=DUP;SVLEN=10\tGT\t0|1
+";
+
+        let svs = sv_from_stream(&vcf[..], &mut rng).unwrap();
+        assert_eq!(
+            svs,
+            vec![
+                StructuralVariant {
+                    ref_id: "random_seq_0".to_string(),
+                    pos: 10,
+                    length: 5,
+                    kind: SvType::Deletion,
+                    seq: Vec::new(),
+                    partner: None,
+                    genotype: vec![0, 1],
+                },
+                StructuralVariant {
+                    ref_id: "random_seq_0".to_string(),
+                    pos: 20,
+                    length: 4,
+                    kind: SvType::Insertion,
+                    seq: b"GGGG".to_vec(),
+                    partner: None,
+                    genotype: vec![1, 1],
+                },
+                StructuralVariant {
+                    ref_id: "random_seq_0".to_string(),
+                    pos: 25,
+                    length: 3,
+                    kind: SvType::Insertion,
+                    seq: b"TTA".to_vec(),
+                    partner: None,
+                    genotype: vec![1, 0],
+                },
+                StructuralVariant {
+                    ref_id: "random_seq_0".to_string(),
+                    pos: 30,
+                    length: 5,
+                    kind: SvType::Translocation,
+                    seq: Vec::new(),
+                    partner: Some(("random_seq_1".to_string(), 2)),
+                    genotype: vec![0, 1],
+                },
+                StructuralVariant {
+                    ref_id: "random_seq_0".to_string(),
+                    pos: 40,
+                    length: 8,
+                    kind: SvType::Inversion,
+                    seq: Vec::new(),
+                    partner: None,
+                    genotype: vec![0, 1],
+                },
+            ]
+        );
+
+        assert!(sv_from_stream(
+            &b"random_seq_0\t60\tsv6\tT\t<DUP>\t.\tPASS\tSVTYPE=DUP\tGT\t0|1\n"[..],
+            &mut rng
+        )
+        .is_err());
+    }
+}
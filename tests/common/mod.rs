@@ -16,7 +16,7 @@ pub fn init() {
 #[allow(dead_code)]
 pub fn diff_unorder_fasta(first: &str, second: &str) {
     let first_file = Reader::new(std::io::BufReader::new(
-        std::fs::File::open(first).expect(&format!("Impossible to open {}", first)),
+        std::fs::File::open(first).unwrap_or_else(|_| panic!("Impossible to open {}", first)),
     ));
 
     let mut first_id = std::collections::HashSet::new();
@@ -26,14 +26,14 @@ pub fn diff_unorder_fasta(first: &str, second: &str) {
 
     for res in first_file.records() {
         let line = res.unwrap();
-        first_id.insert(format!("{}", line.id()));
-        first_desc.insert(format!("{}", line.desc().unwrap()));
-        first_seq.insert(format!("{}", std::str::from_utf8(line.seq()).unwrap()));
-        first_qual.insert(format!("{}", std::str::from_utf8(line.qual()).unwrap()));
+        first_id.insert(line.id().to_string());
+        first_desc.insert(line.desc().unwrap().to_string());
+        first_seq.insert(std::str::from_utf8(line.seq()).unwrap().to_string());
+        first_qual.insert(std::str::from_utf8(line.qual()).unwrap().to_string());
     }
 
     let second_file = Reader::new(std::io::BufReader::new(
-        std::fs::File::open(second).expect(&format!("Impossible to open {}", second)),
+        std::fs::File::open(second).unwrap_or_else(|_| panic!("Impossible to open {}", second)),
     ));
 
     let mut second_id = std::collections::HashSet::new();
@@ -43,10 +43,10 @@ pub fn diff_unorder_fasta(first: &str, second: &str) {
 
     for res in second_file.records() {
         let line = res.unwrap();
-        second_id.insert(format!("{}", line.id()));
-        second_desc.insert(format!("{}", line.desc().unwrap()));
-        second_seq.insert(format!("{}", std::str::from_utf8(line.seq()).unwrap()));
-        second_qual.insert(format!("{}", std::str::from_utf8(line.qual()).unwrap()));
+        second_id.insert(line.id().to_string());
+        second_desc.insert(line.desc().unwrap().to_string());
+        second_seq.insert(std::str::from_utf8(line.seq()).unwrap().to_string());
+        second_qual.insert(std::str::from_utf8(line.qual()).unwrap().to_string());
     }
 
     assert_eq!(first_id, second_id);
@@ -76,3 +76,23 @@ pub fn run_process(path: &str, params: &[&str]) {
         panic!();
     }
 }
+
+#[allow(dead_code)]
+pub fn fastq_lengths(path: &str) -> Vec<usize> {
+    let file = Reader::new(std::io::BufReader::new(
+        std::fs::File::open(path).unwrap_or_else(|_| panic!("Impossible to open {}", path)),
+    ));
+
+    file.records().map(|x| x.unwrap().seq().len()).collect()
+}
+
+#[allow(dead_code)]
+pub fn fastq_descriptions(path: &str) -> Vec<String> {
+    let file = Reader::new(std::io::BufReader::new(
+        std::fs::File::open(path).unwrap_or_else(|_| panic!("Impossible to open {}", path)),
+    ));
+
+    file.records()
+        .map(|x| x.unwrap().desc().unwrap_or("").to_string())
+        .collect()
+}
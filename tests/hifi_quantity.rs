@@ -0,0 +1,35 @@
+mod common;
+
+#[test]
+fn hifi_quantity() {
+    common::init();
+
+    let output = std::env::temp_dir().join("rustyread_hifi_quantity.fastq");
+
+    common::run_process(
+        "./target/debug/rustyread",
+        &[
+            "--threads",
+            "2",
+            "simulate",
+            "--reference",
+            "./tests/data/ref_100000.fasta",
+            "--quantity",
+            "5x",
+            "--seed",
+            "42",
+            "--pacbio",
+            "hifi",
+            "--qscore_model",
+            "random",
+            "--output",
+            output.to_str().unwrap(),
+        ],
+    );
+
+    let lengths = common::fastq_lengths(output.to_str().unwrap());
+    let bases: usize = lengths.iter().sum();
+
+    assert!(lengths.len() > 10);
+    assert!((450_000..600_000).contains(&bases), "{} bases", bases);
+}
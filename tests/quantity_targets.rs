@@ -0,0 +1,124 @@
+mod common;
+
+/* std use */
+use std::io::{BufRead, Write};
+
+/// Split test reference in three references
+fn references(path: &std::path::Path) {
+    let input =
+        std::io::BufReader::new(std::fs::File::open("./tests/data/ref_100000.fasta").unwrap());
+    let seq: String = input
+        .lines()
+        .map(|x| x.unwrap())
+        .filter(|x| !x.starts_with('>'))
+        .collect();
+
+    let mut output = std::fs::File::create(path).unwrap();
+    for (i, id) in ["ref_a", "ref_b", "ref_c"].iter().enumerate() {
+        writeln!(output, ">{}\n{}", id, &seq[i * 30_000..(i + 1) * 30_000]).unwrap();
+    }
+}
+
+fn count(descriptions: &[String], id: &str) -> usize {
+    descriptions
+        .iter()
+        .filter(|x| x.starts_with(&format!("{},", id)))
+        .count()
+}
+
+#[test]
+fn quantity_per_reference() {
+    common::init();
+
+    let reference = std::env::temp_dir().join("rustyread_quantity_targets.fasta");
+    let output = std::env::temp_dir().join("rustyread_quantity_targets.fastq");
+    let truth = std::env::temp_dir().join("rustyread_quantity_targets");
+    references(&reference);
+
+    common::run_process(
+        "./target/debug/rustyread",
+        &[
+            "--threads",
+            "2",
+            "simulate",
+            "--reference",
+            reference.to_str().unwrap(),
+            "--quantity_per_reference",
+            "ref_a:40reads,ref_b:25reads,ref_c:60K",
+            "--length",
+            "2000,500",
+            "--duplex",
+            "20",
+            "--seed",
+            "42",
+            "--error_model",
+            "random",
+            "--qscore_model",
+            "random",
+            "--truth_prefix",
+            truth.to_str().unwrap(),
+            "--output",
+            output.to_str().unwrap(),
+        ],
+    );
+
+    let descriptions = common::fastq_descriptions(output.to_str().unwrap());
+    assert_eq!(count(&descriptions, "ref_a"), 40);
+    assert_eq!(count(&descriptions, "ref_b"), 25);
+
+    let ref_c: usize = descriptions
+        .iter()
+        .filter(|x| x.starts_with("ref_c,"))
+        .map(|x| {
+            x.split(' ')
+                .find_map(|field| field.strip_prefix("length="))
+                .unwrap()
+                .parse::<usize>()
+                .unwrap()
+        })
+        .sum();
+    assert!((60_000..66_000).contains(&ref_c), "{} bases", ref_c);
+}
+
+#[test]
+fn quantity_reads() {
+    common::init();
+
+    let output = std::env::temp_dir().join("rustyread_quantity_reads.fastq");
+    let duplex_output = std::env::temp_dir().join("rustyread_quantity_reads_duplex.fastq");
+    let truth = std::env::temp_dir().join("rustyread_quantity_reads");
+
+    common::run_process(
+        "./target/debug/rustyread",
+        &[
+            "--threads",
+            "2",
+            "simulate",
+            "--reference",
+            "./tests/data/ref_100000.fasta",
+            "--quantity",
+            "101reads",
+            "--length",
+            "2000,500",
+            "--duplex",
+            "30",
+            "--duplex_output",
+            duplex_output.to_str().unwrap(),
+            "--seed",
+            "42",
+            "--error_model",
+            "random",
+            "--qscore_model",
+            "random",
+            "--truth_prefix",
+            truth.to_str().unwrap(),
+            "--output",
+            output.to_str().unwrap(),
+        ],
+    );
+
+    let reads = common::fastq_lengths(output.to_str().unwrap()).len();
+    let consensus = common::fastq_lengths(duplex_output.to_str().unwrap()).len();
+    assert!(consensus > 0);
+    assert_eq!(reads, 101);
+}